@@ -1,8 +1,8 @@
 use clap::Parser;
 use futures::{self, future::join_all, stream, StreamExt};
 use glide_core::{
-    client::Client,
-    connection_request::{ConnectionRequest, NodeAddress, TlsMode},
+    client::{Client, ClientConfig},
+    connection_request::TlsMode,
 };
 use rand::{thread_rng, Rng};
 use serde_json::Value;
@@ -39,14 +39,14 @@ struct Args {
     cluster_mode_enabled: bool,
 
     #[arg(name = "port", long, default_value_t = PORT)]
-    port: u32,
+    port: u16,
 
     #[arg(long, default_value_t = false)]
     minimal: bool,
 }
 
 // Connection constants - these should be adjusted to fit your connection.
-const PORT: u32 = 6379;
+const PORT: u16 = 6379;
 
 // Benchmark constants - adjusting these will change the meaning of the benchmark.
 const PROB_GET: f64 = 0.8;
@@ -208,21 +208,19 @@ fn generate_random_string(length: usize) -> String {
 }
 
 async fn get_connection(args: &Args) -> Client {
-    let mut connection_request = ConnectionRequest::new();
-    connection_request.tls_mode = if args.tls {
-        TlsMode::SecureTls
-    } else {
-        TlsMode::NoTls
-    }
-    .into();
-    let mut address_info: NodeAddress = NodeAddress::new();
-    address_info.host = args.host.clone().into();
-    address_info.port = args.port;
-    connection_request.addresses.push(address_info);
-    connection_request.request_timeout = 2000;
-    connection_request.cluster_mode_enabled = args.cluster_mode_enabled;
+    let config = ClientConfig::builder()
+        .address(args.host.as_str(), args.port)
+        .tls_mode(if args.tls {
+            TlsMode::SecureTls
+        } else {
+            TlsMode::NoTls
+        })
+        .request_timeout(Duration::from_millis(2000))
+        .cluster_mode(args.cluster_mode_enabled)
+        .build()
+        .unwrap();
 
-    glide_core::client::Client::new(connection_request)
+    glide_core::client::Client::new(config.into(), None)
         .await
         .unwrap()
 }
@@ -1,6 +1,6 @@
+use glide_core::client::{parse_connection_uri, Client as GlideClient, ClientConfig};
 use glide_core::connection_request;
-use glide_core::{client::Client as GlideClient, connection_request::NodeAddress};
-use redis::{Cmd, FromRedisValue, RedisResult};
+use redis::{Cmd, ErrorKind, FromRedisValue, RedisResult};
 use std::{
     ffi::{c_void, CStr, CString},
     os::raw::c_char,
@@ -27,39 +27,36 @@ fn create_connection_request(
     host: String,
     port: u32,
     use_tls: bool,
-) -> connection_request::ConnectionRequest {
-    let mut address_info = NodeAddress::new();
-    address_info.host = host.to_string().into();
-    address_info.port = port;
-    let addresses_info = vec![address_info];
-    let mut connection_request = connection_request::ConnectionRequest::new();
-    connection_request.addresses = addresses_info;
-    connection_request.tls_mode = if use_tls {
-        connection_request::TlsMode::SecureTls
-    } else {
-        connection_request::TlsMode::NoTls
-    }
-    .into();
-
-    connection_request
+) -> RedisResult<connection_request::ConnectionRequest> {
+    let port = u16::try_from(port).map_err(|_| {
+        (
+            ErrorKind::InvalidClientConfig,
+            "Port is out of range",
+            port.to_string(),
+        )
+    })?;
+    let config = ClientConfig::builder()
+        .address(host, port)
+        .tls_mode(if use_tls {
+            connection_request::TlsMode::SecureTls
+        } else {
+            connection_request::TlsMode::NoTls
+        })
+        .build()?;
+    Ok(config.into())
 }
 
-fn create_client_internal(
-    host: *const c_char,
-    port: u32,
-    use_tls: bool,
+fn create_client_from_request(
+    request: connection_request::ConnectionRequest,
     success_callback: unsafe extern "C" fn(usize, *const c_char) -> (),
     failure_callback: unsafe extern "C" fn(usize) -> (),
 ) -> RedisResult<Client> {
-    let host_cstring = unsafe { CStr::from_ptr(host as *mut c_char) };
-    let host_string = host_cstring.to_str()?.to_string();
-    let request = create_connection_request(host_string, port, use_tls);
     let runtime = Builder::new_multi_thread()
         .enable_all()
         .thread_name("GLIDE for Redis C# thread")
         .build()?;
     let _runtime_handle = runtime.enter();
-    let client = runtime.block_on(GlideClient::new(request)).unwrap(); // TODO - handle errors.
+    let client = runtime.block_on(GlideClient::new(request, None)).unwrap(); // TODO - handle errors.
     Ok(Client {
         client,
         success_callback,
@@ -68,6 +65,19 @@ fn create_client_internal(
     })
 }
 
+fn create_client_internal(
+    host: *const c_char,
+    port: u32,
+    use_tls: bool,
+    success_callback: unsafe extern "C" fn(usize, *const c_char) -> (),
+    failure_callback: unsafe extern "C" fn(usize) -> (),
+) -> RedisResult<Client> {
+    let host_cstring = unsafe { CStr::from_ptr(host as *mut c_char) };
+    let host_string = host_cstring.to_str()?.to_string();
+    let request = create_connection_request(host_string, port, use_tls)?;
+    create_client_from_request(request, success_callback, failure_callback)
+}
+
 /// Creates a new client to the given address. The success callback needs to copy the given string synchronously, since it will be dropped by Rust once the callback returns. All callbacks should be offloaded to separate threads in order not to exhaust the client's thread pool.
 #[no_mangle]
 pub extern "C" fn create_client(
@@ -83,6 +93,37 @@ pub extern "C" fn create_client(
     }
 }
 
+fn create_client_from_uri_internal(
+    uri: *const c_char,
+    success_callback: unsafe extern "C" fn(usize, *const c_char) -> (),
+    failure_callback: unsafe extern "C" fn(usize) -> (),
+) -> RedisResult<Client> {
+    let uri_cstring = unsafe { CStr::from_ptr(uri as *mut c_char) };
+    let request = parse_connection_uri(uri_cstring.to_str()?)?;
+    create_client_from_request(request, success_callback, failure_callback)
+}
+
+/// Creates a new client from a `redis://` or `rediss://` URI. The callbacks have the same requirements as in `create_client`.
+/// If the client can't be created, `error_callback` is called synchronously with the reason, and null is returned. The callback needs
+/// to copy the given string synchronously, since it will be dropped by Rust once the callback returns.
+#[no_mangle]
+pub extern "C" fn create_client_from_uri(
+    uri: *const c_char,
+    success_callback: unsafe extern "C" fn(usize, *const c_char) -> (),
+    failure_callback: unsafe extern "C" fn(usize) -> (),
+    error_callback: unsafe extern "C" fn(*const c_char) -> (),
+) -> *const c_void {
+    match create_client_from_uri_internal(uri, success_callback, failure_callback) {
+        Err(err) => {
+            // The message can't contain a nul byte, since it's built from a valid C string and Rust strings.
+            let message = CString::new(err.to_string()).unwrap_or_default();
+            unsafe { error_callback(message.as_ptr()) };
+            std::ptr::null()
+        }
+        Ok(client) => Box::into_raw(Box::new(client)) as *const c_void,
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn close_client(client_ptr: *const c_void) {
     let client_ptr = unsafe { Box::from_raw(client_ptr as *mut Client) };
@@ -29,6 +29,22 @@ public class AsyncClientTests
         }
     }
 
+    [Test]
+    public async Task GetReturnsLastSetWithClientFromUri()
+    {
+        using (var client = new AsyncClient("redis://localhost:6379"))
+        {
+            await GetAndSetRandomValues(client);
+        }
+    }
+
+    [Test]
+    public void InvalidUriReportsTheReason()
+    {
+        var exception = Assert.Throws<ArgumentException>(() => new AsyncClient("redis://user@localhost:6379"));
+        Assert.That(exception!.Message, Does.Contain("without a password"));
+    }
+
     [Test]
     public async Task GetAndSetCanHandleNonASCIIUnicode()
     {
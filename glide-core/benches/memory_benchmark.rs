@@ -1,20 +1,17 @@
 use glide_core::{
-    client::Client,
-    connection_request::{ConnectionRequest, NodeAddress, TlsMode},
+    client::{Client, ClientConfig},
+    connection_request::ConnectionRequest,
 };
 use iai_callgrind::{black_box, library_benchmark, library_benchmark_group, main};
 use redis::{cmd, Value};
 use tokio::runtime::Builder;
 
 fn create_connection_request() -> ConnectionRequest {
-    let host = "localhost";
-    let mut request = ConnectionRequest::new();
-    request.tls_mode = TlsMode::NoTls.into();
-    let mut address_info = NodeAddress::new();
-    address_info.host = host.into();
-    address_info.port = 6379;
-    request.addresses.push(address_info);
-    request
+    ClientConfig::builder()
+        .address("localhost", 6379)
+        .build()
+        .unwrap()
+        .into()
 }
 
 fn runner<Fut>(f: impl FnOnce(Client) -> Fut)
@@ -23,7 +20,9 @@ where
 {
     let runtime = Builder::new_current_thread().enable_all().build().unwrap();
     runtime.block_on(async {
-        let client = Client::new(create_connection_request()).await.unwrap();
+        let client = Client::new(create_connection_request(), None)
+            .await
+            .unwrap();
         f(client).await;
     });
 }
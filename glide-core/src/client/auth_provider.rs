@@ -0,0 +1,167 @@
+use super::cluster_connection::SharedClusterConnection;
+use super::{ClusterNodeSettings, HEARTBEAT_SLEEP_DURATION};
+use logger_core::{log_debug, log_warn};
+use redis::{ErrorKind, RedisError, RedisResult};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+/// A token that connections authenticate with instead of a fixed password.
+#[derive(Clone, Debug)]
+pub struct AuthToken {
+    pub token: String,
+    /// How long the token is valid after it was returned by the provider, or `None` if it doesn't expire.
+    pub lifetime: Option<Duration>,
+}
+
+impl AuthToken {
+    /// Returns when the token should be replaced. Tokens are replaced after 80% of their lifetime,
+    /// which leaves time to get a new token and re-authenticate before the current one expires.
+    pub(crate) fn refresh_deadline(&self) -> Option<Instant> {
+        self.lifetime
+            .map(|lifetime| Instant::now() + lifetime * 4 / 5)
+    }
+}
+
+/// Provides short-lived tokens, such as cloud IAM auth tokens, for clients that use token authentication.
+/// The provider is called whenever a connection is created or reconnects, and before the token of a live connection expires.
+/// Each connection calls the provider separately, so providers that are expensive to call should cache their tokens.
+///
+/// Cluster clients reconnect to nodes within the cluster connection, which can't call the provider. Instead, when a node
+/// loses its connection or rejects the token, the provider is called, and the cluster connection is replaced with one that
+/// authenticates with the new token.
+pub trait AuthProvider: Send + Sync {
+    fn get_token(&self) -> redis::RedisFuture<'_, AuthToken>;
+}
+
+/// Returns whether a node rejected the token, in which case it didn't run the command.
+pub(super) fn is_token_rejection(err: &RedisError) -> bool {
+    err.kind() == ErrorKind::AuthenticationFailed
+        || matches!(err.code(), Some("NOAUTH") | Some("WRONGPASS"))
+}
+
+/// Returns whether the error means that a node's connection is being recreated, or that the node rejected the token, so
+/// that the cluster's connections need a new token.
+pub(super) fn needs_new_token(err: &RedisError) -> bool {
+    is_token_rejection(err) || err.is_connection_dropped() || err.is_connection_refusal()
+}
+
+struct InnerClusterTokenRefresher {
+    connection: SharedClusterConnection,
+    auth_provider: Arc<dyn AuthProvider>,
+    node_settings: ClusterNodeSettings,
+    /// When the current token should be replaced, or `None` if it doesn't expire.
+    refresh_deadline: Mutex<Option<Instant>>,
+    /// Held while a token is being requested and applied, so that concurrent failures wait for a single refresh.
+    refresh: tokio::sync::Mutex<()>,
+    /// The number of refreshes that started, so that callers that waited for a refresh that started after they called
+    /// don't start another one.
+    refreshes_started: AtomicU64,
+}
+
+/// Replaces the token of a cluster's connections before it expires, and whenever a node needs a new one.
+/// The scheduled refresh is done by a background task, which stops once every clone of the refresher is dropped.
+#[derive(Clone)]
+pub(super) struct ClusterTokenRefresher {
+    inner: Arc<InnerClusterTokenRefresher>,
+}
+
+impl ClusterTokenRefresher {
+    pub(super) fn new(
+        connection: SharedClusterConnection,
+        auth_provider: Arc<dyn AuthProvider>,
+        node_settings: ClusterNodeSettings,
+        token: &AuthToken,
+    ) -> Self {
+        let inner = Arc::new(InnerClusterTokenRefresher {
+            connection,
+            auth_provider,
+            node_settings,
+            refresh_deadline: Mutex::new(token.refresh_deadline()),
+            refresh: Default::default(),
+            refreshes_started: AtomicU64::new(0),
+        });
+        tokio::spawn(refresh_cluster_token(Arc::downgrade(&inner)));
+        Self { inner }
+    }
+
+    /// Requests a new token, and replaces the cluster's connections with connections that authenticate with it.
+    /// If a refresh is already in progress, waits for it, and then refreshes again unless another refresh started after
+    /// this call, since the refresh in progress might have requested its token before the failure that led to this call.
+    pub(super) async fn refresh(&self) -> RedisResult<()> {
+        let inner = &self.inner;
+        let started_before_call = inner.refreshes_started.load(Ordering::Acquire);
+        let _refresh = inner.refresh.lock().await;
+        if inner.refreshes_started.load(Ordering::Acquire) != started_before_call {
+            return Ok(());
+        }
+        inner.refreshes_started.fetch_add(1, Ordering::AcqRel);
+        // The deadline isn't moved if the token can't be requested, so the scheduled refresh tries again.
+        let token = inner.auth_provider.get_token().await?;
+        *inner.refresh_deadline.lock().unwrap() = token.refresh_deadline();
+        // The connection is replaced rather than re-authenticated, since some of its nodes might be unreachable.
+        inner
+            .connection
+            .replace_password(&inner.node_settings, Some(token.token))
+            .await
+    }
+}
+
+async fn refresh_cluster_token(inner: Weak<InnerClusterTokenRefresher>) {
+    loop {
+        tokio::time::sleep(HEARTBEAT_SLEEP_DURATION).await;
+        let Some(inner) = inner.upgrade() else {
+            log_debug(
+                "ClusterTokenRefresher",
+                "token refresh stopped after client was dropped",
+            );
+            return;
+        };
+        let deadline = *inner.refresh_deadline.lock().unwrap();
+        if !deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+            continue;
+        }
+        if let Err(err) = (ClusterTokenRefresher { inner }).refresh().await {
+            log_warn(
+                "token refresh",
+                format!("failed to refresh the cluster's token: `{err}`"),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_refreshed_before_they_expire() {
+        let token = AuthToken {
+            token: "token".to_string(),
+            lifetime: Some(Duration::from_secs(100)),
+        };
+        let deadline = token.refresh_deadline().unwrap();
+        assert!(deadline <= Instant::now() + Duration::from_secs(80));
+        assert!(deadline > Instant::now() + Duration::from_secs(79));
+
+        let token = AuthToken {
+            lifetime: None,
+            ..token
+        };
+        assert_eq!(token.refresh_deadline(), None);
+    }
+
+    #[test]
+    fn lost_connections_and_rejected_tokens_need_a_new_token() {
+        let rejection = redis::make_extension_error("WRONGPASS".to_string(), None);
+        assert!(is_token_rejection(&rejection));
+        assert!(needs_new_token(&rejection));
+
+        let disconnect = RedisError::from(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+        assert!(!is_token_rejection(&disconnect));
+        assert!(needs_new_token(&disconnect));
+
+        let wrong_type = redis::make_extension_error("WRONGTYPE".to_string(), None);
+        assert!(!needs_new_token(&wrong_type));
+    }
+}
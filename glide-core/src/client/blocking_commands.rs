@@ -0,0 +1,150 @@
+use redis::{Arg, Cmd};
+use std::time::Duration;
+
+/// How long a blocking command may wait on the server before it replies.
+#[derive(Debug, PartialEq)]
+pub(super) enum BlockingTimeout {
+    /// A timeout of 0 blocks until the command can be served.
+    Indefinite,
+    Finite(Duration),
+}
+
+enum TimeoutUnit {
+    Seconds,
+    Milliseconds,
+}
+
+fn simple_arg<'a>(arg: Arg<&'a [u8]>) -> Option<&'a [u8]> {
+    match arg {
+        Arg::Simple(arg) => Some(arg),
+        Arg::Cursor => None,
+    }
+}
+
+/// Returns the timeout argument of a blocking command and its unit, or `None` if the command doesn't block.
+fn get_timeout_arg(cmd: &Cmd) -> Option<(&[u8], TimeoutUnit)> {
+    let mut args = cmd.args_iter().filter_map(simple_arg);
+    let command = args.next()?.to_ascii_uppercase();
+    match command.as_slice() {
+        b"BLPOP" | b"BRPOP" | b"BLMOVE" | b"BRPOPLPUSH" | b"BZPOPMIN" | b"BZPOPMAX" => {
+            Some((args.last()?, TimeoutUnit::Seconds))
+        }
+        b"BLMPOP" | b"BZMPOP" => Some((args.next()?, TimeoutUnit::Seconds)),
+        b"WAIT" | b"WAITAOF" => Some((args.last()?, TimeoutUnit::Milliseconds)),
+        b"XREAD" | b"XREADGROUP" => {
+            // The options precede the STREAMS keyword, and the stream names that follow it might be called "BLOCK".
+            let mut options = args.take_while(|arg| !arg.eq_ignore_ascii_case(b"STREAMS"));
+            options.find(|arg| arg.eq_ignore_ascii_case(b"BLOCK"))?;
+            Some((options.next()?, TimeoutUnit::Milliseconds))
+        }
+        _ => None,
+    }
+}
+
+/// Returns how long the command may block on the server, or `None` if it doesn't block.
+/// Commands with an invalid timeout are treated as non-blocking, since the server rejects them immediately.
+pub(super) fn get_blocking_timeout(cmd: &Cmd) -> Option<BlockingTimeout> {
+    let (timeout, unit) = get_timeout_arg(cmd)?;
+    let timeout: f64 = std::str::from_utf8(timeout).ok()?.parse().ok()?;
+    let timeout = match unit {
+        TimeoutUnit::Seconds => timeout,
+        TimeoutUnit::Milliseconds => timeout / 1000.0,
+    };
+    if timeout == 0.0 {
+        return Some(BlockingTimeout::Indefinite);
+    }
+    Duration::try_from_secs_f64(timeout)
+        .ok()
+        .map(BlockingTimeout::Finite)
+}
+
+pub(super) fn is_blocking_cmd(cmd: &Cmd) -> bool {
+    get_blocking_timeout(cmd).is_some()
+}
+
+/// Returns whether the command holds up its connection's later commands while it blocks, and so needs a connection of its own.
+/// WAIT and WAITAOF are excluded, since they wait for the writes that were sent before them on the same connection.
+pub(super) fn blocks_connection(cmd: &Cmd) -> bool {
+    let command = cmd
+        .args_iter()
+        .filter_map(simple_arg)
+        .next()
+        .unwrap_or_default();
+    is_blocking_cmd(cmd)
+        && !command.eq_ignore_ascii_case(b"WAIT")
+        && !command.eq_ignore_ascii_case(b"WAITAOF")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocking_timeout(args: &[&str]) -> Option<BlockingTimeout> {
+        let mut cmd = Cmd::new();
+        for arg in args {
+            cmd.arg(*arg);
+        }
+        get_blocking_timeout(&cmd)
+    }
+
+    #[test]
+    fn list_commands_use_seconds() {
+        assert_eq!(
+            blocking_timeout(&["BLPOP", "key1", "key2", "1.5"]),
+            Some(BlockingTimeout::Finite(Duration::from_millis(1500)))
+        );
+        assert_eq!(
+            blocking_timeout(&["blmove", "source", "destination", "LEFT", "RIGHT", "0"]),
+            Some(BlockingTimeout::Indefinite)
+        );
+        assert_eq!(
+            blocking_timeout(&["BLMPOP", "2", "2", "key1", "key2", "LEFT"]),
+            Some(BlockingTimeout::Finite(Duration::from_secs(2)))
+        );
+    }
+
+    #[test]
+    fn wait_uses_milliseconds() {
+        assert_eq!(
+            blocking_timeout(&["WAIT", "1", "250"]),
+            Some(BlockingTimeout::Finite(Duration::from_millis(250)))
+        );
+    }
+
+    #[test]
+    fn xread_blocks_only_with_the_block_option() {
+        assert_eq!(
+            blocking_timeout(&["XREAD", "COUNT", "2", "BLOCK", "100", "STREAMS", "key", "0"]),
+            Some(BlockingTimeout::Finite(Duration::from_millis(100)))
+        );
+        assert_eq!(
+            blocking_timeout(&["XREAD", "STREAMS", "BLOCK", "100"]),
+            None
+        );
+    }
+
+    #[test]
+    fn wait_keeps_its_connection() {
+        let cmd = |args: &[&str]| {
+            let mut cmd = Cmd::new();
+            for arg in args {
+                cmd.arg(*arg);
+            }
+            cmd
+        };
+        assert!(blocks_connection(&cmd(&["BLPOP", "key", "0"])));
+        assert!(blocks_connection(&cmd(&[
+            "XREAD", "BLOCK", "100", "STREAMS", "key", "0"
+        ])));
+        assert!(!blocks_connection(&cmd(&["WAIT", "1", "0"])));
+        assert!(!blocks_connection(&cmd(&["waitaof", "1", "0", "0"])));
+        assert!(!blocks_connection(&cmd(&["GET", "key"])));
+    }
+
+    #[test]
+    fn invalid_timeouts_are_not_blocking() {
+        assert_eq!(blocking_timeout(&["BLPOP", "key", "-1"]), None);
+        assert_eq!(blocking_timeout(&["BLPOP", "key", "forever"]), None);
+        assert_eq!(blocking_timeout(&["GET", "key"]), None);
+    }
+}
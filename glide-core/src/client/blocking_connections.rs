@@ -0,0 +1,113 @@
+use super::request_tracing;
+use logger_core::log_debug;
+use redis::aio::MultiplexedConnection;
+use redis::{Cmd, RedisResult, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// How many idle connections are kept for each node. Connections beyond it are closed once their command replies.
+const MAX_IDLE_CONNECTIONS_PER_NODE: usize = 4;
+
+/// How many MOVED and ASK redirections a blocking command follows before its error is returned.
+pub(super) const MAX_REDIRECTIONS: usize = 5;
+
+/// Connections for commands that block on the server until they can be served, such as BLPOP, kept per node.
+/// A blocking command holds up the later commands of its connection on the server, so each blocking command takes a
+/// connection of its own for as long as it blocks, and the connection is kept for the next blocking command once it replies.
+#[derive(Clone, Default)]
+pub(super) struct BlockingConnections {
+    idle: Arc<Mutex<HashMap<String, Vec<MultiplexedConnection>>>>,
+}
+
+impl BlockingConnections {
+    /// Sends the command to the node with the given address on one of the node's idle connections, or on a new connection
+    /// from `connect` if there's none. With `asking`, the command is preceded by ASKING, for commands that were redirected
+    /// by an ASK error.
+    /// A connection whose request failed with an I/O error, or whose request was dropped, such as on a timeout, is closed
+    /// instead of being kept, which also releases the command on the server.
+    pub(super) async fn send<F>(
+        &self,
+        address: &str,
+        connect: impl Fn() -> F,
+        cmd: &Cmd,
+        asking: bool,
+    ) -> RedisResult<Value>
+    where
+        F: Future<Output = RedisResult<MultiplexedConnection>>,
+    {
+        request_tracing::record_node(address);
+        if let Some(connection) = self.take_idle(address) {
+            match Self::send_on_connection(connection.clone(), cmd, asking).await {
+                // The server might have closed the idle connection, in which case the command is sent again on a new one.
+                Err(err) if err.is_connection_dropped() => {
+                    log_debug(
+                        "blocking connections",
+                        format!("An idle connection to {address} was closed: {err}"),
+                    );
+                }
+                result => {
+                    self.keep(address, connection, &result);
+                    return result;
+                }
+            }
+        }
+        let connection = connect().await?;
+        let result = Self::send_on_connection(connection.clone(), cmd, asking).await;
+        self.keep(address, connection, &result);
+        result
+    }
+
+    async fn send_on_connection(
+        mut connection: MultiplexedConnection,
+        cmd: &Cmd,
+        asking: bool,
+    ) -> RedisResult<Value> {
+        if asking {
+            connection
+                .send_packed_command(&redis::cmd("ASKING"))
+                .await?;
+        }
+        connection.send_packed_command(cmd).await
+    }
+
+    fn take_idle(&self, address: &str) -> Option<MultiplexedConnection> {
+        self.idle.lock().unwrap().get_mut(address)?.pop()
+    }
+
+    fn keep(&self, address: &str, connection: MultiplexedConnection, result: &RedisResult<Value>) {
+        if matches!(result, Err(err) if err.is_io_error()) {
+            return;
+        }
+        let mut idle = self.idle.lock().unwrap();
+        let connections = idle.entry(address.to_string()).or_default();
+        if connections.len() < MAX_IDLE_CONNECTIONS_PER_NODE {
+            connections.push(connection);
+        }
+    }
+}
+
+/// Splits a redirection's "host:port" address.
+pub(super) fn parse_redirect_address(address: &str) -> Option<(String, u16)> {
+    let (host, port) = address.rsplit_once(':')?;
+    Some((host.to_string(), port.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redirect_addresses_are_split_at_the_last_colon() {
+        assert_eq!(
+            parse_redirect_address("10.0.0.1:6380"),
+            Some(("10.0.0.1".to_string(), 6380))
+        );
+        assert_eq!(
+            parse_redirect_address("::1:6379"),
+            Some(("::1".to_string(), 6379))
+        );
+        assert_eq!(parse_redirect_address("host"), None);
+        assert_eq!(parse_redirect_address("host:port"), None);
+    }
+}
@@ -0,0 +1,86 @@
+use super::{auth_cmd, create_cluster_client, ClusterNodeSettings};
+use crate::connection_request::ConnectionRequest;
+use logger_core::log_info;
+use redis::cluster_async::ClusterConnection;
+use redis::cluster_routing::{MultipleNodeRoutingInfo, ResponsePolicy, RoutingInfo};
+use redis::RedisResult;
+use std::sync::{Arc, RwLock};
+
+struct InnerSharedClusterConnection {
+    connection: RwLock<ClusterConnection>,
+    /// The request that the current connection was created from. Replacements are created from it, with the new password.
+    /// The lock also keeps concurrent password updates from replacing the connection at the same time.
+    request: tokio::sync::Mutex<ConnectionRequest>,
+}
+
+/// The cluster connection of a client, which is shared by the client's clones and by its background tasks.
+/// The cluster connection reconnects to nodes with the password that it was created with, so when the password is updated,
+/// the connection is replaced with one that was created with the new password. Requests that were already sent complete
+/// on the previous connection, which is closed once they're done.
+#[derive(Clone)]
+pub(super) struct SharedClusterConnection {
+    inner: Arc<InnerSharedClusterConnection>,
+}
+
+impl SharedClusterConnection {
+    pub(super) async fn new(request: ConnectionRequest) -> RedisResult<Self> {
+        let connection = create_cluster_client(request.clone()).await?;
+        Ok(Self {
+            inner: Arc::new(InnerSharedClusterConnection {
+                connection: RwLock::new(connection),
+                request: tokio::sync::Mutex::new(request),
+            }),
+        })
+    }
+
+    /// Returns the current connection. Callers that send several requests should call it for each of them, so that they
+    /// use the replacement once the password is updated.
+    pub(super) fn get(&self) -> ClusterConnection {
+        self.inner.connection.read().unwrap().clone()
+    }
+
+    /// Re-authenticates every node with the password, and then replaces the connection like [Self::replace_password].
+    /// If any node fails to re-authenticate, the current connection and password are kept.
+    pub(super) async fn update_password(
+        &self,
+        node_settings: &ClusterNodeSettings,
+        password: Option<String>,
+    ) -> RedisResult<()> {
+        if let Some(password) = &password {
+            self.get()
+                .route_command(
+                    &auth_cmd(node_settings.username().as_deref(), password),
+                    RoutingInfo::MultiNode((
+                        MultipleNodeRoutingInfo::AllNodes,
+                        Some(ResponsePolicy::AllSucceeded),
+                    )),
+                )
+                .await?;
+        }
+        self.replace_password(node_settings, password).await
+    }
+
+    /// Replaces the connection with one that authenticates with the password, as does the node settings' connection info.
+    /// If the replacement can't be created, the current connection and password are kept.
+    pub(super) async fn replace_password(
+        &self,
+        node_settings: &ClusterNodeSettings,
+        password: Option<String>,
+    ) -> RedisResult<()> {
+        let mut request = self.inner.request.lock().await;
+        let mut new_request = request.clone();
+        new_request
+            .authentication_info
+            .mut_or_insert_default()
+            .password = password.clone().unwrap_or_default().into();
+        let connection = create_cluster_client(new_request.clone()).await?;
+        *self.inner.connection.write().unwrap() = connection;
+        *request = new_request;
+        node_settings.set_password(password);
+        log_info(
+            "cluster connection",
+            "replaced the cluster connection after the password was updated",
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,303 @@
+use super::cluster_connection::SharedClusterConnection;
+use super::HEARTBEAT_SLEEP_DURATION;
+use logger_core::{log_debug, log_warn};
+use redis::cluster_async::ClusterConnection;
+use redis::cluster_routing::{RoutingInfo, SingleNodeRoutingInfo};
+use redis::{RedisResult, Value};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::{Duration, Instant};
+
+/// How often the cached topology is refreshed.
+const TOPOLOGY_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A primary, its replicas, and the slot ranges that it serves.
+#[derive(Clone, Debug, PartialEq)]
+struct Shard {
+    primary: (String, u16),
+    replicas: Vec<(String, u16)>,
+    slots: Vec<(u16, u16)>,
+}
+
+/// The cluster's nodes according to `CLUSTER SLOTS`, with the addresses that the cluster connection uses for them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct ClusterNodes {
+    shards: Vec<Shard>,
+}
+
+/// Parses a node of a `CLUSTER SLOTS` slot range. Like the cluster connection, a node whose endpoint is unknown,
+/// which is reported as an empty or "?" host, is addressed by the host of the node that answered.
+fn parse_node(node: &Value, answering_host: &str) -> RedisResult<Option<(String, u16)>> {
+    let Value::Array(node) = node else {
+        return Ok(None);
+    };
+    let [host, port, ..] = node.as_slice() else {
+        return Ok(None);
+    };
+    let host: String = redis::from_redis_value(host)?;
+    let port: u16 = redis::from_redis_value(port)?;
+    let host = match host.as_str() {
+        "" | "?" => answering_host.to_string(),
+        _ => host,
+    };
+    Ok(Some((host, port)))
+}
+
+impl ClusterNodes {
+    pub(super) fn parse(value: &Value, answering_host: &str) -> RedisResult<Self> {
+        let ranges: Vec<Vec<Value>> = redis::from_redis_value(value)?;
+        let mut shards: Vec<Shard> = Vec::new();
+        for range in ranges {
+            let [start, end, primary, replicas @ ..] = range.as_slice() else {
+                continue;
+            };
+            let Some(primary) = parse_node(primary, answering_host)? else {
+                continue;
+            };
+            let slots = (
+                redis::from_redis_value(start)?,
+                redis::from_redis_value(end)?,
+            );
+            let shard = match shards.iter_mut().find(|shard| shard.primary == primary) {
+                Some(shard) => shard,
+                None => {
+                    shards.push(Shard {
+                        primary,
+                        replicas: Vec::new(),
+                        slots: Vec::new(),
+                    });
+                    shards.last_mut().unwrap()
+                }
+            };
+            shard.slots.push(slots);
+            for replica in replicas {
+                if let Some(replica) = parse_node(replica, answering_host)? {
+                    if !shard.replicas.contains(&replica) {
+                        shard.replicas.push(replica);
+                    }
+                }
+            }
+        }
+        Ok(Self { shards })
+    }
+
+    /// Returns the address of every node, or of every primary, without duplicates.
+    pub(super) fn addresses(&self, primaries_only: bool) -> Vec<(String, u16)> {
+        let mut addresses = Vec::new();
+        for shard in self.shards.iter() {
+            addresses.push(shard.primary.clone());
+            if !primaries_only {
+                addresses.extend(shard.replicas.iter().cloned());
+            }
+        }
+        addresses
+    }
+
+    pub(super) fn contains(&self, host: &str, port: u16) -> bool {
+        self.shards.iter().any(|shard| {
+            std::iter::once(&shard.primary)
+                .chain(shard.replicas.iter())
+                .any(|(node_host, node_port)| node_host == host && *node_port == port)
+        })
+    }
+
+    /// Returns the address of the primary that serves the slot, or `None` if no known primary serves it.
+    pub(super) fn slot_primary(&self, slot: u16) -> Option<&(String, u16)> {
+        self.shards
+            .iter()
+            .find(|shard| {
+                shard
+                    .slots
+                    .iter()
+                    .any(|(start, end)| (*start..=*end).contains(&slot))
+            })
+            .map(|shard| &shard.primary)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.shards.is_empty()
+    }
+}
+
+/// Asks a known primary for the topology, so that its host can stand in for unknown endpoints. Until a primary is known,
+/// or if it can't be reached, a random node is asked, and the seed host stands in for unknown endpoints.
+async fn fetch_nodes(
+    connection: &mut ClusterConnection,
+    known_nodes: &ClusterNodes,
+    seed_host: &str,
+) -> RedisResult<ClusterNodes> {
+    let cmd = redis::cmd("CLUSTER").arg("SLOTS").clone();
+    if let Some((host, port)) = known_nodes.addresses(true).into_iter().next() {
+        let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+            host: host.clone(),
+            port,
+        });
+        match connection.route_command(&cmd, routing).await {
+            Ok(value) => return ClusterNodes::parse(&value, &host),
+            Err(err) => log_debug(
+                "cluster topology",
+                format!("failed to ask {host}:{port} for the topology: `{err}`"),
+            ),
+        }
+    }
+    let value = connection
+        .route_command(&cmd, RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
+        .await?;
+    ClusterNodes::parse(&value, seed_host)
+}
+
+struct InnerClusterTopology {
+    nodes: RwLock<ClusterNodes>,
+    seed_host: String,
+}
+
+/// A cached copy of the cluster's topology, so that requests can be routed by node without asking the cluster first.
+/// It's refreshed periodically by a background task, which stops once every clone of the cache is dropped.
+#[derive(Clone)]
+pub(super) struct ClusterTopology {
+    inner: Arc<InnerClusterTopology>,
+}
+
+impl ClusterTopology {
+    /// Fetches the topology. If that fails, the cache starts out empty and is filled by the next refresh.
+    pub(super) async fn new(connection: SharedClusterConnection, seed_host: String) -> Self {
+        let nodes =
+            match fetch_nodes(&mut connection.get(), &ClusterNodes::default(), &seed_host).await {
+                Ok(nodes) => nodes,
+                Err(err) => {
+                    log_warn(
+                        "cluster topology",
+                        format!("failed to fetch the topology: `{err}`"),
+                    );
+                    ClusterNodes::default()
+                }
+            };
+        let inner = Arc::new(InnerClusterTopology {
+            nodes: RwLock::new(nodes),
+            seed_host,
+        });
+        tokio::spawn(refresh_topology(Arc::downgrade(&inner), connection));
+        Self { inner }
+    }
+
+    pub(super) fn nodes(&self) -> ClusterNodes {
+        self.inner.nodes.read().unwrap().clone()
+    }
+
+    pub(super) fn slot_primary(&self, slot: u16) -> Option<(String, u16)> {
+        self.inner.nodes.read().unwrap().slot_primary(slot).cloned()
+    }
+
+    pub(super) fn contains(&self, host: &str, port: u16) -> bool {
+        self.inner.nodes.read().unwrap().contains(host, port)
+    }
+}
+
+async fn refresh_topology(inner: Weak<InnerClusterTopology>, connection: SharedClusterConnection) {
+    let mut last_refresh = Instant::now();
+    loop {
+        tokio::time::sleep(HEARTBEAT_SLEEP_DURATION).await;
+        let Some(inner) = inner.upgrade() else {
+            log_debug(
+                "cluster topology",
+                "topology refresh stopped after client was dropped",
+            );
+            return;
+        };
+        // An empty topology is retried on every heartbeat.
+        let is_empty = inner.nodes.read().unwrap().is_empty();
+        if !is_empty && last_refresh.elapsed() < TOPOLOGY_REFRESH_INTERVAL {
+            continue;
+        }
+        last_refresh = Instant::now();
+        let known_nodes = inner.nodes.read().unwrap().clone();
+        match fetch_nodes(&mut connection.get(), &known_nodes, &inner.seed_host).await {
+            Ok(nodes) => *inner.nodes.write().unwrap() = nodes,
+            Err(err) => log_debug(
+                "cluster topology",
+                format!("failed to refresh the topology: `{err}`"),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_value(host: &str, port: i64) -> Value {
+        Value::Array(vec![
+            Value::BulkString(host.as_bytes().to_vec()),
+            Value::Int(port),
+        ])
+    }
+
+    fn address(host: &str, port: u16) -> (String, u16) {
+        (host.to_string(), port)
+    }
+
+    #[test]
+    fn nodes_are_grouped_by_primary() {
+        let slots = Value::Array(vec![
+            Value::Array(vec![
+                Value::Int(0),
+                Value::Int(100),
+                node_value("primary1", 6379),
+                node_value("replica1", 6380),
+            ]),
+            Value::Array(vec![
+                Value::Int(101),
+                Value::Int(16383),
+                node_value("primary2", 6379),
+            ]),
+            Value::Array(vec![
+                Value::Int(200),
+                Value::Int(300),
+                node_value("primary1", 6379),
+                node_value("replica1", 6380),
+            ]),
+        ]);
+        let nodes = ClusterNodes::parse(&slots, "answering").unwrap();
+        assert_eq!(
+            nodes.addresses(false),
+            vec![
+                address("primary1", 6379),
+                address("replica1", 6380),
+                address("primary2", 6379),
+            ]
+        );
+        assert_eq!(
+            nodes.addresses(true),
+            vec![address("primary1", 6379), address("primary2", 6379)]
+        );
+        assert_eq!(nodes.slot_primary(250), Some(&address("primary1", 6379)));
+        assert_eq!(nodes.slot_primary(150), Some(&address("primary2", 6379)));
+        assert!(nodes.contains("replica1", 6380));
+        assert!(!nodes.contains("replica1", 6379));
+    }
+
+    #[test]
+    fn unknown_endpoints_are_addressed_by_the_answering_host() {
+        let slots = Value::Array(vec![Value::Array(vec![
+            Value::Int(0),
+            Value::Int(16383),
+            node_value("", 6379),
+            node_value("?", 6380),
+        ])]);
+        let nodes = ClusterNodes::parse(&slots, "answering").unwrap();
+        assert_eq!(
+            nodes.addresses(false),
+            vec![address("answering", 6379), address("answering", 6380)]
+        );
+    }
+
+    #[test]
+    fn slots_without_primary_have_no_node() {
+        let slots = Value::Array(vec![Value::Array(vec![
+            Value::Int(0),
+            Value::Int(100),
+            node_value("primary1", 6379),
+        ])]);
+        let nodes = ClusterNodes::parse(&slots, "answering").unwrap();
+        assert_eq!(nodes.slot_primary(101), None);
+    }
+}
@@ -0,0 +1,382 @@
+use super::cluster_connection::SharedClusterConnection;
+use super::latency::LatencyTracker;
+use super::{
+    get_availability_zone_from_info, run_with_timeout, DEFAULT_RESPONSE_TIMEOUT,
+    HEARTBEAT_SLEEP_DURATION,
+};
+use futures::future;
+use logger_core::{log_debug, log_warn};
+use redis::cluster_async::ClusterConnection;
+use redis::cluster_routing::{RoutingInfo, SingleNodeRoutingInfo, SlotAddr};
+use redis::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock, Weak};
+use std::time::Instant;
+
+#[derive(Clone)]
+pub(super) enum ReadStrategy {
+    /// Read from the node with the lowest latency, including the primary.
+    LowestLatency,
+    /// Read from the fastest replica in the client's availability zone, or from the fastest replica in another zone if none is available.
+    AZAffinity { client_az: String },
+}
+
+/// Availability zones that were set in the connection request, keyed by host and port.
+pub(super) type ConfiguredZones = HashMap<(String, u16), String>;
+
+struct Node {
+    host: String,
+    port: u16,
+    latency: LatencyTracker,
+    /// Empty if the node doesn't report a zone.
+    availability_zone: OnceLock<String>,
+}
+
+struct SlotRange {
+    start: u16,
+    end: u16,
+    /// The primary is the first node, followed by its replicas.
+    nodes: Vec<Arc<Node>>,
+}
+
+/// Routes read-only commands in a cluster to a node that serves the command's slot, according to the read strategy.
+/// The latencies, zones and slot ownership are refreshed by a background task, which stops once the router is dropped.
+#[derive(Clone)]
+pub(super) struct ClusterReadRouter {
+    slots: Arc<RwLock<Vec<SlotRange>>>,
+    strategy: ReadStrategy,
+}
+
+impl ClusterReadRouter {
+    pub(super) fn new(
+        connection: SharedClusterConnection,
+        strategy: ReadStrategy,
+        configured_zones: ConfiguredZones,
+    ) -> Self {
+        let slots = Arc::new(RwLock::new(Vec::new()));
+        let discover_zones = matches!(strategy, ReadStrategy::AZAffinity { .. });
+        tokio::spawn(refresh_nodes(
+            Arc::downgrade(&slots),
+            connection,
+            configured_zones,
+            discover_zones,
+        ));
+        Self { slots, strategy }
+    }
+
+    /// Replaces routes that may be served by a replica with the address of the node chosen by the read strategy.
+    /// Routes that must reach a specific node type are returned unchanged, as are routes to slots without responsive nodes.
+    pub(super) fn route_read(&self, routing: RoutingInfo) -> RoutingInfo {
+        let RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route)) = &routing else {
+            return routing;
+        };
+        if !matches!(route.slot_addr(), SlotAddr::ReplicaOptional) {
+            return routing;
+        }
+        let slot = route.slot();
+        let slots = self.slots.read().unwrap();
+        let Some(range) = slots
+            .iter()
+            .find(|range| range.start <= slot && slot <= range.end)
+        else {
+            return routing;
+        };
+        let chosen_node = match &self.strategy {
+            ReadStrategy::LowestLatency => fastest_node(range.nodes.iter()),
+            ReadStrategy::AZAffinity { client_az } => {
+                // The primary is skipped, since it serves all writes.
+                let replicas = || range.nodes.iter().skip(1);
+                fastest_node(replicas().filter(|node| {
+                    node.availability_zone.get().map(String::as_str) == Some(client_az.as_str())
+                }))
+                .or_else(|| fastest_node(replicas()))
+            }
+        };
+        match chosen_node {
+            Some(node) => node_routing(node),
+            None => routing,
+        }
+    }
+}
+
+/// Returns the node with the lowest latency. Nodes without a measured latency didn't respond recently, and are skipped.
+fn fastest_node<'a>(nodes: impl Iterator<Item = &'a Arc<Node>>) -> Option<&'a Arc<Node>> {
+    nodes
+        .filter_map(|node| node.latency.average().map(|latency| (latency, node)))
+        .min_by_key(|(latency, _)| *latency)
+        .map(|(_, node)| node)
+}
+
+fn node_routing(node: &Node) -> RoutingInfo {
+    RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+        host: node.host.clone(),
+        port: node.port,
+    })
+}
+
+async fn discover_zone(node: &Node, connection: &mut ClusterConnection) {
+    if node.availability_zone.get().is_some() {
+        return;
+    }
+    let result = run_with_timeout(
+        DEFAULT_RESPONSE_TIMEOUT,
+        connection.route_command(redis::cmd("INFO").arg("SERVER"), node_routing(node)),
+    )
+    .await;
+    match result {
+        Ok(info) => {
+            let _ = node
+                .availability_zone
+                .set(get_availability_zone_from_info(&info).unwrap_or_default());
+        }
+        Err(err) => log_debug(
+            "ClusterReadRouter",
+            format!(
+                "failed to get the availability zone of {}:{}: `{err}`",
+                node.host, node.port
+            ),
+        ),
+    }
+}
+
+async fn refresh_nodes(
+    slots: Weak<RwLock<Vec<SlotRange>>>,
+    connection: SharedClusterConnection,
+    configured_zones: ConfiguredZones,
+    discover_zones: bool,
+) {
+    loop {
+        let Some(slots) = slots.upgrade() else {
+            log_debug(
+                "ClusterReadRouter",
+                "node refresh stopped after client was dropped",
+            );
+            return;
+        };
+
+        let connection = connection.get();
+        let result = connection
+            .clone()
+            .route_command(
+                redis::cmd("CLUSTER").arg("SLOTS"),
+                RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random),
+            )
+            .await;
+        match result {
+            Ok(value) => {
+                let known_nodes = slots
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .flat_map(|range| range.nodes.iter())
+                    .map(|node| ((node.host.clone(), node.port), node.clone()))
+                    .collect();
+                let new_slots = parse_cluster_slots(value, known_nodes, &configured_zones);
+                *slots.write().unwrap() = new_slots;
+            }
+            Err(err) => log_warn(
+                "ClusterReadRouter",
+                format!("failed to refresh slots: `{err}`"),
+            ),
+        }
+
+        let nodes: HashMap<(String, u16), Arc<Node>> = slots
+            .read()
+            .unwrap()
+            .iter()
+            .flat_map(|range| range.nodes.iter())
+            .map(|node| ((node.host.clone(), node.port), node.clone()))
+            .collect();
+        drop(slots);
+        future::join_all(nodes.into_values().map(|node| {
+            let mut connection = connection.clone();
+            async move {
+                if discover_zones {
+                    discover_zone(&node, &mut connection).await;
+                }
+                let start = Instant::now();
+                let ping = redis::cmd("PING");
+                match run_with_timeout(
+                    DEFAULT_RESPONSE_TIMEOUT,
+                    connection.route_command(&ping, node_routing(&node)),
+                )
+                .await
+                {
+                    Ok(_) => node.latency.record(start.elapsed()),
+                    // Nodes that don't respond aren't chosen until they respond again.
+                    Err(_) => node.latency.reset(),
+                }
+            }
+        }))
+        .await;
+
+        tokio::time::sleep(HEARTBEAT_SLEEP_DURATION).await;
+    }
+}
+
+/// Parses a `CLUSTER SLOTS` response. Nodes that are already known keep their latency measurements and zones.
+fn parse_cluster_slots(
+    value: Value,
+    mut known_nodes: HashMap<(String, u16), Arc<Node>>,
+    configured_zones: &ConfiguredZones,
+) -> Vec<SlotRange> {
+    let Value::Array(ranges) = value else {
+        return Vec::new();
+    };
+    ranges
+        .into_iter()
+        .filter_map(|range| {
+            let Value::Array(range) = range else {
+                return None;
+            };
+            let mut range = range.into_iter();
+            let start = redis::from_redis_value(&range.next()?).ok()?;
+            let end = redis::from_redis_value(&range.next()?).ok()?;
+            let nodes = range
+                .filter_map(|node| {
+                    let Value::Array(node) = node else {
+                        return None;
+                    };
+                    let host: String = redis::from_redis_value(node.first()?).ok()?;
+                    let port: u16 = redis::from_redis_value(node.get(1)?).ok()?;
+                    let node = known_nodes
+                        .entry((host.clone(), port))
+                        .or_insert_with(|| {
+                            let availability_zone = OnceLock::new();
+                            if let Some(zone) = configured_zones.get(&(host.clone(), port)) {
+                                let _ = availability_zone.set(zone.clone());
+                            }
+                            Arc::new(Node {
+                                host,
+                                port,
+                                latency: LatencyTracker::default(),
+                                availability_zone,
+                            })
+                        })
+                        .clone();
+                    Some(node)
+                })
+                .collect();
+            Some(SlotRange { start, end, nodes })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(host: &str, port: u16, zone: &str, latency_millis: u64) -> Arc<Node> {
+        let node = Arc::new(Node {
+            host: host.to_string(),
+            port,
+            latency: LatencyTracker::default(),
+            availability_zone: OnceLock::from(zone.to_string()),
+        });
+        node.latency
+            .record(std::time::Duration::from_millis(latency_millis));
+        node
+    }
+
+    fn router_with_nodes(strategy: ReadStrategy, nodes: Vec<Arc<Node>>) -> ClusterReadRouter {
+        ClusterReadRouter {
+            slots: Arc::new(RwLock::new(vec![SlotRange {
+                start: 0,
+                end: 16383,
+                nodes,
+            }])),
+            strategy,
+        }
+    }
+
+    fn routed_port(router: &ClusterReadRouter) -> Option<u16> {
+        let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(
+            redis::cluster_routing::Route::new(100, SlotAddr::ReplicaOptional),
+        ));
+        match router.route_read(routing) {
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { port, .. }) => Some(port),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn az_affinity_prefers_replicas_in_the_client_zone() {
+        let router = router_with_nodes(
+            ReadStrategy::AZAffinity {
+                client_az: "zone-a".to_string(),
+            },
+            vec![
+                node("primary", 1, "zone-a", 1),
+                node("remote-replica", 2, "zone-b", 1),
+                node("local-replica", 3, "zone-a", 5),
+            ],
+        );
+        assert_eq!(routed_port(&router), Some(3));
+    }
+
+    #[test]
+    fn az_affinity_falls_back_to_replicas_in_other_zones() {
+        let router = router_with_nodes(
+            ReadStrategy::AZAffinity {
+                client_az: "zone-a".to_string(),
+            },
+            vec![
+                node("primary", 1, "zone-a", 1),
+                node("remote-replica", 2, "zone-b", 1),
+                node("local-replica", 3, "zone-a", 5),
+            ],
+        );
+        // A replica without a measured latency didn't respond to the latest probe.
+        router.slots.read().unwrap()[0].nodes[2].latency.reset();
+        assert_eq!(routed_port(&router), Some(2));
+    }
+
+    fn node_value(host: &str, port: i64) -> Value {
+        Value::Array(vec![
+            Value::BulkString(host.as_bytes().to_vec()),
+            Value::Int(port),
+        ])
+    }
+
+    #[test]
+    fn parse_cluster_slots_keeps_known_latencies() {
+        let value = Value::Array(vec![Value::Array(vec![
+            Value::Int(0),
+            Value::Int(16383),
+            node_value("primary", 6379),
+            node_value("replica", 6380),
+        ])]);
+        let known_node = Arc::new(Node {
+            host: "replica".to_string(),
+            port: 6380,
+            latency: LatencyTracker::default(),
+            availability_zone: OnceLock::new(),
+        });
+        known_node
+            .latency
+            .record(std::time::Duration::from_millis(1));
+        let known_nodes = HashMap::from([(("replica".to_string(), 6380), known_node.clone())]);
+
+        let configured_zones =
+            HashMap::from([(("primary".to_string(), 6379), "zone-a".to_string())]);
+
+        let slots = parse_cluster_slots(value, known_nodes, &configured_zones);
+
+        assert_eq!(slots.len(), 1);
+        assert_eq!((slots[0].start, slots[0].end), (0, 16383));
+        let addresses: Vec<_> = slots[0]
+            .nodes
+            .iter()
+            .map(|node| (node.host.as_str(), node.port))
+            .collect();
+        assert_eq!(addresses, vec![("primary", 6379), ("replica", 6380)]);
+        assert!(Arc::ptr_eq(&slots[0].nodes[1], &known_node));
+        assert_eq!(
+            slots[0].nodes[0]
+                .availability_zone
+                .get()
+                .map(String::as_str),
+            Some("zone-a")
+        );
+    }
+}
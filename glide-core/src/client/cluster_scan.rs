@@ -0,0 +1,406 @@
+use logger_core::log_debug;
+use redis::cluster_async::ClusterConnection;
+use redis::cluster_routing::{RoutingInfo, SingleNodeRoutingInfo};
+use redis::{ErrorKind, RedisResult, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const SLOT_COUNT: usize = 16384;
+
+/// How long the state of an unfinished scan is kept after its last step. Abandoned scans are released after this time.
+const CURSOR_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// The cursor that starts a cluster scan, and that is returned once every slot was scanned.
+pub const FINISHED_SCAN_CURSOR: &str = "0";
+
+/// Filters that are passed to the SCAN command of each node.
+#[derive(Clone, Debug, Default)]
+pub struct ClusterScanArgs {
+    pub match_pattern: Option<Vec<u8>>,
+    pub count: Option<u32>,
+    pub object_type: Option<String>,
+}
+
+/// A primary and the slot ranges it served, according to `CLUSTER SLOTS`.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Primary {
+    pub(super) host: String,
+    pub(super) port: u16,
+    pub(super) slots: Vec<(u16, u16)>,
+}
+
+#[derive(Clone)]
+struct NodeScan {
+    primary: Primary,
+    cursor: u64,
+}
+
+/// The progress of a cluster scan. Keys are scanned one node at a time, and a node's slots are marked as
+/// scanned once its own SCAN cursor returns to 0.
+#[derive(Clone)]
+struct ScanState {
+    scanned_slots: Vec<u64>,
+    node_scan: Option<NodeScan>,
+}
+
+impl ScanState {
+    fn new() -> Self {
+        Self {
+            scanned_slots: vec![0; SLOT_COUNT / 64],
+            node_scan: None,
+        }
+    }
+
+    fn is_scanned(&self, slot: u16) -> bool {
+        let slot = slot as usize;
+        self.scanned_slots[slot / 64] & (1 << (slot % 64)) != 0
+    }
+
+    fn mark_scanned(&mut self, slot: u16) {
+        let slot = slot as usize;
+        self.scanned_slots[slot / 64] |= 1 << (slot % 64);
+    }
+
+    fn has_unscanned_slots(&self, primary: &Primary) -> bool {
+        primary
+            .slots
+            .iter()
+            .any(|(start, end)| (*start..=*end).any(|slot| !self.is_scanned(slot)))
+    }
+
+    /// The scan is finished once none of the primaries has unscanned slots. Slots that no primary serves are skipped.
+    fn is_finished(&self, primaries: &[Primary]) -> bool {
+        self.node_scan.is_none()
+            && !primaries
+                .iter()
+                .any(|primary| self.has_unscanned_slots(primary))
+    }
+}
+
+struct StoredScan {
+    state: ScanState,
+    stored_at: Instant,
+}
+
+#[derive(Default)]
+struct InnerScanCursors {
+    scans: HashMap<String, StoredScan>,
+    last_cursor_id: u64,
+}
+
+/// The states of a client's unfinished scans, by their cursors. A state is released once its cursor is used to continue
+/// the scan, when the cursor is released, after it wasn't used for [CURSOR_TTL], or when every clone of the client is dropped.
+#[derive(Clone, Default)]
+pub(super) struct ClusterScanCursors {
+    inner: Arc<Mutex<InnerScanCursors>>,
+}
+
+impl ClusterScanCursors {
+    fn store(&self, state: ScanState) -> String {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .scans
+            .retain(|_, scan| scan.stored_at.elapsed() < CURSOR_TTL);
+        inner.last_cursor_id += 1;
+        let cursor = inner.last_cursor_id.to_string();
+        inner.scans.insert(
+            cursor.clone(),
+            StoredScan {
+                state,
+                stored_at: Instant::now(),
+            },
+        );
+        cursor
+    }
+
+    fn get(&self, cursor: &str) -> RedisResult<ScanState> {
+        if cursor == FINISHED_SCAN_CURSOR {
+            return Ok(ScanState::new());
+        }
+        self.inner
+            .lock()
+            .unwrap()
+            .scans
+            .get(cursor)
+            .filter(|scan| scan.stored_at.elapsed() < CURSOR_TTL)
+            .map(|scan| scan.state.clone())
+            .ok_or_else(|| {
+                (
+                    ErrorKind::ClientError,
+                    "Unknown or expired cluster scan cursor",
+                    cursor.to_string(),
+                )
+                    .into()
+            })
+    }
+
+    /// Releases the state of a scan that won't be continued.
+    pub(super) fn release(&self, cursor: &str) {
+        self.inner.lock().unwrap().scans.remove(cursor);
+    }
+}
+
+/// Parses the primaries out of a `CLUSTER SLOTS` response.
+pub(super) fn parse_primaries(value: &Value) -> RedisResult<Vec<Primary>> {
+    let ranges: Vec<Vec<Value>> = redis::from_redis_value(value)?;
+    let mut primaries: Vec<Primary> = Vec::new();
+    for range in ranges {
+        let [start, end, primary, ..] = range.as_slice() else {
+            continue;
+        };
+        let slots = (
+            redis::from_redis_value(start)?,
+            redis::from_redis_value(end)?,
+        );
+        let (host, port): (String, u16) = match primary {
+            Value::Array(primary) if primary.len() >= 2 => (
+                redis::from_redis_value(&primary[0])?,
+                redis::from_redis_value(&primary[1])?,
+            ),
+            _ => continue,
+        };
+        match primaries
+            .iter_mut()
+            .find(|primary| primary.host == host && primary.port == port)
+        {
+            Some(primary) => primary.slots.push(slots),
+            None => primaries.push(Primary {
+                host,
+                port,
+                slots: vec![slots],
+            }),
+        }
+    }
+    Ok(primaries)
+}
+
+async fn get_primaries(connection: &mut ClusterConnection) -> RedisResult<Vec<Primary>> {
+    let value = connection
+        .route_command(
+            redis::cmd("CLUSTER").arg("SLOTS"),
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random),
+        )
+        .await?;
+    parse_primaries(&value)
+}
+
+fn scan_cmd(cursor: u64, args: &ClusterScanArgs) -> redis::Cmd {
+    let mut cmd = redis::cmd("SCAN");
+    cmd.arg(cursor);
+    if let Some(match_pattern) = &args.match_pattern {
+        cmd.arg("MATCH").arg(match_pattern.as_slice());
+    }
+    if let Some(count) = args.count {
+        cmd.arg("COUNT").arg(count);
+    }
+    if let Some(object_type) = &args.object_type {
+        cmd.arg("TYPE").arg(object_type);
+    }
+    cmd
+}
+
+/// Marks the slots that the node served both when its scan started and when it ended.
+/// Slots that migrated during the scan might have been missed, so they're scanned again on their current node.
+fn mark_node_scanned(state: &mut ScanState, scanned: &Primary, primaries: &[Primary]) {
+    let Some(current) = primaries
+        .iter()
+        .find(|primary| primary.host == scanned.host && primary.port == scanned.port)
+    else {
+        return;
+    };
+    for (start, end) in scanned.slots.iter() {
+        for slot in *start..=*end {
+            if current
+                .slots
+                .iter()
+                .any(|(current_start, current_end)| *current_start <= slot && slot <= *current_end)
+            {
+                state.mark_scanned(slot);
+            }
+        }
+    }
+}
+
+/// Runs a single SCAN iteration on one of the cluster's primaries, and returns an array of the next cursor and the keys that were found.
+/// Keys from slots that were already scanned are filtered out, but a key might still be returned more than once if its slot migrated during the scan.
+pub(super) async fn cluster_scan(
+    connection: &mut ClusterConnection,
+    cursors: &ClusterScanCursors,
+    cursor: &str,
+    args: &ClusterScanArgs,
+) -> RedisResult<Value> {
+    let mut state = cursors.get(cursor)?;
+    // The latest primaries, which are always known by the end of a step in which no node scan is left.
+    let mut primaries = Vec::new();
+    let node_scan = match state.node_scan.take() {
+        Some(node_scan) => Some(node_scan),
+        None => {
+            primaries = get_primaries(connection).await?;
+            primaries
+                .iter()
+                .find(|primary| state.has_unscanned_slots(primary))
+                .map(|primary| NodeScan {
+                    primary: primary.clone(),
+                    cursor: 0,
+                })
+        }
+    };
+
+    let mut keys = Vec::new();
+    if let Some(node_scan) = node_scan {
+        let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+            host: node_scan.primary.host.clone(),
+            port: node_scan.primary.port,
+        });
+        match connection
+            .route_command(&scan_cmd(node_scan.cursor, args), routing)
+            .await
+        {
+            Ok(value) => {
+                let (next_cursor, found_keys): (u64, Vec<Vec<u8>>) =
+                    redis::from_redis_value(&value)?;
+                keys = found_keys
+                    .into_iter()
+                    .filter(|key| !state.is_scanned(redis::cluster_topology::get_slot(key)))
+                    .map(Value::BulkString)
+                    .collect();
+                if next_cursor == 0 {
+                    primaries = get_primaries(connection).await?;
+                    mark_node_scanned(&mut state, &node_scan.primary, &primaries);
+                } else {
+                    state.node_scan = Some(NodeScan {
+                        cursor: next_cursor,
+                        ..node_scan
+                    });
+                }
+            }
+            Err(err) => {
+                // If the node is no longer a primary, its slots are scanned from the start on their new primaries.
+                primaries = get_primaries(connection).await?;
+                if primaries.iter().any(|primary| {
+                    primary.host == node_scan.primary.host && primary.port == node_scan.primary.port
+                }) {
+                    return Err(err);
+                }
+                log_debug(
+                    "cluster scan",
+                    format!(
+                        "{}:{} is no longer a primary, skipping it: `{err}`",
+                        node_scan.primary.host, node_scan.primary.port
+                    ),
+                );
+            }
+        }
+    }
+
+    let next_cursor = if state.is_finished(&primaries) {
+        FINISHED_SCAN_CURSOR.to_string()
+    } else {
+        cursors.store(state)
+    };
+    cursors.release(cursor);
+    Ok(Value::Array(vec![
+        Value::BulkString(next_cursor.into_bytes()),
+        Value::Array(keys),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(host: &str, port: u16) -> Value {
+        Value::Array(vec![
+            Value::BulkString(host.as_bytes().to_vec()),
+            Value::Int(port as i64),
+            Value::BulkString(b"node-id".to_vec()),
+        ])
+    }
+
+    fn range(start: i64, end: i64, nodes: Vec<Value>) -> Value {
+        let mut range = vec![Value::Int(start), Value::Int(end)];
+        range.extend(nodes);
+        Value::Array(range)
+    }
+
+    #[test]
+    fn primaries_are_grouped_by_address() {
+        let cluster_slots = Value::Array(vec![
+            range(0, 99, vec![node("a", 1), node("b", 2)]),
+            range(100, 16383, vec![node("c", 3)]),
+            range(200, 299, vec![node("a", 1)]),
+        ]);
+        assert_eq!(
+            parse_primaries(&cluster_slots).unwrap(),
+            vec![
+                Primary {
+                    host: "a".to_string(),
+                    port: 1,
+                    slots: vec![(0, 99), (200, 299)],
+                },
+                Primary {
+                    host: "c".to_string(),
+                    port: 3,
+                    slots: vec![(100, 16383)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn only_slots_that_stayed_on_the_node_are_marked_scanned() {
+        let scanned = Primary {
+            host: "a".to_string(),
+            port: 1,
+            slots: vec![(0, 99)],
+        };
+        let current = Primary {
+            slots: vec![(50, 199)],
+            ..scanned.clone()
+        };
+        let mut state = ScanState::new();
+        mark_node_scanned(&mut state, &scanned, &[current]);
+        assert!(!state.is_scanned(49));
+        assert!(state.is_scanned(50));
+        assert!(state.is_scanned(99));
+        assert!(!state.is_scanned(100));
+        assert!(!state.is_finished(&[scanned]));
+    }
+
+    #[test]
+    fn scan_is_finished_once_every_slot_is_scanned() {
+        let primary = Primary {
+            host: "a".to_string(),
+            port: 1,
+            slots: vec![(0, 16383)],
+        };
+        let mut state = ScanState::new();
+        mark_node_scanned(&mut state, &primary, &[primary.clone()]);
+        assert!(state.is_finished(&[primary.clone()]));
+        assert!(!state.has_unscanned_slots(&primary));
+    }
+
+    #[test]
+    fn scan_is_finished_when_unassigned_slots_are_left() {
+        let primary = Primary {
+            host: "a".to_string(),
+            port: 1,
+            slots: vec![(0, 99), (200, 16383)],
+        };
+        let mut state = ScanState::new();
+        mark_node_scanned(&mut state, &primary, &[primary.clone()]);
+        assert!(!state.is_scanned(100));
+        assert!(state.is_finished(&[primary]));
+    }
+
+    #[test]
+    fn released_cursors_are_unknown() {
+        let cursors = ClusterScanCursors::default();
+        let cursor = cursors.store(ScanState::new());
+        assert!(cursors.get(&cursor).is_ok());
+        cursors.release(&cursor);
+        assert!(cursors.get(&cursor).is_err());
+        assert!(cursors.get(FINISHED_SCAN_CURSOR).is_ok());
+    }
+}
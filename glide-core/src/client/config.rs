@@ -0,0 +1,407 @@
+use super::connection_uri::parse_connection_uri;
+use crate::connection_request::{
+    AuthenticationInfo, ConnectionRequest, ConnectionRetryStrategy, NodeAddress, ProtocolVersion,
+    ReadFrom, SentinelConfiguration, TlsConfiguration, TlsMode,
+};
+use protobuf::MessageField;
+use redis::{ErrorKind, RedisResult};
+use std::time::Duration;
+
+fn invalid_config(description: &'static str, detail: String) -> redis::RedisError {
+    (ErrorKind::InvalidClientConfig, description, detail).into()
+}
+
+fn validate_address(address: &NodeAddress) -> RedisResult<()> {
+    if !address.unix_socket_path.is_empty() {
+        return Ok(());
+    }
+    if address.host.is_empty() {
+        return Err(invalid_config(
+            "Address without a host",
+            format!("port {}", address.port),
+        ));
+    }
+    if address.port > u16::MAX as u32 {
+        return Err(invalid_config(
+            "Port is out of range",
+            format!("{}:{}", address.host, address.port),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks the parts of the request that would otherwise fail late, or be silently misread when connecting.
+pub fn validate_connection_request(request: &ConnectionRequest) -> RedisResult<()> {
+    let addresses = match request.sentinel_configuration.as_ref() {
+        Some(sentinel_configuration) => {
+            if sentinel_configuration.master_name.is_empty() {
+                return Err((
+                    ErrorKind::InvalidClientConfig,
+                    "Sentinel configuration requires a master name",
+                )
+                    .into());
+            }
+            if request.cluster_mode_enabled {
+                return Err((
+                    ErrorKind::InvalidClientConfig,
+                    "Sentinel configuration isn't supported in cluster mode",
+                )
+                    .into());
+            }
+            &sentinel_configuration.sentinel_addresses
+        }
+        None => &request.addresses,
+    };
+    if addresses.is_empty() {
+        return Err((
+            ErrorKind::InvalidClientConfig,
+            "At least one address is required",
+        )
+            .into());
+    }
+    addresses.iter().try_for_each(validate_address)?;
+    if request.tls_mode.enum_value_or_default() != TlsMode::NoTls {
+        if let Some(address) = addresses
+            .iter()
+            .find(|address| !address.unix_socket_path.is_empty())
+        {
+            return Err(invalid_config(
+                "TLS isn't supported for unix socket addresses",
+                address.unix_socket_path.to_string(),
+            ));
+        }
+    }
+    if let Some(tls_configuration) = request.tls_configuration.as_ref() {
+        if !tls_configuration.server_name.is_empty() {
+            if request.tls_mode.enum_value_or_default() != TlsMode::SecureTls {
+                return Err((
+                    ErrorKind::InvalidClientConfig,
+                    "A TLS server name requires secure TLS",
+                )
+                    .into());
+            }
+            if request.cluster_mode_enabled {
+                return Err((
+                    ErrorKind::InvalidClientConfig,
+                    "A TLS server name isn't supported in cluster mode",
+                )
+                    .into());
+            }
+        }
+    }
+    if let Some(info) = request.authentication_info.as_ref() {
+        // With token authentication, the password is replaced by the auth provider's tokens.
+        if !info.username.is_empty() && info.password.is_empty() && !info.token_auth {
+            return Err((
+                ErrorKind::InvalidClientConfig,
+                "A username requires a password",
+            )
+                .into());
+        }
+    }
+    Ok(())
+}
+
+/// A validated client configuration, which converts into a [ConnectionRequest].
+#[derive(Clone, Debug)]
+pub struct ClientConfig {
+    request: ConnectionRequest,
+}
+
+impl ClientConfig {
+    pub fn builder() -> ClientConfigBuilder {
+        ClientConfigBuilder::default()
+    }
+
+    /// Returns a builder that starts from the settings of a `redis://` or `rediss://` URI. See [parse_connection_uri] for the format.
+    pub fn builder_from_uri(uri: &str) -> RedisResult<ClientConfigBuilder> {
+        Ok(ClientConfigBuilder {
+            request: parse_connection_uri(uri)?,
+            request_timeout: None,
+        })
+    }
+}
+
+impl From<ClientConfig> for ConnectionRequest {
+    fn from(config: ClientConfig) -> Self {
+        config.request
+    }
+}
+
+fn node_address(host: String, port: u16) -> NodeAddress {
+    NodeAddress {
+        host: host.into(),
+        port: port as u32,
+        ..Default::default()
+    }
+}
+
+/// Builds a [ClientConfig]. Settings that aren't set keep the defaults of [ConnectionRequest].
+#[derive(Clone, Debug, Default)]
+pub struct ClientConfigBuilder {
+    request: ConnectionRequest,
+    request_timeout: Option<Duration>,
+}
+
+impl ClientConfigBuilder {
+    /// Adds a node to connect to. In cluster mode, the rest of the cluster is discovered through these nodes.
+    pub fn address(mut self, host: impl Into<String>, port: u16) -> Self {
+        self.request.addresses.push(node_address(host.into(), port));
+        self
+    }
+
+    /// Adds a node that is reached through a unix domain socket. Not supported in cluster mode, or with TLS.
+    pub fn unix_socket(mut self, path: impl Into<String>) -> Self {
+        self.request.addresses.push(NodeAddress {
+            unix_socket_path: path.into().into(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Discovers the primary and replicas of `master_name` through the given sentinels, instead of connecting to the added addresses.
+    pub fn sentinel(
+        mut self,
+        master_name: impl Into<String>,
+        sentinels: impl IntoIterator<Item = (String, u16)>,
+    ) -> Self {
+        self.request.sentinel_configuration = MessageField::some(SentinelConfiguration {
+            sentinel_addresses: sentinels
+                .into_iter()
+                .map(|(host, port)| node_address(host, port))
+                .collect(),
+            master_name: master_name.into().into(),
+            ..Default::default()
+        });
+        self
+    }
+
+    pub fn cluster_mode(mut self, cluster_mode_enabled: bool) -> Self {
+        self.request.cluster_mode_enabled = cluster_mode_enabled;
+        self
+    }
+
+    pub fn tls_mode(mut self, tls_mode: TlsMode) -> Self {
+        self.request.tls_mode = tls_mode.into();
+        self
+    }
+
+    /// Sets the certificates that verify the server's certificate, instead of the system's root certificates. Certificates are PEM-encoded.
+    pub fn root_certs(mut self, root_certs: Vec<u8>) -> Self {
+        self.request
+            .tls_configuration
+            .mut_or_insert_default()
+            .root_certs = root_certs.into();
+        self
+    }
+
+    /// Sets the PEM-encoded certificate and private key that the client presents to servers that require mutual TLS.
+    pub fn client_certificate(mut self, client_cert: Vec<u8>, client_key: Vec<u8>) -> Self {
+        let tls_configuration: &mut TlsConfiguration =
+            self.request.tls_configuration.mut_or_insert_default();
+        tls_configuration.client_cert = client_cert.into();
+        tls_configuration.client_key = client_key.into();
+        self
+    }
+
+    /// Sets the name that is sent as the TLS server name (SNI) and that the server's certificate is verified against, instead
+    /// of the host of each address. Requires [TlsMode::SecureTls], and isn't supported in cluster mode.
+    pub fn server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.request
+            .tls_configuration
+            .mut_or_insert_default()
+            .server_name = server_name.into().into();
+        self
+    }
+
+    /// Sets how long a request may take, including reconnections and retries. Must fit in `u32` milliseconds.
+    pub fn request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = Some(request_timeout);
+        self
+    }
+
+    pub fn read_from(mut self, read_from: ReadFrom) -> Self {
+        self.request.read_from = read_from.into();
+        self
+    }
+
+    /// Sets the client's availability zone, used by [ReadFrom::AZAffinity].
+    pub fn client_az(mut self, client_az: impl Into<String>) -> Self {
+        self.request.client_az = client_az.into().into();
+        self
+    }
+
+    /// Sets the password, and optionally the username, that connections authenticate with.
+    pub fn credentials(mut self, username: Option<String>, password: impl Into<String>) -> Self {
+        self.request.authentication_info = MessageField::some(AuthenticationInfo {
+            username: username.unwrap_or_default().into(),
+            password: password.into().into(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Authenticates with tokens from an auth provider, optionally as `username`, instead of a fixed password.
+    /// The built config must be passed to [crate::client::Client::new_with_auth_provider].
+    pub fn token_authentication(mut self, username: Option<String>) -> Self {
+        self.request.authentication_info = MessageField::some(AuthenticationInfo {
+            username: username.unwrap_or_default().into(),
+            token_auth: true,
+            ..Default::default()
+        });
+        self
+    }
+
+    pub fn database_id(mut self, database_id: u32) -> Self {
+        self.request.database_id = database_id;
+        self
+    }
+
+    pub fn protocol(mut self, protocol: ProtocolVersion) -> Self {
+        self.request.protocol = protocol.into();
+        self
+    }
+
+    /// Sets the name that connections set with CLIENT SETNAME.
+    pub fn client_name(mut self, client_name: impl Into<String>) -> Self {
+        self.request.client_name = client_name.into().into();
+        self
+    }
+
+    /// Sets the exponential backoff between reconnection attempts.
+    pub fn connection_retry_strategy(
+        mut self,
+        number_of_retries: u32,
+        factor: u32,
+        exponent_base: u32,
+    ) -> Self {
+        self.request.connection_retry_strategy = MessageField::some(ConnectionRetryStrategy {
+            number_of_retries,
+            factor,
+            exponent_base,
+            ..Default::default()
+        });
+        self
+    }
+
+    pub fn build(self) -> RedisResult<ClientConfig> {
+        let mut request = self.request;
+        if let Some(request_timeout) = self.request_timeout {
+            request.request_timeout = u32::try_from(request_timeout.as_millis())
+                .ok()
+                .filter(|request_timeout| *request_timeout > 0)
+                .ok_or_else(|| {
+                    invalid_config(
+                        "Request timeout must be between 1 and u32::MAX milliseconds",
+                        format!("{request_timeout:?}"),
+                    )
+                })?;
+        }
+        validate_connection_request(&request)?;
+        Ok(ClientConfig { request })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_converts_into_connection_request() {
+        let request: ConnectionRequest = ClientConfig::builder()
+            .address("localhost", 6379)
+            .cluster_mode(true)
+            .tls_mode(TlsMode::SecureTls)
+            .request_timeout(Duration::from_secs(1))
+            .read_from(ReadFrom::PreferReplica)
+            .credentials(Some("user".to_string()), "password")
+            .client_name("name")
+            .build()
+            .unwrap()
+            .into();
+        assert_eq!(request.addresses.len(), 1);
+        assert_eq!(request.addresses[0].host.to_string(), "localhost");
+        assert_eq!(request.addresses[0].port, 6379);
+        assert!(request.cluster_mode_enabled);
+        assert_eq!(request.tls_mode.enum_value(), Ok(TlsMode::SecureTls));
+        assert_eq!(request.request_timeout, 1000);
+        assert_eq!(request.read_from.enum_value(), Ok(ReadFrom::PreferReplica));
+        assert_eq!(request.authentication_info.username.to_string(), "user");
+        assert_eq!(request.authentication_info.password.to_string(), "password");
+        assert_eq!(request.client_name.to_string(), "name");
+    }
+
+    #[test]
+    fn builder_requires_an_address() {
+        let err = ClientConfig::builder().build().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidClientConfig);
+    }
+
+    #[test]
+    fn sentinels_replace_the_addresses() {
+        assert!(ClientConfig::builder()
+            .sentinel("mymaster", [("localhost".to_string(), 26379)])
+            .build()
+            .is_ok());
+        assert!(ClientConfig::builder()
+            .sentinel("", [("localhost".to_string(), 26379)])
+            .build()
+            .is_err());
+        assert!(ClientConfig::builder()
+            .sentinel("mymaster", [("localhost".to_string(), 26379)])
+            .cluster_mode(true)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn out_of_range_values_are_rejected() {
+        let mut request = ConnectionRequest::new();
+        request.addresses.push(NodeAddress {
+            host: "localhost".into(),
+            port: 65536,
+            ..Default::default()
+        });
+        assert!(validate_connection_request(&request).is_err());
+
+        assert!(ClientConfig::builder()
+            .address("localhost", 6379)
+            .request_timeout(Duration::from_secs(u64::MAX))
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn unix_sockets_are_rejected_with_tls() {
+        let err = ClientConfig::builder()
+            .unix_socket("/tmp/redis.sock")
+            .tls_mode(TlsMode::SecureTls)
+            .build()
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidClientConfig);
+
+        assert!(ClientConfig::builder()
+            .unix_socket("/tmp/redis.sock")
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn server_name_requires_secure_standalone_tls() {
+        let builder = ClientConfig::builder()
+            .address("10.0.0.1", 6379)
+            .server_name("redis.example.com");
+        assert!(builder.clone().tls_mode(TlsMode::SecureTls).build().is_ok());
+        assert!(builder
+            .clone()
+            .tls_mode(TlsMode::InsecureTls)
+            .build()
+            .is_err());
+        assert!(builder.clone().build().is_err());
+        assert!(builder
+            .tls_mode(TlsMode::SecureTls)
+            .cluster_mode(true)
+            .build()
+            .is_err());
+    }
+}
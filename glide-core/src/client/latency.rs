@@ -0,0 +1,66 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// The weight of a new sample in the moving average. Higher values react faster to latency changes, but are noisier.
+const SMOOTHING_FACTOR: f64 = 0.2;
+
+/// Tracks an exponentially weighted moving average of a node's round-trip time.
+#[derive(Default, Debug)]
+pub(super) struct LatencyTracker {
+    /// The average in microseconds. 0 means that no sample was recorded since the last reset.
+    average_micros: AtomicU64,
+}
+
+impl LatencyTracker {
+    pub(super) fn record(&self, round_trip_time: Duration) {
+        // A 0 average is reserved for "unknown", so samples are at least 1 microsecond.
+        let sample = (round_trip_time.as_micros() as u64).max(1);
+        let _ = self
+            .average_micros
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |average| {
+                if average == 0 {
+                    return Some(sample);
+                }
+                let new_average =
+                    SMOOTHING_FACTOR * sample as f64 + (1.0 - SMOOTHING_FACTOR) * average as f64;
+                Some((new_average.round() as u64).max(1))
+            });
+    }
+
+    /// Forget the recorded average, for example after the node stopped responding.
+    pub(super) fn reset(&self) {
+        self.average_micros.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns the average round-trip time, or `None` if no sample was recorded.
+    pub(super) fn average(&self) -> Option<Duration> {
+        match self.average_micros.load(Ordering::Relaxed) {
+            0 => None,
+            average => Some(Duration::from_micros(average)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_sample_sets_the_average() {
+        let tracker = LatencyTracker::default();
+        assert_eq!(tracker.average(), None);
+        tracker.record(Duration::from_millis(10));
+        assert_eq!(tracker.average(), Some(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn average_moves_towards_new_samples() {
+        let tracker = LatencyTracker::default();
+        tracker.record(Duration::from_millis(10));
+        tracker.record(Duration::from_millis(20));
+        assert_eq!(tracker.average(), Some(Duration::from_millis(12)));
+
+        tracker.reset();
+        assert_eq!(tracker.average(), None);
+    }
+}
@@ -1,22 +1,71 @@
 use crate::connection_request::{
     ConnectionRequest, NodeAddress, ProtocolVersion, ReadFrom, TlsMode,
 };
+use crate::retry_strategies::RetryStrategy;
 use crate::scripts_container::get_script;
-use futures::FutureExt;
-use logger_core::log_info;
-use redis::cluster_async::ClusterConnection;
-use redis::cluster_routing::{RoutingInfo, SingleNodeRoutingInfo};
+pub use auth_provider::{AuthProvider, AuthToken};
+pub use cluster_scan::{ClusterScanArgs, FINISHED_SCAN_CURSOR};
+pub use config::{validate_connection_request, ClientConfig, ClientConfigBuilder};
+pub use connection_uri::parse_connection_uri;
+use futures::future::BoxFuture;
+use futures::{future, FutureExt};
+use logger_core::{log_info, log_warn};
+pub use multi_node::NodeResults;
+use redis::cluster_routing::{
+    MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
+};
 use redis::RedisResult;
-use redis::{Cmd, ErrorKind, Value};
-pub use standalone_client::StandaloneClient;
+use redis::{Cmd, ErrorKind, PushInfo, Value};
+#[cfg(feature = "otel-tracing")]
+pub use request_tracing::otlp_layer;
+pub(crate) use request_tracing::RequestSpan;
+pub use sentinel_client::SentinelClient;
+pub use standalone_client::{StandaloneClient, StandaloneClientConnectionError};
+use statistics::ClientStatistics;
+pub(crate) use statistics::InFlightRequests;
+pub use statistics::{
+    get_statistics, LatencyHistogram, StatisticsSnapshot, LATENCY_BUCKET_BOUNDS_MICROS,
+    OTHER_COMMAND_NAME,
+};
 use std::io;
-use std::ops::Deref;
-use std::time::Duration;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+pub use watch_session::WatchSession;
 
+use self::auth_provider::{is_token_rejection, needs_new_token, ClusterTokenRefresher};
+use self::blocking_commands::{
+    blocks_connection, get_blocking_timeout, is_blocking_cmd, BlockingTimeout,
+};
+use self::blocking_connections::{parse_redirect_address, BlockingConnections, MAX_REDIRECTIONS};
+use self::cluster_connection::SharedClusterConnection;
+use self::cluster_nodes::{ClusterNodes, ClusterTopology};
+use self::cluster_read_router::{ClusterReadRouter, ConfiguredZones, ReadStrategy};
+use self::cluster_scan::ClusterScanCursors;
+use self::connection_uri::apply_connection_uri;
+use self::pubsub::{is_subscription_cmd, PubSubConnection};
+use self::tls_server_name::{NodeTlsParams, ServerNameConnector};
 use self::value_conversion::{convert_to_expected_type, expected_type_for_cmd};
+mod auth_provider;
+mod blocking_commands;
+mod blocking_connections;
+mod cluster_connection;
+mod cluster_nodes;
+mod cluster_read_router;
+mod cluster_scan;
+mod config;
+mod connection_uri;
+mod latency;
+mod multi_node;
+mod pubsub;
 mod reconnecting_connection;
+mod request_tracing;
+mod sentinel_client;
 mod standalone_client;
+mod statistics;
+mod tls_server_name;
 mod value_conversion;
+mod watch_session;
 
 pub const HEARTBEAT_SLEEP_DURATION: Duration = Duration::from_secs(1);
 
@@ -24,6 +73,9 @@ pub const DEFAULT_RESPONSE_TIMEOUT: Duration = Duration::from_millis(250);
 pub const DEFAULT_CONNECTION_ATTEMPT_TIMEOUT: Duration = Duration::from_millis(250);
 pub const INTERNAL_CONNECTION_TIMEOUT: Duration = Duration::from_millis(250);
 
+/// The code of the error that is returned when a transaction wasn't executed, because a watched key was modified.
+pub const WATCH_ABORTED_ERROR_CODE: &str = "WATCHABORTED";
+
 pub(super) fn get_port(address: &NodeAddress) -> u16 {
     const DEFAULT_PORT: u16 = 6379;
     if address.port == 0 {
@@ -41,6 +93,15 @@ fn chars_to_string_option(chars: &::protobuf::Chars) -> Option<String> {
     }
 }
 
+/// Returns the availability zone that a node reports in its `INFO SERVER` response, if it reports one.
+pub(super) fn get_availability_zone_from_info(info: &Value) -> Option<String> {
+    let info: String = redis::from_redis_value(info).ok()?;
+    info.lines()
+        .find_map(|line| line.strip_prefix("availability_zone:"))
+        .map(|zone| zone.trim().to_string())
+        .filter(|zone| !zone.is_empty())
+}
+
 pub fn convert_to_redis_protocol(protocol: ProtocolVersion) -> redis::ProtocolVersion {
     match protocol {
         ProtocolVersion::RESP3 => redis::ProtocolVersion::RESP3,
@@ -69,17 +130,152 @@ pub(super) fn get_redis_connection_info(
     }
 }
 
+/// Returns an AUTH command. Without a username, the connection authenticates as the default user.
+pub(super) fn auth_cmd(username: Option<&str>, password: &str) -> Cmd {
+    let mut cmd = redis::cmd("AUTH");
+    if let Some(username) = username {
+        cmd.arg(username);
+    }
+    cmd.arg(password);
+    cmd
+}
+
+/// Returns the custom certificates from the request's TLS configuration, or `None` if TLS is disabled or no certificates were set.
+fn get_tls_certificates(
+    request: &ConnectionRequest,
+) -> RedisResult<Option<redis::TlsCertificates>> {
+    if request.tls_mode.enum_value_or_default() == TlsMode::NoTls {
+        return Ok(None);
+    }
+    let Some(tls_configuration) = request.tls_configuration.as_ref() else {
+        return Ok(None);
+    };
+    let client_tls = match (
+        tls_configuration.client_cert.is_empty(),
+        tls_configuration.client_key.is_empty(),
+    ) {
+        (true, true) => None,
+        (false, false) => Some(redis::ClientTlsConfig {
+            client_cert: tls_configuration.client_cert.to_vec(),
+            client_key: tls_configuration.client_key.to_vec(),
+        }),
+        _ => {
+            return Err((
+                ErrorKind::InvalidClientConfig,
+                "Mutual TLS requires both a client certificate and a client key",
+            )
+                .into())
+        }
+    };
+    let root_cert =
+        (!tls_configuration.root_certs.is_empty()).then(|| tls_configuration.root_certs.to_vec());
+    if client_tls.is_none() && root_cert.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(redis::TlsCertificates {
+        client_tls,
+        root_cert,
+    }))
+}
+
+/// Returns the TLS parameters of connections to the request's nodes, or `None` if the default parameters should be used.
+/// The server name override isn't included, since it's only supported by [get_node_tls_params].
+pub(super) fn get_tls_params(
+    request: &ConnectionRequest,
+) -> RedisResult<Option<redis::TlsConnParams>> {
+    get_tls_certificates(request)?
+        .map(redis::retrieve_tls_certificates)
+        .transpose()
+}
+
+/// Returns the TLS parameters of connections to the nodes of standalone and sentinel clients, including the server name
+/// override of the request's TLS configuration.
+pub(super) fn get_node_tls_params(request: &ConnectionRequest) -> RedisResult<NodeTlsParams> {
+    let certificates = get_tls_certificates(request)?;
+    let server_name = match request.tls_configuration.as_ref() {
+        Some(tls_configuration)
+            if request.tls_mode.enum_value_or_default() != TlsMode::NoTls
+                && !tls_configuration.server_name.is_empty() =>
+        {
+            Some(ServerNameConnector::new(
+                &tls_configuration.server_name,
+                certificates.as_ref(),
+            )?)
+        }
+        _ => None,
+    };
+    Ok(NodeTlsParams {
+        params: certificates
+            .map(redis::retrieve_tls_certificates)
+            .transpose()?,
+        server_name,
+    })
+}
+
+/// Sends the command on the cluster connection. With token authentication, a failure that means that a node needs a new
+/// token gets the cluster's connections a new one from the auth provider. If the node rejected the token, it didn't run the
+/// command, so the command is retried on the new connections.
+async fn route_cluster_command(
+    client: &SharedClusterConnection,
+    token_refresher: &Option<ClusterTokenRefresher>,
+    cmd: &Cmd,
+    routing: RoutingInfo,
+) -> RedisResult<Value> {
+    let result = client.get().route_command(cmd, routing.clone()).await;
+    let (Err(err), Some(token_refresher)) = (&result, token_refresher) else {
+        return result;
+    };
+    if !needs_new_token(err) {
+        return result;
+    }
+    if let Err(refresh_err) = token_refresher.refresh().await {
+        log_warn(
+            "token refresh",
+            format!("failed to refresh the cluster's token: `{refresh_err}`"),
+        );
+        return result;
+    }
+    if is_token_rejection(err) {
+        request_tracing::record_retry();
+        client.get().route_command(cmd, routing).await
+    } else {
+        result
+    }
+}
+
+/// Returns the address of a request that's routed by address, as "host:port".
+fn get_routed_address(routing: &RoutingInfo) -> Option<String> {
+    match routing {
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port }) => {
+            Some(format!("{host}:{port}"))
+        }
+        _ => None,
+    }
+}
+
+/// Returns a readable description of the address, for logs and errors.
+pub(super) fn format_address(address: &NodeAddress) -> String {
+    if address.unix_socket_path.is_empty() {
+        format!("{}:{}", address.host, get_port(address))
+    } else {
+        address.unix_socket_path.to_string()
+    }
+}
+
 pub(super) fn get_connection_info(
     address: &NodeAddress,
     tls_mode: TlsMode,
+    tls_params: Option<redis::TlsConnParams>,
     redis_connection_info: redis::RedisConnectionInfo,
 ) -> redis::ConnectionInfo {
-    let addr = if tls_mode != TlsMode::NoTls {
+    let addr = if !address.unix_socket_path.is_empty() {
+        redis::ConnectionAddr::Unix(address.unix_socket_path.to_string().into())
+    } else if tls_mode != TlsMode::NoTls {
         redis::ConnectionAddr::TcpTls {
             host: address.host.to_string(),
             port: get_port(address),
             insecure: tls_mode == TlsMode::InsecureTls,
-            tls_params: None,
+            tls_params,
         }
     } else {
         redis::ConnectionAddr::Tcp(address.host.to_string(), get_port(address))
@@ -90,16 +286,112 @@ pub(super) fn get_connection_info(
     }
 }
 
+/// The settings of connections that the client opens to cluster nodes by itself, outside of the cluster connection.
+#[derive(Clone)]
+pub(super) struct ClusterNodeSettings {
+    tls_mode: TlsMode,
+    tls_params: Option<redis::TlsConnParams>,
+    /// Holds the current password, which is replaced when the password is updated.
+    redis_connection_info: Arc<RwLock<redis::RedisConnectionInfo>>,
+}
+
+impl ClusterNodeSettings {
+    fn new(request: &ConnectionRequest) -> RedisResult<Self> {
+        Ok(Self {
+            tls_mode: request.tls_mode.enum_value_or_default(),
+            tls_params: get_tls_params(request)?,
+            redis_connection_info: Arc::new(RwLock::new(get_redis_connection_info(request))),
+        })
+    }
+
+    /// The username that the connections authenticate with, which is needed to re-authenticate them with a new password.
+    pub(super) fn username(&self) -> Option<String> {
+        self.redis_connection_info.read().unwrap().username.clone()
+    }
+
+    fn set_password(&self, password: Option<String>) {
+        self.redis_connection_info.write().unwrap().password = password;
+    }
+
+    pub(super) fn connection_info(&self, host: &str, port: u16) -> redis::ConnectionInfo {
+        let address = NodeAddress {
+            host: host.to_string().into(),
+            port: port as u32,
+            ..Default::default()
+        };
+        get_connection_info(
+            &address,
+            self.tls_mode,
+            self.tls_params.clone(),
+            self.redis_connection_info.read().unwrap().clone(),
+        )
+    }
+}
+
 #[derive(Clone)]
 pub enum ClientWrapper {
     Standalone(StandaloneClient),
-    Cluster { client: ClusterConnection },
+    Cluster {
+        client: SharedClusterConnection,
+        /// Set when read-only commands should be routed to the node with the lowest latency.
+        read_router: Option<ClusterReadRouter>,
+        /// Used to re-authenticate the connections with a new password, and to open connections to specific nodes.
+        node_settings: ClusterNodeSettings,
+        /// Set when the connections authenticate with tokens from an auth provider.
+        token_refresher: Option<ClusterTokenRefresher>,
+        /// The states of the client's unfinished cluster scans.
+        scan_cursors: ClusterScanCursors,
+        /// The cluster's nodes, for requests that are grouped or validated by node.
+        topology: ClusterTopology,
+    },
+    Sentinel(SentinelClient),
 }
 
 #[derive(Clone)]
 pub struct Client {
     internal_client: ClientWrapper,
     request_timeout: Duration,
+    /// Set for a single request. Unlike the client's request timeout, it isn't extended for blocking commands.
+    request_timeout_override: Option<Duration>,
+    pubsub: Option<PubSubConnection>,
+    blocking_connections: BlockingConnections,
+    statistics: ClientStatistics,
+}
+
+/// Where a command of a batch is sent.
+enum BatchTarget {
+    /// Pipelined with the batch's other commands that are sent to the same node.
+    Pipelined(SingleNodeRoutingInfo),
+    /// Sent on its own, for commands that aren't sent to a single node.
+    Alone,
+}
+
+/// Returns an equivalent error, for requests that share the error of a single request, since errors can't be cloned.
+fn copy_error(err: &redis::RedisError) -> redis::RedisError {
+    if err.is_io_error() {
+        let kind = if err.is_timeout() {
+            io::ErrorKind::TimedOut
+        } else if err.is_connection_refusal() {
+            io::ErrorKind::ConnectionRefused
+        } else if err.is_connection_dropped() {
+            io::ErrorKind::BrokenPipe
+        } else {
+            io::ErrorKind::Other
+        };
+        return io::Error::new(kind, err.to_string()).into();
+    }
+    match (err.kind(), err.code()) {
+        (ErrorKind::ExtensionError, Some(code)) => {
+            redis::make_extension_error(code.to_string(), err.detail().map(str::to_string))
+        }
+        (kind, Some(_)) => (
+            kind,
+            "An error was signalled by the server",
+            err.detail().unwrap_or_default().to_string(),
+        )
+            .into(),
+        (kind, None) => (kind, "The request failed", err.to_string()).into(),
+    }
 }
 
 async fn run_with_timeout<T>(
@@ -112,27 +404,274 @@ async fn run_with_timeout<T>(
         .and_then(|res| res)
 }
 
+/// Runs the future like [run_with_timeout], or without a deadline if `timeout` is `None`.
+async fn run_with_optional_timeout<T>(
+    timeout: Option<Duration>,
+    future: impl futures::Future<Output = RedisResult<T>> + Send,
+) -> redis::RedisResult<T> {
+    match timeout {
+        Some(timeout) => run_with_timeout(timeout, future).await,
+        None => future.await,
+    }
+}
+
 impl Client {
+    /// Returns a snapshot of this client's request counts, latencies, and connection events, where [get_statistics] covers
+    /// every client in the process. Clones of the client, including those from [Client::with_request_timeout], share it.
+    /// The cluster connection recreates its connections by itself, so a cluster client doesn't count disconnects and reconnects.
+    pub fn statistics(&self) -> StatisticsSnapshot {
+        let mut snapshot = self.statistics.snapshot();
+        match self.internal_client {
+            ClientWrapper::Standalone(ref client) => client.add_connection_events(&mut snapshot),
+            ClientWrapper::Sentinel(ref client) => client.add_connection_events(&mut snapshot),
+            ClientWrapper::Cluster { .. } => {}
+        }
+        if let Some(pubsub) = &self.pubsub {
+            pubsub.add_connection_events(&mut snapshot);
+        }
+        snapshot
+    }
+
+    /// Returns a client that shares this client's connections, but uses a different request timeout.
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout_override = Some(request_timeout);
+        self
+    }
+
+    /// Returns the client-side deadline of the command, or `None` if it has none.
+    /// Blocking commands get the time they may block on the server on top of the request timeout, and commands that
+    /// block indefinitely have no deadline, since they're sent on a connection of their own.
+    fn get_request_timeout(&self, cmd: &Cmd) -> Option<Duration> {
+        if let Some(request_timeout) = self.request_timeout_override {
+            return Some(request_timeout);
+        }
+        match get_blocking_timeout(cmd) {
+            None => Some(self.request_timeout),
+            Some(BlockingTimeout::Indefinite) => None,
+            Some(BlockingTimeout::Finite(blocking_timeout)) => {
+                Some(blocking_timeout + self.request_timeout)
+            }
+        }
+    }
+
+    /// Sends a command that blocks on the server on one of the pooled connections of its node, so that it doesn't hold up
+    /// the shared connection's later commands. In cluster mode, the command is sent to its slot's primary, and MOVED and
+    /// ASK redirections are followed. Returns the result and the address of the node that served it, or `None` if the
+    /// command's node isn't known, or if it's routed explicitly, in which case the command is sent on the shared connection.
+    async fn send_blocking_command(
+        &self,
+        cmd: &Cmd,
+        routing: &Option<RoutingInfo>,
+    ) -> Option<(RedisResult<Value>, String)> {
+        if routing.is_some() {
+            return None;
+        }
+        match self.internal_client {
+            ClientWrapper::Standalone(ref client) => {
+                let address = client.primary_address();
+                let connect = || client.get_dedicated_primary_connection();
+                let result = self
+                    .blocking_connections
+                    .send(&address, connect, cmd, false)
+                    .await;
+                Some((result, address))
+            }
+
+            ClientWrapper::Cluster {
+                ref node_settings,
+                ref topology,
+                ..
+            } => {
+                let Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) =
+                    RoutingInfo::for_routable(cmd)
+                else {
+                    return None;
+                };
+                let (mut host, mut port) = topology.slot_primary(route.slot())?;
+                let mut asking = false;
+                let mut redirections = 0;
+                loop {
+                    let address = format!("{host}:{port}");
+                    let connect = || async {
+                        redis::Client::open(node_settings.connection_info(&host, port))?
+                            .get_multiplexed_async_connection()
+                            .await
+                    };
+                    let result = self
+                        .blocking_connections
+                        .send(&address, connect, cmd, asking)
+                        .await;
+                    let redirect = match &result {
+                        Err(err)
+                            if redirections < MAX_REDIRECTIONS
+                                && matches!(err.kind(), ErrorKind::Moved | ErrorKind::Ask) =>
+                        {
+                            err.redirect_node()
+                                .and_then(|(address, _)| parse_redirect_address(address))
+                                .map(|node| (node, err.kind() == ErrorKind::Ask))
+                        }
+                        _ => None,
+                    };
+                    let Some(((redirect_host, redirect_port), is_ask)) = redirect else {
+                        return Some((result, address));
+                    };
+                    request_tracing::record_redirect();
+                    self.statistics.record_redirect();
+                    (host, port, asking) = (redirect_host, redirect_port, is_ask);
+                    redirections += 1;
+                }
+            }
+
+            ClientWrapper::Sentinel(ref client) => {
+                let address = client.primary_address();
+                let connect = || client.get_dedicated_primary_connection();
+                let result = self
+                    .blocking_connections
+                    .send(&address, connect, cmd, false)
+                    .await;
+                Some((result, address))
+            }
+        }
+    }
+
     pub fn send_command<'a>(
         &'a mut self,
         cmd: &'a Cmd,
         routing: Option<RoutingInfo>,
     ) -> redis::RedisFuture<'a, Value> {
+        self.send_command_with_node(cmd, routing)
+            .map(|(result, _)| result)
+            .boxed()
+    }
+
+    /// Sends the command like [Client::send_command], and also returns the address of the node that served it as
+    /// "host:port", when it's known. The node is known for requests routed by address, and for requests for a slot's
+    /// primary in cluster mode.
+    pub async fn send_command_with_node(
+        &mut self,
+        cmd: &Cmd,
+        routing: Option<RoutingInfo>,
+    ) -> (RedisResult<Value>, Option<String>) {
+        let mut serving_node = None;
         let expected_type = expected_type_for_cmd(cmd);
-        run_with_timeout(self.request_timeout, async {
+        let request_timeout = self.get_request_timeout(cmd);
+        let statistics = self.statistics.clone();
+        statistics.record_request(cmd);
+        let span = RequestSpan::command(cmd);
+        let outcome_span = span.clone();
+        let future = async {
+            if is_subscription_cmd(cmd) {
+                return self.send_subscription_command(cmd).await;
+            }
+            if blocks_connection(cmd) {
+                if let Some((result, node)) = self.send_blocking_command(cmd, &routing).await {
+                    serving_node = Some(node);
+                    return result.and_then(|value| convert_to_expected_type(value, expected_type));
+                }
+            }
             match self.internal_client {
-                ClientWrapper::Standalone(ref mut client) => client.send_command(cmd).await,
+                ClientWrapper::Standalone(ref mut client) => match routing {
+                    Some(routing) => {
+                        serving_node = get_routed_address(&routing);
+                        client.route_command(cmd, routing).await
+                    }
+                    None => client.send_command(cmd).await,
+                },
 
-                ClientWrapper::Cluster { ref mut client } => {
-                    let routing = routing
+                ClientWrapper::Cluster {
+                    ref client,
+                    ref read_router,
+                    ref topology,
+                    ref token_refresher,
+                    ..
+                } => {
+                    let routed_by_address = matches!(
+                        routing,
+                        Some(RoutingInfo::SingleNode(
+                            SingleNodeRoutingInfo::ByAddress { .. }
+                        ))
+                    );
+                    let mut routing = routing
                         .or_else(|| RoutingInfo::for_routable(cmd))
                         .unwrap_or(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random));
-                    client.route_command(cmd, routing).await
+                    if let Some(read_router) = read_router {
+                        routing = read_router.route_read(routing);
+                    }
+                    // Requests for a slot's primary are attributed to the primary in the cached topology.
+                    // Random nodes and replicas are picked by the cluster connection, so their node isn't known.
+                    let node = match &routing {
+                        RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+                            host,
+                            port,
+                        }) => Some((host.clone(), *port)),
+                        RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))
+                            if matches!(route.slot_addr(), SlotAddr::Master) =>
+                        {
+                            topology.slot_primary(route.slot())
+                        }
+                        _ => None,
+                    };
+                    let Some((host, port)) = node else {
+                        return route_cluster_command(client, token_refresher, cmd, routing).await;
+                    };
+                    let node = format!("{host}:{port}");
+                    serving_node = Some(node.clone());
+                    request_tracing::record_node(&node);
+                    let start = Instant::now();
+                    let result = route_cluster_command(client, token_refresher, cmd, routing).await;
+                    if result.is_ok() && !is_blocking_cmd(cmd) {
+                        self.statistics.record_node_latency(&node, start.elapsed());
+                    }
+                    match result {
+                        // The cached topology is only consulted after a failure, so that nodes that joined since
+                        // it was refreshed can still be reached.
+                        Err(_) if routed_by_address && !topology.contains(&host, port) => Err((
+                            ErrorKind::ClientError,
+                            "The address isn't part of the cluster's topology",
+                            node,
+                        )
+                            .into()),
+                        result => result,
+                    }
                 }
+
+                ClientWrapper::Sentinel(ref mut client) => match routing {
+                    Some(routing) => {
+                        serving_node = get_routed_address(&routing);
+                        client.route_command(cmd, routing).await
+                    }
+                    None => client.send_command(cmd).await,
+                },
             }
             .and_then(|value| convert_to_expected_type(value, expected_type))
-        })
-        .boxed()
+        };
+        let result = span
+            .instrument(async move {
+                let result = run_with_optional_timeout(request_timeout, future).await;
+                // Only redirections that the cluster connection didn't follow by itself reach the client.
+                if let Err(err) = &result {
+                    if matches!(err.kind(), ErrorKind::Moved | ErrorKind::Ask) {
+                        request_tracing::record_redirect();
+                        statistics.record_redirect();
+                    }
+                }
+                statistics.record_result(&result);
+                outcome_span.record_outcome(&result);
+                result
+            })
+            .await;
+        (result, serving_node)
+    }
+
+    async fn send_subscription_command(&self, cmd: &Cmd) -> RedisResult<Value> {
+        let Some(pubsub) = &self.pubsub else {
+            return Err((
+                ErrorKind::InvalidClientConfig,
+                "Pub/Sub requires a RESP3 client that was created with a push notifications sender",
+            )
+                .into());
+        };
+        pubsub.send_subscription_command(cmd).await
     }
 
     fn get_transaction_values(
@@ -146,7 +685,10 @@ impl Client {
         let values = match value {
             Some(Value::Array(values)) => values,
             Some(Value::Nil) => {
-                return Ok(Value::Nil);
+                return Err(redis::make_extension_error(
+                    WATCH_ABORTED_ERROR_CODE.to_string(),
+                    Some("The transaction was aborted, because a watched key was modified".into()),
+                ));
             }
             Some(value) => {
                 if offset == 2 {
@@ -197,28 +739,442 @@ impl Client {
     ) -> redis::RedisFuture<'a, Value> {
         let command_count = pipeline.cmd_iter().count();
         let offset = command_count + 1;
-        run_with_timeout(self.request_timeout, async move {
+        // Blocking commands don't block inside a transaction, so the deadline isn't extended.
+        let request_timeout = self
+            .request_timeout_override
+            .unwrap_or(self.request_timeout);
+        let statistics = self.statistics.clone();
+        for cmd in pipeline.cmd_iter() {
+            statistics.record_request(cmd);
+        }
+        let transaction = run_with_timeout(request_timeout, async move {
             let values = match self.internal_client {
                 ClientWrapper::Standalone(ref mut client) => {
                     client.send_pipeline(pipeline, offset, 1).await
                 }
 
-                ClientWrapper::Cluster { ref mut client } => {
+                ClientWrapper::Cluster { ref client, .. } => {
                     let route = match routing {
                         Some(RoutingInfo::SingleNode(route)) => route,
                         _ => SingleNodeRoutingInfo::Random,
                     };
 
-                    client.route_pipeline(pipeline, offset, 1, route).await
+                    client
+                        .get()
+                        .route_pipeline(pipeline, offset, 1, route)
+                        .await
+                }
+
+                ClientWrapper::Sentinel(ref mut client) => {
+                    client.send_pipeline(pipeline, offset, 1).await
                 }
             }?;
 
             Self::get_transaction_values(pipeline, values, command_count, offset)
+        });
+        let span = RequestSpan::transaction(pipeline);
+        let outcome_span = span.clone();
+        span.instrument(async move {
+            let result = transaction.await;
+            statistics.record_result(&result);
+            outcome_span.record_outcome(&result);
+            result
         })
         .boxed()
     }
 
-    pub async fn invoke_script<'a, T: Deref<Target = str>>(
+    /// Watches the keys on a connection that is opened for the session, and returns the session.
+    /// Reads of the watched keys and the transaction are then sent through the session, so that they run on that connection,
+    /// after WATCH and before EXEC. In cluster mode, the keys must be in the same slot, and the connection is opened to the slot's primary.
+    pub async fn watch(&mut self, keys: &[Vec<u8>]) -> RedisResult<WatchSession> {
+        let Some(first_key) = keys.first() else {
+            return Err((ErrorKind::ClientError, "WATCH requires at least one key").into());
+        };
+        let request_timeout = self
+            .request_timeout_override
+            .unwrap_or(self.request_timeout);
+        run_with_timeout(request_timeout, async {
+            let connection = match self.internal_client {
+                ClientWrapper::Standalone(ref client) => {
+                    client.get_dedicated_primary_connection().await?
+                }
+
+                ClientWrapper::Cluster {
+                    ref client,
+                    ref node_settings,
+                    ..
+                } => {
+                    let slot = redis::cluster_topology::get_slot(first_key);
+                    watch_session::connect_to_slot_primary(&mut client.get(), node_settings, slot)
+                        .await?
+                }
+
+                ClientWrapper::Sentinel(ref client) => {
+                    client.get_dedicated_primary_connection().await?
+                }
+            };
+            WatchSession::start(connection, keys, request_timeout).await
+        })
+        .await
+    }
+
+    /// Returns where the batch's command is sent. Commands that are pipelined to the same node are grouped together.
+    fn get_batch_target(
+        &self,
+        cmd: &Cmd,
+        routing: &Option<RoutingInfo>,
+        cluster_nodes: &Option<ClusterNodes>,
+    ) -> BatchTarget {
+        // Blocking commands are sent on a connection of their own.
+        if is_subscription_cmd(cmd) || blocks_connection(cmd) {
+            return BatchTarget::Alone;
+        }
+        match self.internal_client {
+            ClientWrapper::Cluster {
+                ref read_router, ..
+            } => {
+                let routing = match routing {
+                    Some(routing) => routing.clone(),
+                    None => {
+                        let routing = RoutingInfo::for_routable(cmd)
+                            .unwrap_or(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random));
+                        match read_router {
+                            Some(read_router) => read_router.route_read(routing),
+                            None => routing,
+                        }
+                    }
+                };
+                match routing {
+                    RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))
+                        if matches!(route.slot_addr(), SlotAddr::Master) =>
+                    {
+                        // Commands of different slots are pipelined together if the same primary serves them.
+                        match cluster_nodes
+                            .as_ref()
+                            .and_then(|nodes| nodes.slot_primary(route.slot()))
+                        {
+                            Some((host, port)) => {
+                                BatchTarget::Pipelined(SingleNodeRoutingInfo::ByAddress {
+                                    host: host.clone(),
+                                    port: *port,
+                                })
+                            }
+                            None => {
+                                BatchTarget::Pipelined(SingleNodeRoutingInfo::SpecificNode(route))
+                            }
+                        }
+                    }
+                    RoutingInfo::SingleNode(route) => BatchTarget::Pipelined(route),
+                    RoutingInfo::MultiNode(_) => BatchTarget::Alone,
+                }
+            }
+            ClientWrapper::Standalone(_) | ClientWrapper::Sentinel(_) => {
+                if routing.is_some()
+                    || matches!(
+                        RoutingInfo::for_routable(cmd),
+                        Some(RoutingInfo::MultiNode(_))
+                    )
+                {
+                    BatchTarget::Alone
+                } else {
+                    // Pipelines are sent to the primary.
+                    BatchTarget::Pipelined(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                        0,
+                        SlotAddr::Master,
+                    )))
+                }
+            }
+        }
+    }
+
+    /// Sends the batch's commands that are pipelined to the same node, and returns their results.
+    /// The connections return a command's server error as a [Value::ServerError] among the pipeline's replies, so each
+    /// command gets its own error. Only a failure of the whole request, such as a disconnect or a timeout, is returned
+    /// for each of the commands.
+    async fn send_batch_pipeline(
+        &mut self,
+        pipeline: redis::Pipeline,
+        route: SingleNodeRoutingInfo,
+    ) -> Vec<RedisResult<Value>> {
+        let command_count = pipeline.cmd_iter().count();
+        let mut request_timeout = Some(Duration::ZERO);
+        for cmd in pipeline.cmd_iter() {
+            self.statistics.record_request(cmd);
+            request_timeout = request_timeout
+                .zip(self.get_request_timeout(cmd))
+                .map(|(request_timeout, cmd_timeout)| request_timeout.max(cmd_timeout));
+        }
+        let future = async {
+            match self.internal_client {
+                ClientWrapper::Standalone(ref mut client) => {
+                    client.send_pipeline(&pipeline, 0, command_count).await
+                }
+
+                ClientWrapper::Cluster { ref client, .. } => {
+                    if let SingleNodeRoutingInfo::ByAddress { ref host, port } = route {
+                        request_tracing::record_node(&format!("{host}:{port}"));
+                    }
+                    client
+                        .get()
+                        .route_pipeline(&pipeline, 0, command_count, route)
+                        .await
+                }
+
+                ClientWrapper::Sentinel(ref mut client) => {
+                    client.send_pipeline(&pipeline, 0, command_count).await
+                }
+            }
+        };
+        let span = RequestSpan::batch(&pipeline);
+        let result = span
+            .instrument(async {
+                let result = run_with_optional_timeout(request_timeout, future).await;
+                span.record_outcome(&result);
+                result
+            })
+            .await;
+        let results: Vec<RedisResult<Value>> = match result {
+            Ok(values) => values
+                .into_iter()
+                .zip(pipeline.cmd_iter())
+                .map(|(value, cmd)| match value {
+                    Value::ServerError(err) => Err(err.into()),
+                    value => convert_to_expected_type(value, expected_type_for_cmd(cmd)),
+                })
+                .collect(),
+            Err(err) => (0..command_count).map(|_| Err(copy_error(&err))).collect(),
+        };
+        for result in results.iter() {
+            self.statistics.record_result(result);
+        }
+        results
+    }
+
+    /// Sends the pipeline's commands without MULTI/EXEC, each to the node that serves its keys unless `routing` is set,
+    /// and returns their results in the order of the commands.
+    /// The commands of each node are sent to it as a single pipeline, so they run in order, and the nodes' pipelines are sent concurrently.
+    /// A command that fails gets its own error, and the other commands' results aren't affected.
+    /// In standalone mode the commands are pipelined to the primary, except for commands with `routing` and commands
+    /// that are sent to all nodes, which are sent on their own, like commands that are sent to several nodes in cluster mode.
+    /// Commands that are sent on their own, which also include subscription commands and blocking commands other than WAIT,
+    /// run concurrently with the pipelines, so they aren't ordered relative to the batch's other commands.
+    pub async fn send_batch(
+        &mut self,
+        pipeline: &redis::Pipeline,
+        routing: Option<RoutingInfo>,
+    ) -> Vec<RedisResult<Value>> {
+        let cluster_nodes = match self.internal_client {
+            ClientWrapper::Cluster { ref topology, .. } => Some(topology.nodes()),
+            _ => None,
+        };
+        let mut groups: Vec<(SingleNodeRoutingInfo, Vec<usize>)> = Vec::new();
+        let mut requests: Vec<BoxFuture<'_, Vec<(usize, RedisResult<Value>)>>> = Vec::new();
+        for (index, cmd) in pipeline.cmd_iter().enumerate() {
+            match self.get_batch_target(cmd, &routing, &cluster_nodes) {
+                BatchTarget::Pipelined(route) => {
+                    match groups
+                        .iter_mut()
+                        .find(|(group_route, _)| *group_route == route)
+                    {
+                        Some((_, indices)) => indices.push(index),
+                        None => groups.push((route, vec![index])),
+                    }
+                }
+                BatchTarget::Alone => {
+                    let mut client = self.clone();
+                    let routing = routing.clone();
+                    requests.push(
+                        async move { vec![(index, client.send_command(cmd, routing).await)] }
+                            .boxed(),
+                    );
+                }
+            }
+        }
+        let commands: Vec<&Cmd> = pipeline.cmd_iter().collect();
+        for (route, indices) in groups {
+            let mut group_pipeline = redis::pipe();
+            for index in indices.iter() {
+                group_pipeline.add_command(commands[*index].clone());
+            }
+            let mut client = self.clone();
+            requests.push(
+                async move {
+                    let results = client.send_batch_pipeline(group_pipeline, route).await;
+                    indices.into_iter().zip(results).collect()
+                }
+                .boxed(),
+            );
+        }
+        let mut results: Vec<Option<RedisResult<Value>>> =
+            (0..commands.len()).map(|_| None).collect();
+        for (index, result) in future::join_all(requests).await.into_iter().flatten() {
+            results[index] = Some(result);
+        }
+        results
+            .into_iter()
+            .map(|result| {
+                result.unwrap_or_else(|| {
+                    Err((ErrorKind::ClientError, "The command's result is missing").into())
+                })
+            })
+            .collect()
+    }
+
+    /// Sends the command to each of the nodes, and returns every node's result instead of aggregating them,
+    /// so that a failure on some of the nodes doesn't hide the replies of the others.
+    /// Each node's request has its own deadline, and a node that doesn't respond in time gets a timeout error as its result.
+    /// The returned error is only set if the command couldn't be sent at all, for example if the cluster's nodes aren't known yet.
+    /// In standalone mode, [MultipleNodeRoutingInfo::AllMasters] only sends the command to the primary.
+    /// In cluster mode, the nodes are taken from the client's cached topology, and are addressed like the cluster connection addresses them.
+    pub async fn send_command_to_nodes(
+        &mut self,
+        cmd: &Cmd,
+        nodes: &MultipleNodeRoutingInfo,
+    ) -> RedisResult<NodeResults> {
+        let primaries_only = match nodes {
+            MultipleNodeRoutingInfo::AllNodes => false,
+            MultipleNodeRoutingInfo::AllMasters => true,
+            MultipleNodeRoutingInfo::MultiSlot(_) => {
+                return Err((
+                    ErrorKind::ClientError,
+                    "Per-node results are only supported for commands that are sent to all nodes or all primaries",
+                )
+                    .into());
+            }
+        };
+        let expected_type = || expected_type_for_cmd(cmd);
+        let request_timeout = self.get_request_timeout(cmd);
+        let statistics = self.statistics.clone();
+        statistics.record_request(cmd);
+        let span = RequestSpan::command(cmd);
+        span.instrument(async {
+            let results = match self.internal_client {
+                ClientWrapper::Standalone(ref client) => Ok(client
+                    .send_request_to_nodes(cmd, primaries_only, request_timeout)
+                    .await),
+                ClientWrapper::Cluster {
+                    ref client,
+                    ref topology,
+                    ..
+                } => {
+                    let nodes = topology.nodes().addresses(primaries_only);
+                    if nodes.is_empty() {
+                        Err((
+                            ErrorKind::ClientError,
+                            "The cluster's nodes aren't known yet",
+                        )
+                            .into())
+                    } else {
+                        Ok(multi_node::send_to_cluster_nodes(
+                            &mut client.get(),
+                            nodes,
+                            cmd,
+                            request_timeout,
+                        )
+                        .await)
+                    }
+                }
+                ClientWrapper::Sentinel(ref client) => Ok(client
+                    .send_request_to_nodes(cmd, primaries_only, request_timeout)
+                    .await),
+            };
+            let result = results.map(|results: NodeResults| {
+                results
+                    .into_iter()
+                    .map(|(address, result)| {
+                        let result = result
+                            .and_then(|value| convert_to_expected_type(value, expected_type()));
+                        (address, result)
+                    })
+                    .collect()
+            });
+            statistics.record_result(&result);
+            span.record_outcome(&result);
+            result
+        })
+        .await
+    }
+
+    /// Runs a single step of a scan over the keys of every primary in the cluster.
+    /// Pass [FINISHED_SCAN_CURSOR] to start a scan, and then the cursor returned by the previous step, until it returns [FINISHED_SCAN_CURSOR] again.
+    /// The result is an array of the next cursor and the keys that were found, like the response to SCAN.
+    pub async fn cluster_scan(
+        &mut self,
+        cursor: &str,
+        args: &ClusterScanArgs,
+    ) -> RedisResult<Value> {
+        let request_timeout = self
+            .request_timeout_override
+            .unwrap_or(self.request_timeout);
+        let ClientWrapper::Cluster {
+            ref client,
+            ref scan_cursors,
+            ..
+        } = self.internal_client
+        else {
+            return Err((
+                ErrorKind::InvalidClientConfig,
+                "Cluster scan is only supported in cluster mode, use SCAN instead",
+            )
+                .into());
+        };
+        run_with_timeout(
+            request_timeout,
+            cluster_scan::cluster_scan(&mut client.get(), scan_cursors, cursor, args),
+        )
+        .await
+    }
+
+    /// Releases the state of a cluster scan that won't be continued. States are released automatically when their cursor
+    /// is used to continue the scan, when they weren't used for a while, and when every clone of the client is dropped.
+    pub fn release_cluster_scan_cursor(&self, cursor: &str) {
+        if let ClientWrapper::Cluster {
+            ref scan_cursors, ..
+        } = self.internal_client
+        {
+            scan_cursors.release(cursor);
+        }
+    }
+
+    /// Re-authenticates the connected nodes with the password, and then replaces the password that the client's
+    /// connections authenticate with after they reconnect, so that credentials can be rotated without recreating the client.
+    /// The password is only replaced for connections that re-authenticated successfully.
+    /// Passing `None` removes the password from later connections, but the current connections stay authenticated.
+    pub async fn update_password(&mut self, password: Option<String>) -> RedisResult<Value> {
+        let request_timeout = self
+            .request_timeout_override
+            .unwrap_or(self.request_timeout);
+        run_with_timeout(request_timeout, async {
+            let result = match self.internal_client {
+                ClientWrapper::Standalone(ref client) => {
+                    client.update_password(password.clone()).await
+                }
+
+                ClientWrapper::Cluster {
+                    ref client,
+                    ref node_settings,
+                    ..
+                } => {
+                    client
+                        .update_password(node_settings, password.clone())
+                        .await
+                }
+
+                ClientWrapper::Sentinel(ref client) => {
+                    client.update_password(password.clone()).await
+                }
+            };
+            // The Pub/Sub connection keeps the previous password if the nodes failed to re-authenticate.
+            result?;
+            if let Some(pubsub) = &self.pubsub {
+                pubsub.update_password(password).await?;
+            }
+            Ok(Value::Okay)
+        })
+        .await
+    }
+
+    pub async fn invoke_script<'a, T: AsRef<[u8]>>(
         &'a mut self,
         hash: &'a str,
         keys: Vec<T>,
@@ -226,20 +1182,29 @@ impl Client {
         routing: Option<RoutingInfo>,
     ) -> redis::RedisResult<Value> {
         let eval = eval_cmd(hash, keys, args);
-        let result = self.send_command(&eval, routing.clone()).await;
-        let Err(err) = result else {
-            return result;
-        };
-        if err.kind() == ErrorKind::NoScriptError {
-            let Some(code) = get_script(hash) else {
-                return Err(err);
-            };
-            let load = load_cmd(code.as_str());
-            self.send_command(&load, None).await?;
-            self.send_command(&eval, routing).await
-        } else {
-            Err(err)
-        }
+        let span = RequestSpan::script(hash);
+        let result = span
+            .instrument(async {
+                let result = self.send_command(&eval, routing.clone()).await;
+                let Err(err) = result else {
+                    return result;
+                };
+                if err.kind() == ErrorKind::NoScriptError {
+                    let Some(code) = get_script(hash) else {
+                        return Err(err);
+                    };
+                    // The script is loaded and invoked again.
+                    request_tracing::record_retry();
+                    let load = load_cmd(code.as_str());
+                    self.send_command(&load, None).await?;
+                    self.send_command(&eval, routing).await
+                } else {
+                    Err(err)
+                }
+            })
+            .await;
+        span.record_outcome(&result);
+        result
     }
 }
 
@@ -249,14 +1214,14 @@ fn load_cmd(code: &str) -> Cmd {
     cmd
 }
 
-fn eval_cmd<T: Deref<Target = str>>(hash: &str, keys: Vec<T>, args: Vec<T>) -> Cmd {
+fn eval_cmd<T: AsRef<[u8]>>(hash: &str, keys: Vec<T>, args: Vec<T>) -> Cmd {
     let mut cmd = redis::cmd("EVALSHA");
     cmd.arg(hash).arg(keys.len());
     for key in keys {
-        cmd.arg(&*key);
+        cmd.arg(key.as_ref());
     }
     for arg in args {
-        cmd.arg(&*arg);
+        cmd.arg(arg.as_ref());
     }
     cmd
 }
@@ -275,10 +1240,22 @@ async fn create_cluster_client(
     // TODO - implement timeout for each connection attempt
     let tls_mode = request.tls_mode.enum_value_or_default();
     let redis_connection_info = get_redis_connection_info(&request);
+    let tls_certificates = get_tls_certificates(&request)?;
+    if request
+        .addresses
+        .iter()
+        .any(|address| !address.unix_socket_path.is_empty())
+    {
+        return Err((
+            ErrorKind::InvalidClientConfig,
+            "Unix socket addresses aren't supported in cluster mode",
+        )
+            .into());
+    }
     let initial_nodes: Vec<_> = request
         .addresses
         .into_iter()
-        .map(|address| get_connection_info(&address, tls_mode, redis_connection_info.clone()))
+        .map(|address| get_connection_info(&address, tls_mode, None, redis_connection_info.clone()))
         .collect();
     let read_from = request.read_from.enum_value().unwrap_or(ReadFrom::Primary);
     let read_from_replicas = !matches!(read_from, ReadFrom::Primary,); // TODO - implement different read from replica strategies.
@@ -300,6 +1277,9 @@ async fn create_cluster_client(
             redis::cluster::TlsMode::Insecure
         };
         builder = builder.tls(tls);
+        if let Some(certificates) = tls_certificates {
+            builder = builder.certs(certificates);
+        }
     }
     let client = builder.build()?;
     client.get_async_connection().await
@@ -309,6 +1289,8 @@ async fn create_cluster_client(
 pub enum ConnectionError {
     Standalone(standalone_client::StandaloneClientConnectionError),
     Cluster(redis::RedisError),
+    Sentinel(redis::RedisError),
+    InvalidConfiguration(redis::RedisError),
     Timeout,
 }
 
@@ -317,6 +1299,10 @@ impl std::fmt::Debug for ConnectionError {
         match self {
             Self::Standalone(arg0) => f.debug_tuple("Standalone").field(arg0).finish(),
             Self::Cluster(arg0) => f.debug_tuple("Cluster").field(arg0).finish(),
+            Self::Sentinel(arg0) => f.debug_tuple("Sentinel").field(arg0).finish(),
+            Self::InvalidConfiguration(arg0) => {
+                f.debug_tuple("InvalidConfiguration").field(arg0).finish()
+            }
             Self::Timeout => write!(f, "Timeout"),
         }
     }
@@ -327,6 +1313,8 @@ impl std::fmt::Display for ConnectionError {
         match self {
             ConnectionError::Standalone(err) => write!(f, "{err:?}"),
             ConnectionError::Cluster(err) => write!(f, "{err}"),
+            ConnectionError::Sentinel(err) => write!(f, "{err}"),
+            ConnectionError::InvalidConfiguration(err) => write!(f, "{err}"),
             ConnectionError::Timeout => f.write_str("connection attempt timed out"),
         }
     }
@@ -344,7 +1332,7 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
     let addresses = request
         .addresses
         .iter()
-        .map(|address| format!("{}:{}", address.host, address.port))
+        .map(format_address)
         .collect::<Vec<_>>()
         .join(", ");
     let tls_mode = request
@@ -361,10 +1349,19 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
             )
         })
         .unwrap_or_default();
-    let cluster_mode = if request.cluster_mode_enabled {
-        "\nCluster mode"
-    } else {
-        "\nStandalone mode"
+    let cluster_mode = match request.sentinel_configuration.as_ref() {
+        Some(sentinel_configuration) => format!(
+            "\nSentinel mode, master name: {}, sentinels: {}",
+            sentinel_configuration.master_name,
+            sentinel_configuration
+                .sentinel_addresses
+                .iter()
+                .map(format_address)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        None if request.cluster_mode_enabled => "\nCluster mode".to_string(),
+        None => "\nStandalone mode".to_string(),
     };
     let request_timeout = format_non_zero_value("Request timeout", request.request_timeout);
     let database_id = format_non_zero_value("database ID", request.database_id);
@@ -395,38 +1392,218 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
     let client_name = chars_to_string_option(&request.client_name)
         .map(|client_name| format!("\nClient name: {client_name}"))
         .unwrap_or_default();
+    let client_az = chars_to_string_option(&request.client_az)
+        .map(|client_az| format!("\nClient availability zone: {client_az}"))
+        .unwrap_or_default();
+    let token_auth = if request
+        .authentication_info
+        .as_ref()
+        .is_some_and(|info| info.token_auth)
+    {
+        "\nToken authentication"
+    } else {
+        ""
+    };
 
     format!(
-        "\nAddresses: {addresses}{tls_mode}{cluster_mode}{request_timeout}{rfr_strategy}{connection_retry_strategy}{database_id}{protocol}{client_name}",
+        "\nAddresses: {addresses}{tls_mode}{cluster_mode}{request_timeout}{rfr_strategy}{connection_retry_strategy}{database_id}{protocol}{client_name}{client_az}{token_auth}",
     )
 }
 
+/// Returns the read strategy that requires routing decisions beyond what the cluster connection makes, if there is one.
+fn get_cluster_read_strategy(
+    request: &ConnectionRequest,
+) -> Option<(ReadStrategy, ConfiguredZones)> {
+    let strategy = match request.read_from.enum_value_or_default() {
+        ReadFrom::Primary | ReadFrom::PreferReplica => return None,
+        ReadFrom::LowestLatency => ReadStrategy::LowestLatency,
+        ReadFrom::AZAffinity => {
+            let Some(client_az) = chars_to_string_option(&request.client_az) else {
+                log_warn(
+                    "client creation",
+                    "AZAffinity requires a client availability zone, reading from any replica instead",
+                );
+                return None;
+            };
+            ReadStrategy::AZAffinity { client_az }
+        }
+    };
+    let configured_zones = request
+        .addresses
+        .iter()
+        .filter_map(|address| {
+            let zone = chars_to_string_option(&address.availability_zone)?;
+            Some(((address.host.to_string(), get_port(address)), zone))
+        })
+        .collect();
+    Some((strategy, configured_zones))
+}
+
+/// Returns the Pub/Sub connection, which connects to the first configured address. In standalone mode this may be a replica,
+/// which receives the messages that are published on its primary as well. Sentinel clients don't support Pub/Sub yet,
+/// since the primary they discover may change.
+fn create_pubsub_connection(
+    request: &ConnectionRequest,
+    push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+    auth_provider: Option<Arc<dyn AuthProvider>>,
+) -> Option<PubSubConnection> {
+    let push_sender = push_sender?;
+    if request.sentinel_configuration.is_some() {
+        return None;
+    }
+    if request.protocol.enum_value_or_default() != ProtocolVersion::RESP3 {
+        // Push notifications are only delivered separately from command responses in RESP3.
+        return None;
+    }
+    let address = request.addresses.first()?.clone();
+    // An invalid TLS configuration fails the client creation, so there's no need to report it here.
+    let tls_params = get_node_tls_params(request).ok()?;
+    Some(PubSubConnection::new(
+        address,
+        RetryStrategy::new(&request.connection_retry_strategy.0),
+        get_redis_connection_info(request),
+        request.tls_mode.enum_value_or_default(),
+        tls_params,
+        push_sender,
+        auth_provider,
+    ))
+}
+
 impl Client {
-    pub async fn new(request: ConnectionRequest) -> Result<Self, ConnectionError> {
+    /// Creates a new client. Push notifications, such as Pub/Sub messages, are passed to `push_sender` if it is set.
+    pub async fn new(
+        request: ConnectionRequest,
+        push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+    ) -> Result<Self, ConnectionError> {
+        Self::create(request, push_sender, None).await
+    }
+
+    /// Creates a client whose connections authenticate with tokens from `auth_provider` instead of a fixed password.
+    /// The request must enable token authentication in its authentication info, and its password is ignored.
+    pub async fn new_with_auth_provider(
+        request: ConnectionRequest,
+        push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+        auth_provider: Arc<dyn AuthProvider>,
+    ) -> Result<Self, ConnectionError> {
+        Self::create(request, push_sender, Some(auth_provider)).await
+    }
+
+    async fn create(
+        request: ConnectionRequest,
+        push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+        auth_provider: Option<Arc<dyn AuthProvider>>,
+    ) -> Result<Self, ConnectionError> {
         const DEFAULT_CLIENT_CREATION_TIMEOUT: Duration = Duration::from_secs(10);
 
+        let mut request =
+            apply_connection_uri(request).map_err(ConnectionError::InvalidConfiguration)?;
         log_info(
             "Connection configuration",
             sanitized_request_string(&request),
         );
+        validate_connection_request(&request).map_err(ConnectionError::InvalidConfiguration)?;
+        let token_auth = request
+            .authentication_info
+            .as_ref()
+            .is_some_and(|info| info.token_auth);
+        match (token_auth, &auth_provider) {
+            (true, None) => {
+                return Err(ConnectionError::InvalidConfiguration(
+                    (
+                        ErrorKind::InvalidClientConfig,
+                        "Token authentication requires an auth provider",
+                    )
+                        .into(),
+                ))
+            }
+            (false, Some(_)) => {
+                return Err(ConnectionError::InvalidConfiguration(
+                    (
+                        ErrorKind::InvalidClientConfig,
+                        "An auth provider requires token authentication to be enabled",
+                    )
+                        .into(),
+                ))
+            }
+            _ => {}
+        }
         let request_timeout = to_duration(request.request_timeout, DEFAULT_RESPONSE_TIMEOUT);
+        let pubsub = create_pubsub_connection(&request, push_sender, auth_provider.clone());
+        let statistics = ClientStatistics::default();
         tokio::time::timeout(DEFAULT_CLIENT_CREATION_TIMEOUT, async move {
-            let internal_client = if request.cluster_mode_enabled {
-                let client = create_cluster_client(request)
+            let internal_client = if request.sentinel_configuration.is_some() {
+                ClientWrapper::Sentinel(
+                    SentinelClient::create_client_with_auth_provider(
+                        request,
+                        auth_provider,
+                        statistics.clone(),
+                    )
+                    .await?,
+                )
+            } else if request.cluster_mode_enabled {
+                // The cluster connection keeps the password it was created with, so the first token is passed as the password.
+                let token = match &auth_provider {
+                    Some(auth_provider) => {
+                        let token = auth_provider
+                            .get_token()
+                            .await
+                            .map_err(ConnectionError::Cluster)?;
+                        request.authentication_info.mut_or_insert_default().password =
+                            token.token.clone().into();
+                        Some(token)
+                    }
+                    None => None,
+                };
+                let read_strategy = get_cluster_read_strategy(&request);
+                let node_settings =
+                    ClusterNodeSettings::new(&request).map_err(ConnectionError::Cluster)?;
+                let seed_host = request
+                    .addresses
+                    .first()
+                    .map(|address| address.host.to_string())
+                    .unwrap_or_default();
+                let client = SharedClusterConnection::new(request)
                     .await
                     .map_err(ConnectionError::Cluster)?;
-                ClientWrapper::Cluster { client }
+                let topology = ClusterTopology::new(client.clone(), seed_host).await;
+                let read_router = read_strategy.map(|(strategy, configured_zones)| {
+                    ClusterReadRouter::new(client.clone(), strategy, configured_zones)
+                });
+                let token_refresher = auth_provider.zip(token).map(|(auth_provider, token)| {
+                    ClusterTokenRefresher::new(
+                        client.clone(),
+                        auth_provider,
+                        node_settings.clone(),
+                        &token,
+                    )
+                });
+                ClientWrapper::Cluster {
+                    client,
+                    read_router,
+                    node_settings,
+                    token_refresher,
+                    scan_cursors: ClusterScanCursors::default(),
+                    topology,
+                }
             } else {
                 ClientWrapper::Standalone(
-                    StandaloneClient::create_client(request)
-                        .await
-                        .map_err(ConnectionError::Standalone)?,
+                    StandaloneClient::create_client_with_auth_provider(
+                        request,
+                        auth_provider,
+                        statistics.clone(),
+                    )
+                    .await
+                    .map_err(ConnectionError::Standalone)?,
                 )
             };
 
             Ok(Self {
                 internal_client,
                 request_timeout,
+                request_timeout_override: None,
+                pubsub,
+                blocking_connections: BlockingConnections::default(),
+                statistics,
             })
         })
         .await
@@ -457,8 +1634,11 @@ impl GlideClientForTests for StandaloneClient {
     fn send_command<'a>(
         &'a mut self,
         cmd: &'a Cmd,
-        _routing: Option<RoutingInfo>,
+        routing: Option<RoutingInfo>,
     ) -> redis::RedisFuture<'a, redis::Value> {
-        self.send_command(cmd).boxed()
+        match routing {
+            Some(routing) => self.route_command(cmd, routing).boxed(),
+            None => self.send_command(cmd).boxed(),
+        }
     }
 }
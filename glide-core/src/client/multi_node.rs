@@ -0,0 +1,84 @@
+use super::run_with_timeout;
+use redis::cluster_async::ClusterConnection;
+use redis::cluster_routing::{RoutingInfo, SingleNodeRoutingInfo};
+use redis::{RedisResult, Value};
+use std::future::Future;
+use std::time::Duration;
+
+/// The result of a request on each node that it was sent to, keyed by the node's address as "host:port".
+pub type NodeResults = Vec<(String, RedisResult<Value>)>;
+
+/// Returns a map of each node's address to its value, like the cluster connection's response to commands that are sent to
+/// multiple nodes without a response policy. A value can't hold errors, so the first failure fails the whole response.
+pub(super) fn node_results_to_map(results: NodeResults) -> RedisResult<Value> {
+    results
+        .into_iter()
+        .map(|(address, result)| {
+            result.map(|value| (Value::BulkString(address.into_bytes()), value))
+        })
+        .collect::<RedisResult<Vec<_>>>()
+        .map(Value::Map)
+}
+
+/// Sends a node's request with its own deadline, so that a node that doesn't respond in time only fails its own result.
+/// Without a deadline, the caller bounds the request.
+pub(super) async fn send_to_node_with_timeout(
+    request_timeout: Option<Duration>,
+    request: impl Future<Output = RedisResult<Value>> + Send,
+) -> RedisResult<Value> {
+    match request_timeout {
+        Some(request_timeout) => run_with_timeout(request_timeout, request).await,
+        None => request.await,
+    }
+}
+
+/// Sends the command to each of the nodes, which are addressed like the cluster connection's nodes, and returns each node's result.
+/// A failure or a timeout on one node doesn't affect the results of the others.
+pub(super) async fn send_to_cluster_nodes(
+    connection: &mut ClusterConnection,
+    nodes: Vec<(String, u16)>,
+    cmd: &redis::Cmd,
+    request_timeout: Option<Duration>,
+) -> NodeResults {
+    futures::future::join_all(nodes.into_iter().map(|(host, port)| {
+        let mut connection = connection.clone();
+        async move {
+            let address = format!("{host}:{port}");
+            let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port });
+            let result =
+                send_to_node_with_timeout(request_timeout, connection.route_command(cmd, routing))
+                    .await;
+            (address, result)
+        }
+    }))
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_results_are_keyed_by_address() {
+        let results = vec![
+            ("primary:6379".to_string(), Ok(Value::Int(1))),
+            ("replica:6380".to_string(), Ok(Value::Int(2))),
+        ];
+        assert_eq!(
+            node_results_to_map(results).unwrap(),
+            Value::Map(vec![
+                (Value::BulkString(b"primary:6379".to_vec()), Value::Int(1)),
+                (Value::BulkString(b"replica:6380".to_vec()), Value::Int(2)),
+            ])
+        );
+
+        let results = vec![
+            ("primary:6379".to_string(), Ok(Value::Int(1))),
+            (
+                "replica:6380".to_string(),
+                Err((redis::ErrorKind::ResponseError, "failed").into()),
+            ),
+        ];
+        assert!(node_results_to_map(results).is_err());
+    }
+}
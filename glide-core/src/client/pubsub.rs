@@ -0,0 +1,191 @@
+use super::auth_provider::AuthProvider;
+use super::reconnecting_connection::{
+    subscription_pipeline, ReconnectingConnection, SubscriptionKind,
+};
+use super::statistics::StatisticsSnapshot;
+use super::tls_server_name::NodeTlsParams;
+use crate::connection_request::{NodeAddress, TlsMode};
+use crate::retry_strategies::RetryStrategy;
+use logger_core::log_warn;
+use redis::cluster_routing::Routable;
+use redis::{Arg, Cmd, PushInfo, RedisConnectionInfo, RedisResult, Value};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, OnceCell};
+
+enum SubscriptionAction {
+    Subscribe,
+    Unsubscribe,
+}
+
+/// Returns the action and kind of a subscription command, or `None` if the command doesn't change subscriptions.
+fn get_subscription_command(cmd: &Cmd) -> Option<(SubscriptionAction, SubscriptionKind)> {
+    match cmd.command()?.as_slice() {
+        b"SUBSCRIBE" => Some((SubscriptionAction::Subscribe, SubscriptionKind::Exact)),
+        b"PSUBSCRIBE" => Some((SubscriptionAction::Subscribe, SubscriptionKind::Pattern)),
+        b"UNSUBSCRIBE" => Some((SubscriptionAction::Unsubscribe, SubscriptionKind::Exact)),
+        b"PUNSUBSCRIBE" => Some((SubscriptionAction::Unsubscribe, SubscriptionKind::Pattern)),
+        _ => None,
+    }
+}
+
+fn get_command_name(action: &SubscriptionAction, kind: SubscriptionKind) -> &'static str {
+    match (action, kind) {
+        (SubscriptionAction::Subscribe, SubscriptionKind::Exact) => "SUBSCRIBE",
+        (SubscriptionAction::Subscribe, SubscriptionKind::Pattern) => "PSUBSCRIBE",
+        (SubscriptionAction::Unsubscribe, SubscriptionKind::Exact) => "UNSUBSCRIBE",
+        (SubscriptionAction::Unsubscribe, SubscriptionKind::Pattern) => "PUNSUBSCRIBE",
+    }
+}
+
+pub(super) fn is_subscription_cmd(cmd: &Cmd) -> bool {
+    get_subscription_command(cmd).is_some()
+}
+
+struct InnerPubSubConnection {
+    address: NodeAddress,
+    retry_strategy: RetryStrategy,
+    redis_connection_info: Mutex<RedisConnectionInfo>,
+    tls_mode: TlsMode,
+    tls_params: NodeTlsParams,
+    push_sender: mpsc::UnboundedSender<PushInfo>,
+    auth_provider: Option<Arc<dyn AuthProvider>>,
+    /// The connection is created on the first subscription, so clients that don't use Pub/Sub don't pay for it.
+    connection: OnceCell<ReconnectingConnection>,
+}
+
+impl Drop for InnerPubSubConnection {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.get() {
+            connection.mark_as_dropped();
+        }
+    }
+}
+
+/// A dedicated connection for Pub/Sub subscriptions. Messages and subscription confirmations are passed to the push sender.
+/// Since non-sharded Pub/Sub messages are propagated to every node in a cluster, a single connection serves both standalone and cluster clients.
+#[derive(Clone)]
+pub(super) struct PubSubConnection {
+    inner: Arc<InnerPubSubConnection>,
+}
+
+impl PubSubConnection {
+    pub(super) fn new(
+        address: NodeAddress,
+        retry_strategy: RetryStrategy,
+        redis_connection_info: RedisConnectionInfo,
+        tls_mode: TlsMode,
+        tls_params: NodeTlsParams,
+        push_sender: mpsc::UnboundedSender<PushInfo>,
+        auth_provider: Option<Arc<dyn AuthProvider>>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(InnerPubSubConnection {
+                address,
+                retry_strategy,
+                redis_connection_info: Mutex::new(redis_connection_info),
+                tls_mode,
+                tls_params,
+                push_sender,
+                auth_provider,
+                connection: OnceCell::new(),
+            }),
+        }
+    }
+
+    async fn get_reconnecting_connection(&self) -> RedisResult<&ReconnectingConnection> {
+        self.inner
+            .connection
+            .get_or_try_init(|| async {
+                let redis_connection_info =
+                    self.inner.redis_connection_info.lock().unwrap().clone();
+                ReconnectingConnection::new(
+                    &self.inner.address,
+                    self.inner.retry_strategy.clone(),
+                    redis_connection_info,
+                    self.inner.tls_mode,
+                    self.inner.tls_params.clone(),
+                    Some(self.inner.push_sender.clone()),
+                    self.inner.auth_provider.clone(),
+                )
+                .await
+                .map_err(|(connection, err)| {
+                    // The connection won't be stored, so it shouldn't keep reconnecting in the background.
+                    connection.mark_as_dropped();
+                    err
+                })
+            })
+            .await
+    }
+
+    /// Adds the disconnects and reconnects of the connection, if it was created, to a snapshot of the client's statistics.
+    pub(super) fn add_connection_events(&self, snapshot: &mut StatisticsSnapshot) {
+        if let Some(connection) = self.inner.connection.get() {
+            connection.add_connection_events(snapshot);
+        }
+    }
+
+    /// Re-authenticates the connection if it was already created, and then replaces its password.
+    pub(super) async fn update_password(&self, password: Option<String>) -> RedisResult<()> {
+        if let Some(connection) = self.inner.connection.get() {
+            connection.update_password(password.clone()).await?;
+        }
+        self.inner.redis_connection_info.lock().unwrap().password = password;
+        Ok(())
+    }
+
+    pub(super) async fn send_subscription_command(&self, cmd: &Cmd) -> RedisResult<Value> {
+        let Some((action, kind)) = get_subscription_command(cmd) else {
+            return Err((
+                redis::ErrorKind::ClientError,
+                "Received a non-subscription command",
+            )
+                .into());
+        };
+        let reconnecting_connection = self.get_reconnecting_connection().await?;
+        let mut names: Vec<Vec<u8>> = cmd
+            .args_iter()
+            .skip(1)
+            .filter_map(|arg| match arg {
+                Arg::Simple(name) => Some(name.to_vec()),
+                Arg::Cursor => None,
+            })
+            .collect();
+        if names.is_empty() && matches!(action, SubscriptionAction::Unsubscribe) {
+            // An unsubscribe without arguments removes all subscriptions, and receives a reply per removed subscription.
+            names = reconnecting_connection.get_subscriptions(kind);
+        }
+
+        let mut connection = reconnecting_connection.get_connection().await?;
+        let result = if names.is_empty() {
+            connection.send_packed_command(cmd).await.map(|_| ())
+        } else {
+            let pipeline = subscription_pipeline(get_command_name(&action, kind), &names);
+            connection
+                .send_packed_commands(&pipeline, 0, names.len())
+                .await
+                .map(|_| ())
+        };
+        match result {
+            Ok(()) => {
+                match action {
+                    SubscriptionAction::Subscribe => {
+                        reconnecting_connection.add_subscriptions(kind, &names)
+                    }
+                    SubscriptionAction::Unsubscribe => {
+                        reconnecting_connection.remove_subscriptions(kind, &names)
+                    }
+                };
+                Ok(Value::Okay)
+            }
+            Err(err) if err.is_connection_dropped() => {
+                log_warn(
+                    "subscription request",
+                    format!("received disconnect error `{err}`"),
+                );
+                reconnecting_connection.reconnect();
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
@@ -1,26 +1,98 @@
 use crate::connection_request::{NodeAddress, TlsMode};
 use crate::retry_strategies::RetryStrategy;
 use futures_intrusive::sync::ManualResetEvent;
-use logger_core::{log_debug, log_trace, log_warn};
+use logger_core::{log_debug, log_info, log_trace, log_warn};
 use redis::aio::MultiplexedConnection;
-use redis::{RedisConnectionInfo, RedisError, RedisResult};
-use std::sync::atomic::{AtomicBool, Ordering};
+use redis::{ConnectionAddr, PushInfo, RedisConnectionInfo, RedisError, RedisResult};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::sync::Mutex;
-use std::time::Duration;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 use tokio::task;
 use tokio_retry::Retry;
 
-use super::{run_with_timeout, DEFAULT_CONNECTION_ATTEMPT_TIMEOUT};
+use super::auth_provider::AuthProvider;
+use super::latency::LatencyTracker;
+use super::statistics::{self, StatisticsSnapshot};
+use super::tls_server_name::{NodeTlsParams, ServerNameConnector};
+use super::{
+    auth_cmd, run_with_timeout, DEFAULT_CONNECTION_ATTEMPT_TIMEOUT, DEFAULT_RESPONSE_TIMEOUT,
+};
 
 /// The object that is used in order to recreate a connection after a disconnect.
 struct ConnectionBackend {
     /// This signal is reset when a connection disconnects, and set when a new `ConnectionState` has been set with a `Connected` state.
     connection_available_signal: ManualResetEvent,
-    /// Information needed in order to create a new connection.
-    connection_info: redis::Client,
+    /// Information needed in order to create a new connection. Replaced when the password or the address is updated.
+    connection_info: RwLock<redis::Client>,
+    /// If set, connections are opened with the TLS server name override, instead of by redis-rs.
+    server_name: Option<ServerNameConnector>,
     /// Once this flag is set, the internal connection needs no longer try to reconnect to the server, because all the outer clients were dropped.
     client_dropped_flagged: AtomicBool,
+    /// If set, push notifications received on the connection are passed to this sender.
+    push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+    /// Channels and patterns the connection is subscribed to. These are resubscribed after every reconnect.
+    subscriptions: Mutex<Subscriptions>,
+    /// The node's address, which identifies it in the client's statistics. Replaced when the connection is re-pointed.
+    address: RwLock<String>,
+    /// The node's availability zone from the connection request. Replaced when the connection is re-pointed.
+    configured_availability_zone: RwLock<Option<String>>,
+    /// The zone that the node reported in its `INFO SERVER` response. It's read after every connection once the zone is
+    /// discovered, unless the zone is configured.
+    discovered_availability_zone: RwLock<Option<String>>,
+    discovers_availability_zone: AtomicBool,
+    /// Round-trip times of requests sent to the node.
+    latency: LatencyTracker,
+    /// How many times the connection was lost, and how many times it was recreated, for the statistics of its client.
+    disconnects: AtomicU64,
+    reconnects: AtomicU64,
+    /// If set, a new token is requested before every connection attempt, and used as the password.
+    auth_provider: Option<Arc<dyn AuthProvider>>,
+    /// When the token of the current connection should be replaced, or `None` if it doesn't expire.
+    token_refresh_deadline: Mutex<Option<Instant>>,
+}
+
+/// The kind of a Pub/Sub subscription.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum SubscriptionKind {
+    /// A subscription to a specific channel, created by `SUBSCRIBE`.
+    Exact,
+    /// A subscription to a glob-style pattern, created by `PSUBSCRIBE`.
+    Pattern,
+}
+
+impl SubscriptionKind {
+    fn subscribe_command(&self) -> &'static str {
+        match self {
+            SubscriptionKind::Exact => "SUBSCRIBE",
+            SubscriptionKind::Pattern => "PSUBSCRIBE",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Subscriptions {
+    channels: HashSet<Vec<u8>>,
+    patterns: HashSet<Vec<u8>>,
+}
+
+impl Subscriptions {
+    fn names(&self, kind: SubscriptionKind) -> Vec<Vec<u8>> {
+        let subscriptions = match kind {
+            SubscriptionKind::Exact => &self.channels,
+            SubscriptionKind::Pattern => &self.patterns,
+        };
+        subscriptions.iter().cloned().collect()
+    }
+
+    fn get_mut(&mut self, kind: SubscriptionKind) -> &mut HashSet<Vec<u8>> {
+        match kind {
+            SubscriptionKind::Exact => &mut self.channels,
+            SubscriptionKind::Pattern => &mut self.patterns,
+        }
+    }
 }
 
 /// State of the current connection. Allows the user to use a connection only when a reconnect isn't in progress or has failed.
@@ -43,20 +115,165 @@ pub(super) struct ReconnectingConnection {
     inner: Arc<InnerReconnectingConnection>,
 }
 
-async fn get_multiplexed_connection(client: &redis::Client) -> RedisResult<MultiplexedConnection> {
-    run_with_timeout(
-        DEFAULT_CONNECTION_ATTEMPT_TIMEOUT,
-        client.get_multiplexed_async_connection(),
+async fn get_multiplexed_connection(
+    client: &redis::Client,
+    server_name: &Option<ServerNameConnector>,
+) -> RedisResult<MultiplexedConnection> {
+    match server_name {
+        Some(server_name) => {
+            run_with_timeout(
+                DEFAULT_CONNECTION_ATTEMPT_TIMEOUT,
+                server_name.connect(client.get_connection_info()),
+            )
+            .await
+        }
+        None => {
+            run_with_timeout(
+                DEFAULT_CONNECTION_ATTEMPT_TIMEOUT,
+                client.get_multiplexed_async_connection(),
+            )
+            .await
+        }
+    }
+}
+
+/// Sends a single-channel subscription command for each name, so that every command receives exactly one reply.
+pub(super) fn subscription_pipeline(command: &str, names: &[Vec<u8>]) -> redis::Pipeline {
+    let mut pipeline = redis::Pipeline::with_capacity(names.len());
+    for name in names {
+        let mut cmd = redis::cmd(command);
+        cmd.arg(name.as_slice());
+        pipeline.add_command(cmd);
+    }
+    pipeline
+}
+
+async fn restore_subscriptions(
+    connection: &mut MultiplexedConnection,
+    backend: &ConnectionBackend,
+) -> RedisResult<()> {
+    for kind in [SubscriptionKind::Exact, SubscriptionKind::Pattern] {
+        let names = backend.subscriptions.lock().unwrap().names(kind);
+        if names.is_empty() {
+            continue;
+        }
+        let pipeline = subscription_pipeline(kind.subscribe_command(), &names);
+        connection
+            .send_packed_commands(&pipeline, 0, names.len())
+            .await?;
+    }
+    Ok(())
+}
+
+impl ConnectionBackend {
+    fn client(&self) -> redis::Client {
+        self.connection_info.read().unwrap().clone()
+    }
+
+    /// Replaces the password that new connections authenticate with.
+    fn set_password(&self, password: Option<String>) {
+        let mut client = self.connection_info.write().unwrap();
+        let mut connection_info = client.get_connection_info().clone();
+        connection_info.redis.password = password;
+        *client = redis::Client::open(connection_info).unwrap(); // can unwrap, because [open] fails only on trying to convert input to ConnectionInfo, and we pass ConnectionInfo.
+    }
+
+    /// Replaces the host and port that new connections connect to, keeping the TLS settings.
+    fn set_address(&self, address: &NodeAddress) {
+        let host = address.host.to_string();
+        let port = super::get_port(address);
+        let mut client = self.connection_info.write().unwrap();
+        let mut connection_info = client.get_connection_info().clone();
+        connection_info.addr = match connection_info.addr {
+            ConnectionAddr::TcpTls {
+                insecure,
+                tls_params,
+                ..
+            } => ConnectionAddr::TcpTls {
+                host,
+                port,
+                insecure,
+                tls_params,
+            },
+            _ => ConnectionAddr::Tcp(host, port),
+        };
+        *client = redis::Client::open(connection_info).unwrap(); // can unwrap, because [open] fails only on trying to convert input to ConnectionInfo, and we pass ConnectionInfo.
+        *self.address.write().unwrap() = super::format_address(address);
+        *self.configured_availability_zone.write().unwrap() =
+            super::chars_to_string_option(&address.availability_zone);
+        *self.discovered_availability_zone.write().unwrap() = None;
+    }
+
+    /// The username that connections authenticate with.
+    fn username(&self) -> Option<String> {
+        self.connection_info
+            .read()
+            .unwrap()
+            .get_connection_info()
+            .redis
+            .username
+            .clone()
+    }
+}
+
+/// Reads the node's availability zone from its `INFO SERVER` response, if the zone is discovered and isn't configured.
+/// If the node doesn't respond, its zone is unknown until the next connection.
+async fn refresh_availability_zone(
+    connection: &mut MultiplexedConnection,
+    backend: &ConnectionBackend,
+) {
+    if !backend.discovers_availability_zone.load(Ordering::Acquire)
+        || backend
+            .configured_availability_zone
+            .read()
+            .unwrap()
+            .is_some()
+    {
+        return;
+    }
+    let zone = match run_with_timeout(
+        DEFAULT_RESPONSE_TIMEOUT,
+        connection.send_packed_command(redis::cmd("INFO").arg("SERVER")),
     )
     .await
+    {
+        Ok(info) => super::get_availability_zone_from_info(&info),
+        Err(err) => {
+            log_warn(
+                "availability zone",
+                format!(
+                    "failed to get the availability zone of {}: `{err}`",
+                    backend.address.read().unwrap()
+                ),
+            );
+            None
+        }
+    };
+    *backend.discovered_availability_zone.write().unwrap() = zone;
+}
+
+async fn get_multiplexed_connection_with_backend(
+    backend: &ConnectionBackend,
+) -> RedisResult<MultiplexedConnection> {
+    if let Some(auth_provider) = &backend.auth_provider {
+        let token = auth_provider.get_token().await?;
+        *backend.token_refresh_deadline.lock().unwrap() = token.refresh_deadline();
+        backend.set_password(Some(token.token));
+    }
+    let connection = get_multiplexed_connection(&backend.client(), &backend.server_name).await?;
+    if let Some(push_sender) = &backend.push_sender {
+        connection
+            .get_push_manager()
+            .replace_sender(push_sender.clone());
+    }
+    Ok(connection)
 }
 
 async fn create_connection(
     connection_backend: ConnectionBackend,
     retry_strategy: RetryStrategy,
 ) -> Result<ReconnectingConnection, (ReconnectingConnection, RedisError)> {
-    let client = &connection_backend.connection_info;
-    let action = || get_multiplexed_connection(client);
+    let action = || get_multiplexed_connection_with_backend(&connection_backend);
 
     match Retry::spawn(retry_strategy.get_iterator(), action).await {
         Ok(connection) => {
@@ -64,10 +281,7 @@ async fn create_connection(
                 "connection creation",
                 format!(
                     "Connection to {} created",
-                    connection_backend
-                        .connection_info
-                        .get_connection_info()
-                        .addr
+                    connection_backend.client().get_connection_info().addr
                 ),
             );
             Ok(ReconnectingConnection {
@@ -82,10 +296,7 @@ async fn create_connection(
                 "connection creation",
                 format!(
                     "Failed connecting to {}, due to {err}",
-                    connection_backend
-                        .connection_info
-                        .get_connection_info()
-                        .addr
+                    connection_backend.client().get_connection_info().addr
                 ),
             );
             let connection = ReconnectingConnection {
@@ -103,11 +314,13 @@ async fn create_connection(
 fn get_client(
     address: &NodeAddress,
     tls_mode: TlsMode,
+    tls_params: Option<redis::TlsConnParams>,
     redis_connection_info: redis::RedisConnectionInfo,
 ) -> redis::Client {
     redis::Client::open(super::get_connection_info(
         address,
         tls_mode,
+        tls_params,
         redis_connection_info,
     ))
     .unwrap() // can unwrap, because [open] fails only on trying to convert input to ConnectionInfo, and we pass ConnectionInfo.
@@ -131,19 +344,44 @@ impl ReconnectingConnection {
         connection_retry_strategy: RetryStrategy,
         redis_connection_info: RedisConnectionInfo,
         tls_mode: TlsMode,
+        tls_params: NodeTlsParams,
+        push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+        auth_provider: Option<Arc<dyn AuthProvider>>,
     ) -> Result<ReconnectingConnection, (ReconnectingConnection, RedisError)> {
         log_debug(
             "connection creation",
             format!("Attempting connection to {address}"),
         );
 
-        let connection_info = get_client(address, tls_mode, redis_connection_info);
+        let connection_info =
+            get_client(address, tls_mode, tls_params.params, redis_connection_info);
         let backend = ConnectionBackend {
-            connection_info,
+            connection_info: RwLock::new(connection_info),
+            server_name: tls_params.server_name,
             connection_available_signal: ManualResetEvent::new(true),
             client_dropped_flagged: AtomicBool::new(false),
+            push_sender,
+            subscriptions: Default::default(),
+            address: RwLock::new(super::format_address(address)),
+            configured_availability_zone: RwLock::new(super::chars_to_string_option(
+                &address.availability_zone,
+            )),
+            discovered_availability_zone: Default::default(),
+            discovers_availability_zone: AtomicBool::new(false),
+            latency: Default::default(),
+            disconnects: Default::default(),
+            reconnects: Default::default(),
+            auth_provider,
+            token_refresh_deadline: Default::default(),
         };
-        create_connection(backend, connection_retry_strategy).await
+        let uses_tokens = backend.auth_provider.is_some();
+        let result = create_connection(backend, connection_retry_strategy).await;
+        if uses_tokens {
+            match &result {
+                Ok(connection) | Err((connection, _)) => connection.start_token_refresh(),
+            }
+        }
+        result
     }
 
     pub(super) fn is_dropped(&self) -> bool {
@@ -178,6 +416,15 @@ impl ReconnectingConnection {
         }
     }
 
+    /// Opens a new connection to the node, which isn't shared with other requests and isn't reconnected or resubscribed.
+    pub(super) async fn get_dedicated_connection(&self) -> RedisResult<MultiplexedConnection> {
+        get_multiplexed_connection(
+            &self.inner.backend.client(),
+            &self.inner.backend.server_name,
+        )
+        .await
+    }
+
     pub(super) fn reconnect(&self) {
         {
             let mut guard = self.inner.state.lock().unwrap();
@@ -186,6 +433,13 @@ impl ReconnectingConnection {
                 // exit early - if reconnection already started or failed, there's nothing else to do.
                 return;
             }
+            if matches!(*guard, ConnectionState::Connected(_)) {
+                statistics::record_disconnect();
+                self.inner
+                    .backend
+                    .disconnects
+                    .fetch_add(1, Ordering::Relaxed);
+            }
             self.inner.backend.connection_available_signal.reset();
             *guard = ConnectionState::Reconnecting;
         };
@@ -195,7 +449,7 @@ impl ReconnectingConnection {
         // The reconnect task is spawned instead of awaited here, so that the reconnect attempt will continue in the
         // background, regardless of whether the calling task is dropped or not.
         task::spawn(async move {
-            let client = &connection_clone.inner.backend.connection_info;
+            let backend = &connection_clone.inner.backend;
             for sleep_duration in internal_retry_iterator() {
                 if connection_clone.is_dropped() {
                     log_debug(
@@ -205,16 +459,21 @@ impl ReconnectingConnection {
                     // Client was dropped, reconnection attempts can stop
                     return;
                 }
-                match get_multiplexed_connection(client).await {
+                match get_multiplexed_connection_with_backend(backend).await {
                     Ok(mut connection) => {
                         if connection
                             .send_packed_command(&redis::cmd("PING"))
                             .await
                             .is_err()
+                            || restore_subscriptions(&mut connection, backend)
+                                .await
+                                .is_err()
                         {
                             tokio::time::sleep(sleep_duration).await;
                             continue;
                         }
+                        // The node might have been unreachable when its zone was first read, or might be another node.
+                        refresh_availability_zone(&mut connection, backend).await;
                         {
                             let mut guard = connection_clone.inner.state.lock().unwrap();
                             log_debug("reconnect", "completed succesfully");
@@ -225,6 +484,8 @@ impl ReconnectingConnection {
                                 .set();
                             *guard = ConnectionState::Connected(connection);
                         }
+                        statistics::record_reconnect();
+                        backend.reconnects.fetch_add(1, Ordering::Relaxed);
                         return;
                     }
                     Err(_) => tokio::time::sleep(sleep_duration).await,
@@ -233,6 +494,159 @@ impl ReconnectingConnection {
         });
     }
 
+    /// Points the connection at another node, such as a node that replaced this one after a failover. The connection is
+    /// recreated to the new address, with its subscriptions. Requests that were already sent complete on the previous
+    /// connection.
+    pub(super) fn set_address(&self, address: &NodeAddress) {
+        log_info(
+            "reconnect",
+            format!(
+                "Re-pointing the connection to {} at {}",
+                self.address(),
+                super::format_address(address)
+            ),
+        );
+        self.inner.backend.set_address(address);
+        self.inner.backend.latency.reset();
+        self.reconnect();
+    }
+
+    pub(super) fn get_subscriptions(&self, kind: SubscriptionKind) -> Vec<Vec<u8>> {
+        self.inner.backend.subscriptions.lock().unwrap().names(kind)
+    }
+
+    pub(super) fn add_subscriptions(&self, kind: SubscriptionKind, names: &[Vec<u8>]) {
+        self.inner
+            .backend
+            .subscriptions
+            .lock()
+            .unwrap()
+            .get_mut(kind)
+            .extend(names.iter().cloned());
+    }
+
+    pub(super) fn remove_subscriptions(&self, kind: SubscriptionKind, names: &[Vec<u8>]) {
+        let mut guard = self.inner.backend.subscriptions.lock().unwrap();
+        let subscriptions = guard.get_mut(kind);
+        for name in names {
+            subscriptions.remove(name);
+        }
+    }
+
+    /// Re-authenticates the current connection with the password, and then replaces the password that is used when
+    /// reconnecting. If the connection fails to re-authenticate, the previous password is kept. A connection that is
+    /// reconnecting can't be re-authenticated, so the password is replaced right away, and is used once it's recreated.
+    /// Without a password, the current connection stays authenticated, and only later connections are affected.
+    pub(super) async fn update_password(&self, password: Option<String>) -> RedisResult<()> {
+        let Some(password) = password else {
+            self.inner.backend.set_password(None);
+            return Ok(());
+        };
+        let Some(mut connection) = self.try_get_connection().await else {
+            self.inner.backend.set_password(Some(password));
+            return Ok(());
+        };
+        let username = self.inner.backend.username();
+        match connection
+            .send_packed_command(&auth_cmd(username.as_deref(), &password))
+            .await
+        {
+            Ok(_) => {
+                self.inner.backend.set_password(Some(password));
+                Ok(())
+            }
+            Err(err) if err.is_connection_dropped() => {
+                log_warn(
+                    "update password",
+                    format!("received disconnect error `{err}`"),
+                );
+                self.reconnect();
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Re-authenticates the connection with a new token from the auth provider before the current token expires.
+    fn start_token_refresh(&self) {
+        let reconnecting_connection = self.clone();
+        task::spawn(async move {
+            let backend = &reconnecting_connection.inner.backend;
+            let Some(auth_provider) = &backend.auth_provider else {
+                return;
+            };
+            loop {
+                tokio::time::sleep(super::HEARTBEAT_SLEEP_DURATION).await;
+                if reconnecting_connection.is_dropped() {
+                    log_debug(
+                        "ReconnectingConnection",
+                        "token refresh stopped after connection was dropped",
+                    );
+                    return;
+                }
+                let deadline = *backend.token_refresh_deadline.lock().unwrap();
+                if !deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+                    continue;
+                }
+                match auth_provider.get_token().await {
+                    Ok(token) => {
+                        *backend.token_refresh_deadline.lock().unwrap() = token.refresh_deadline();
+                        if let Err(err) = reconnecting_connection
+                            .update_password(Some(token.token))
+                            .await
+                        {
+                            log_warn(
+                                "token refresh",
+                                format!("failed to re-authenticate: `{err}`"),
+                            );
+                        }
+                    }
+                    // The deadline isn't moved, so the token is requested again on the next iteration.
+                    Err(err) => log_warn(
+                        "token refresh",
+                        format!("failed to get a new token: `{err}`"),
+                    ),
+                }
+            }
+        });
+    }
+
+    pub(super) fn address(&self) -> String {
+        self.inner.backend.address.read().unwrap().clone()
+    }
+
+    /// Reads the node's availability zone from the node, now and after every reconnect, unless the zone is configured.
+    pub(super) async fn discover_availability_zone(&self) {
+        self.inner
+            .backend
+            .discovers_availability_zone
+            .store(true, Ordering::Release);
+        if let Some(mut connection) = self.try_get_connection().await {
+            refresh_availability_zone(&mut connection, &self.inner.backend).await;
+        }
+    }
+
+    /// The node's configured availability zone, or the zone that it reported, if it's known.
+    pub(super) fn availability_zone(&self) -> Option<String> {
+        let backend = &self.inner.backend;
+        backend
+            .configured_availability_zone
+            .read()
+            .unwrap()
+            .clone()
+            .or_else(|| backend.discovered_availability_zone.read().unwrap().clone())
+    }
+
+    pub(super) fn latency(&self) -> &LatencyTracker {
+        &self.inner.backend.latency
+    }
+
+    /// Adds the connection's disconnects and reconnects to a snapshot of its client's statistics.
+    pub(super) fn add_connection_events(&self, snapshot: &mut StatisticsSnapshot) {
+        snapshot.disconnects += self.inner.backend.disconnects.load(Ordering::Relaxed);
+        snapshot.reconnects += self.inner.backend.reconnects.load(Ordering::Relaxed);
+    }
+
     pub fn is_connected(&self) -> bool {
         !matches!(
             *self.inner.state.lock().unwrap(),
@@ -0,0 +1,292 @@
+//! Spans that trace requests from the socket down to the node that served them.
+//! The spans are only emitted with the `otel-tracing` feature. Without it, these types are no-ops.
+//!
+//! Each span records the command, the node that it was sent to, how many times the client retried it, how many MOVED or ASK
+//! redirections reached the client, and its outcome. In cluster mode the node is the one that the request was routed to by address,
+//! or the primary that serves its slot according to the client's cached topology. Redirections that the cluster connection
+//! follows by itself aren't visible to the client, so they aren't counted.
+
+#[cfg(feature = "otel-tracing")]
+pub use enabled::otlp_layer;
+#[cfg(feature = "otel-tracing")]
+pub(crate) use enabled::*;
+
+#[cfg(not(feature = "otel-tracing"))]
+pub(crate) use disabled::*;
+
+#[cfg(feature = "otel-tracing")]
+mod enabled {
+    use super::super::statistics::command_name;
+    use redis::{Cmd, RedisResult};
+    use std::future::Future;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use tracing::field::Empty;
+    use tracing::{info_span, Instrument, Span};
+
+    tokio::task_local! {
+        /// The innermost span that the current request is instrumented with.
+        static CURRENT_SPAN: RequestSpan;
+    }
+
+    #[derive(Default)]
+    struct Counters {
+        retries: AtomicU64,
+        redirects: AtomicU64,
+    }
+
+    #[derive(Clone)]
+    pub(crate) struct RequestSpan {
+        span: Span,
+        counters: Arc<Counters>,
+    }
+
+    impl From<Span> for RequestSpan {
+        fn from(span: Span) -> Self {
+            Self {
+                span,
+                counters: Default::default(),
+            }
+        }
+    }
+
+    impl RequestSpan {
+        /// A request that was received on the socket. The client's spans are nested in it.
+        pub(crate) fn socket_request(callback_idx: u32) -> Self {
+            info_span!("socket_request", callback_idx).into()
+        }
+
+        pub(crate) fn command(cmd: &Cmd) -> Self {
+            info_span!(
+                "command",
+                command = %command_name(cmd),
+                node = Empty,
+                retries = 0,
+                redirects = 0,
+                outcome = Empty
+            )
+            .into()
+        }
+
+        pub(crate) fn transaction(pipeline: &redis::Pipeline) -> Self {
+            info_span!(
+                "transaction",
+                commands = pipeline.cmd_iter().count(),
+                outcome = Empty
+            )
+            .into()
+        }
+
+        /// The commands of a batch that are pipelined to the same node.
+        pub(crate) fn batch(pipeline: &redis::Pipeline) -> Self {
+            info_span!(
+                "batch",
+                commands = pipeline.cmd_iter().count(),
+                node = Empty,
+                outcome = Empty
+            )
+            .into()
+        }
+
+        pub(crate) fn script(hash: &str) -> Self {
+            info_span!("script", hash, retries = 0, outcome = Empty).into()
+        }
+
+        pub(crate) fn record_outcome<T>(&self, result: &RedisResult<T>) {
+            match result {
+                Ok(_) => self.span.record("outcome", "ok"),
+                Err(err) if err.is_timeout() => self.span.record("outcome", "timeout"),
+                Err(err) => match err.code() {
+                    Some(code) => self.span.record("outcome", code),
+                    None => self
+                        .span
+                        .record("outcome", format!("{:?}", err.kind()).as_str()),
+                },
+            };
+        }
+
+        /// Runs the future in the span, so that the functions below record into it.
+        pub(crate) fn instrument<F: Future>(&self, future: F) -> impl Future<Output = F::Output> {
+            CURRENT_SPAN.scope(self.clone(), future.instrument(self.span.clone()))
+        }
+    }
+
+    /// Adds one to the counter of the current span, and records the counter's new value in the span's field.
+    fn increment(field: &str, counter: impl Fn(&Counters) -> &AtomicU64) {
+        // Outside of an instrumented request there's no span to record into.
+        let _ = CURRENT_SPAN.try_with(|span| {
+            let count = counter(&span.counters).fetch_add(1, Ordering::Relaxed) + 1;
+            span.span.record(field, count);
+        });
+    }
+
+    /// Records the node that serves the request of the current span.
+    pub(crate) fn record_node(node: &str) {
+        Span::current().record("node", node);
+    }
+
+    /// Records that the request of the current span was sent again, after the client handled a failure.
+    pub(crate) fn record_retry() {
+        increment("retries", |counters| &counters.retries);
+    }
+
+    /// Records that the request of the current span received a MOVED or ASK redirection.
+    pub(crate) fn record_redirect() {
+        increment("redirects", |counters| &counters.redirects);
+    }
+
+    /// Returns a layer that exports the spans to an OpenTelemetry collector over OTLP/gRPC, for example at `http://localhost:4317`.
+    /// Add it to the application's `tracing` subscriber. Spans are exported in batches by a background task,
+    /// so this must be called from within a Tokio runtime.
+    pub fn otlp_layer<S>(
+        endpoint: impl Into<String>,
+    ) -> Result<
+        tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>,
+        opentelemetry::trace::TraceError,
+    >
+    where
+        S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+    {
+        use opentelemetry_otlp::WithExportConfig;
+
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(endpoint),
+            )
+            .with_trace_config(opentelemetry_sdk::trace::config().with_resource(
+                opentelemetry_sdk::Resource::new(vec![opentelemetry::KeyValue::new(
+                    "service.name",
+                    "glide",
+                )]),
+            ))
+            .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+        Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+}
+
+#[cfg(not(feature = "otel-tracing"))]
+mod disabled {
+    use redis::{Cmd, RedisResult};
+    use std::future::Future;
+
+    #[derive(Clone)]
+    pub(crate) struct RequestSpan;
+
+    impl RequestSpan {
+        pub(crate) fn socket_request(_callback_idx: u32) -> Self {
+            Self
+        }
+
+        pub(crate) fn command(_cmd: &Cmd) -> Self {
+            Self
+        }
+
+        pub(crate) fn transaction(_pipeline: &redis::Pipeline) -> Self {
+            Self
+        }
+
+        pub(crate) fn batch(_pipeline: &redis::Pipeline) -> Self {
+            Self
+        }
+
+        pub(crate) fn script(_hash: &str) -> Self {
+            Self
+        }
+
+        pub(crate) fn record_outcome<T>(&self, _result: &RedisResult<T>) {}
+
+        pub(crate) fn instrument<F: Future>(&self, future: F) -> F {
+            future
+        }
+    }
+
+    pub(crate) fn record_node(_node: &str) {}
+
+    pub(crate) fn record_retry() {}
+
+    pub(crate) fn record_redirect() {}
+}
+
+#[cfg(all(test, feature = "otel-tracing"))]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::Layer;
+
+    /// Keeps the last value of every span field, by field name.
+    #[derive(Clone, Default)]
+    struct RecordedFields(Arc<Mutex<HashMap<String, String>>>);
+
+    impl Visit for RecordedFields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+
+    impl<S: tracing::Subscriber> Layer<S> for RecordedFields {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            attrs.record(&mut self.clone());
+        }
+
+        fn on_record(&self, _id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+            values.record(&mut self.clone());
+        }
+    }
+
+    #[test]
+    fn command_span_counts_retries_and_redirects() {
+        let fields = RecordedFields::default();
+        let subscriber = tracing_subscriber::registry().with(fields.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let span = RequestSpan::command(&redis::cmd("GET"));
+            futures::executor::block_on(span.instrument(async {
+                record_node("primary:6379");
+                record_retry();
+                record_retry();
+                record_redirect();
+            }));
+            span.record_outcome::<()>(&Ok(()));
+        });
+
+        let fields = fields.0.lock().unwrap();
+        assert_eq!(fields["command"], "GET");
+        assert_eq!(fields["node"], "primary:6379");
+        assert_eq!(fields["retries"], "2");
+        assert_eq!(fields["redirects"], "1");
+        assert_eq!(fields["outcome"], "ok");
+    }
+
+    #[test]
+    fn counters_belong_to_the_innermost_span() {
+        let fields = RecordedFields::default();
+        let subscriber = tracing_subscriber::registry().with(fields.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let script = RequestSpan::script("hash");
+            futures::executor::block_on(script.instrument(async {
+                let command = RequestSpan::command(&redis::cmd("EVALSHA"));
+                command.instrument(async { record_retry() }).await;
+                // Recorded into the script's span, whose count starts from 0.
+                record_retry();
+            }));
+        });
+
+        assert_eq!(fields.0.lock().unwrap()["retries"], "1");
+    }
+}
@@ -0,0 +1,331 @@
+use super::auth_provider::AuthProvider;
+use super::multi_node::NodeResults;
+use super::statistics::{ClientStatistics, StatisticsSnapshot};
+use super::{
+    get_connection_info, get_tls_params, run_with_timeout, ConnectionError, StandaloneClient,
+    DEFAULT_CONNECTION_ATTEMPT_TIMEOUT, DEFAULT_RESPONSE_TIMEOUT, HEARTBEAT_SLEEP_DURATION,
+};
+use crate::connection_request::{ConnectionRequest, NodeAddress};
+use futures::StreamExt;
+use logger_core::{log_info, log_warn};
+use redis::aio::MultiplexedConnection;
+use redis::cluster_routing::RoutingInfo;
+use redis::{ErrorKind, RedisConnectionInfo, RedisResult, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+const SWITCH_MASTER_CHANNEL: &str = "+switch-master";
+
+struct InnerSentinelClient {
+    /// A client for the primary and replicas that the sentinels report. Its connections are re-pointed after a failover.
+    standalone_client: StandaloneClient,
+    sentinels: Vec<redis::Client>,
+    master_name: String,
+    switch_master_listener: JoinHandle<()>,
+}
+
+impl Drop for InnerSentinelClient {
+    fn drop(&mut self) {
+        self.switch_master_listener.abort();
+    }
+}
+
+impl InnerSentinelClient {
+    /// Re-points the standalone client's connections at the nodes that the sentinels report, so that requests in flight
+    /// and the connections' subscriptions are kept.
+    async fn handle_master_switch(&self) {
+        match resolve_addresses(&self.sentinels, &self.master_name).await {
+            Ok(addresses) => self.standalone_client.update_nodes(&addresses).await,
+            Err(err) => log_warn(
+                "sentinel",
+                format!("failed to resolve the nodes after the master switched: {err}"),
+            ),
+        }
+    }
+}
+
+/// A client that discovers the primary and replicas of a monitored master through Redis Sentinel,
+/// and reconnects to the new primary when the sentinels announce a failover.
+#[derive(Clone)]
+pub struct SentinelClient {
+    inner: Arc<InnerSentinelClient>,
+}
+
+impl SentinelClient {
+    pub async fn create_client(
+        connection_request: ConnectionRequest,
+    ) -> Result<Self, ConnectionError> {
+        Self::create_client_with_auth_provider(connection_request, None, Default::default()).await
+    }
+
+    /// Like [SentinelClient::create_client], but the nodes' connections authenticate with tokens from `auth_provider`, if it is set.
+    /// Node latencies are recorded in `statistics`.
+    pub(super) async fn create_client_with_auth_provider(
+        mut connection_request: ConnectionRequest,
+        auth_provider: Option<Arc<dyn AuthProvider>>,
+        statistics: ClientStatistics,
+    ) -> Result<Self, ConnectionError> {
+        let sentinel_configuration = connection_request
+            .sentinel_configuration
+            .take()
+            .unwrap_or_default();
+        let master_name = sentinel_configuration.master_name.to_string();
+        let tls_mode = connection_request.tls_mode.enum_value_or_default();
+        let tls_params = get_tls_params(&connection_request).map_err(ConnectionError::Sentinel)?;
+        let sentinels: Vec<_> = sentinel_configuration
+            .sentinel_addresses
+            .iter()
+            .map(|address| {
+                redis::Client::open(get_connection_info(
+                    address,
+                    tls_mode,
+                    tls_params.clone(),
+                    RedisConnectionInfo::default(),
+                ))
+                .unwrap() // can unwrap, because [open] fails only on trying to convert input to ConnectionInfo, and we pass ConnectionInfo.
+            })
+            .collect();
+
+        let standalone_client = create_standalone_client(
+            &sentinels,
+            &master_name,
+            &connection_request,
+            &auth_provider,
+            &statistics,
+        )
+        .await?;
+
+        let inner = Arc::new_cyclic(|weak_inner| InnerSentinelClient {
+            standalone_client,
+            sentinels: sentinels.clone(),
+            master_name: master_name.clone(),
+            switch_master_listener: tokio::spawn(listen_for_master_switches(
+                weak_inner.clone(),
+                sentinels,
+                master_name,
+            )),
+        });
+        Ok(Self { inner })
+    }
+
+    fn get_standalone_client(&self) -> StandaloneClient {
+        self.inner.standalone_client.clone()
+    }
+
+    pub async fn send_command(&mut self, cmd: &redis::Cmd) -> RedisResult<Value> {
+        self.get_standalone_client().send_command(cmd).await
+    }
+
+    pub async fn route_command(
+        &mut self,
+        cmd: &redis::Cmd,
+        routing: RoutingInfo,
+    ) -> RedisResult<Value> {
+        self.get_standalone_client()
+            .route_command(cmd, routing)
+            .await
+    }
+
+    pub(super) fn add_connection_events(&self, snapshot: &mut StatisticsSnapshot) {
+        self.get_standalone_client().add_connection_events(snapshot);
+    }
+
+    pub(super) fn primary_address(&self) -> String {
+        self.get_standalone_client().primary_address()
+    }
+
+    pub(super) async fn get_dedicated_primary_connection(
+        &self,
+    ) -> RedisResult<MultiplexedConnection> {
+        self.get_standalone_client()
+            .get_dedicated_primary_connection()
+            .await
+    }
+
+    pub(super) async fn send_request_to_nodes(
+        &self,
+        cmd: &redis::Cmd,
+        primaries_only: bool,
+        request_timeout: Option<Duration>,
+    ) -> NodeResults {
+        self.get_standalone_client()
+            .send_request_to_nodes(cmd, primaries_only, request_timeout)
+            .await
+    }
+
+    /// Replaces the password of the nodes' connections, which keep it when they're re-pointed after a failover.
+    pub async fn update_password(&self, password: Option<String>) -> RedisResult<()> {
+        self.get_standalone_client().update_password(password).await
+    }
+
+    pub async fn send_pipeline(
+        &mut self,
+        pipeline: &redis::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisResult<Vec<Value>> {
+        self.get_standalone_client()
+            .send_pipeline(pipeline, offset, count)
+            .await
+    }
+}
+
+async fn create_standalone_client(
+    sentinels: &[redis::Client],
+    master_name: &str,
+    connection_request: &ConnectionRequest,
+    auth_provider: &Option<Arc<dyn AuthProvider>>,
+    statistics: &ClientStatistics,
+) -> Result<StandaloneClient, ConnectionError> {
+    let addresses = resolve_addresses(sentinels, master_name)
+        .await
+        .map_err(ConnectionError::Sentinel)?;
+    let mut connection_request = connection_request.clone();
+    connection_request.addresses = addresses;
+    StandaloneClient::create_client_with_auth_provider(
+        connection_request,
+        auth_provider.clone(),
+        statistics.clone(),
+    )
+    .await
+    .map_err(ConnectionError::Standalone)
+}
+
+/// Returns the addresses of the master and its replicas, according to the first sentinel that responds.
+async fn resolve_addresses(
+    sentinels: &[redis::Client],
+    master_name: &str,
+) -> RedisResult<Vec<NodeAddress>> {
+    let mut last_error = None;
+    for sentinel in sentinels {
+        match query_sentinel(sentinel, master_name).await {
+            Ok(addresses) => return Ok(addresses),
+            Err(err) => {
+                log_warn(
+                    "sentinel",
+                    format!(
+                        "failed to resolve `{master_name}` from {}: {err}",
+                        sentinel.get_connection_info().addr
+                    ),
+                );
+                last_error = Some(err);
+            }
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        (
+            ErrorKind::InvalidClientConfig,
+            "No sentinel addresses provided",
+        )
+            .into()
+    }))
+}
+
+async fn query_sentinel(
+    sentinel: &redis::Client,
+    master_name: &str,
+) -> RedisResult<Vec<NodeAddress>> {
+    let mut connection = run_with_timeout(
+        DEFAULT_CONNECTION_ATTEMPT_TIMEOUT,
+        sentinel.get_multiplexed_async_connection(),
+    )
+    .await?;
+
+    let master = run_with_timeout(
+        DEFAULT_RESPONSE_TIMEOUT,
+        connection.send_packed_command(
+            redis::cmd("SENTINEL")
+                .arg("GET-MASTER-ADDR-BY-NAME")
+                .arg(master_name),
+        ),
+    )
+    .await?;
+    if master == Value::Nil {
+        return Err((
+            ErrorKind::ResponseError,
+            "Sentinel doesn't monitor the master",
+            master_name.to_string(),
+        )
+            .into());
+    }
+    let (host, port): (String, u16) = redis::from_redis_value(&master)?;
+    let mut addresses = vec![node_address(host, port)];
+
+    let replicas = run_with_timeout(
+        DEFAULT_RESPONSE_TIMEOUT,
+        connection.send_packed_command(redis::cmd("SENTINEL").arg("REPLICAS").arg(master_name)),
+    )
+    .await?;
+    let replicas: Vec<HashMap<String, String>> = redis::from_redis_value(&replicas)?;
+    addresses.extend(replicas.into_iter().filter_map(|replica| {
+        let flags = replica.get("flags")?;
+        if flags.contains("s_down") || flags.contains("o_down") || flags.contains("disconnected") {
+            return None;
+        }
+        let port = replica.get("port")?.parse().ok()?;
+        Some(node_address(replica.get("ip")?.clone(), port))
+    }));
+    Ok(addresses)
+}
+
+fn node_address(host: String, port: u16) -> NodeAddress {
+    NodeAddress {
+        host: host.into(),
+        port: port as u32,
+        ..Default::default()
+    }
+}
+
+/// Subscribes to master switch announcements, and re-points the standalone client's connections when the monitored master
+/// switches. If the subscription disconnects, the next sentinel is used, and the nodes are resolved again in case a switch
+/// was missed.
+async fn listen_for_master_switches(
+    client: Weak<InnerSentinelClient>,
+    sentinels: Vec<redis::Client>,
+    master_name: String,
+) {
+    let mut subscribed_before = false;
+    loop {
+        for sentinel in sentinels.iter() {
+            let result = async {
+                let mut pubsub = sentinel.get_async_pubsub().await?;
+                pubsub.subscribe(SWITCH_MASTER_CHANNEL).await?;
+                if subscribed_before {
+                    let Some(client) = client.upgrade() else {
+                        return Ok(());
+                    };
+                    client.handle_master_switch().await;
+                }
+                subscribed_before = true;
+
+                let mut messages = pubsub.on_message();
+                while let Some(message) = messages.next().await {
+                    // The payload is "<master name> <old ip> <old port> <new ip> <new port>".
+                    let payload: String = message.get_payload()?;
+                    if payload.split_whitespace().next() != Some(master_name.as_str()) {
+                        continue;
+                    }
+                    let Some(client) = client.upgrade() else {
+                        return Ok(());
+                    };
+                    log_info("sentinel", format!("master switched: {payload}"));
+                    client.handle_master_switch().await;
+                }
+                RedisResult::Ok(())
+            }
+            .await;
+            if client.strong_count() == 0 {
+                return;
+            }
+            if let Err(err) = result {
+                log_warn(
+                    "sentinel",
+                    format!("lost the master switch subscription: {err}"),
+                );
+            }
+        }
+        tokio::time::sleep(HEARTBEAT_SLEEP_DURATION).await;
+    }
+}
@@ -1,31 +1,107 @@
-use super::get_redis_connection_info;
+use super::auth_provider::AuthProvider;
+use super::blocking_commands::is_blocking_cmd;
+use super::multi_node::{node_results_to_map, send_to_node_with_timeout, NodeResults};
 use super::reconnecting_connection::ReconnectingConnection;
+use super::request_tracing;
+use super::statistics::{ClientStatistics, StatisticsSnapshot};
+use super::tls_server_name::NodeTlsParams;
+use super::{
+    chars_to_string_option, format_address, get_node_tls_params, get_redis_connection_info,
+    run_with_timeout, DEFAULT_RESPONSE_TIMEOUT,
+};
 use crate::connection_request::{ConnectionRequest, NodeAddress, TlsMode};
 use crate::retry_strategies::RetryStrategy;
 use futures::{future, stream, StreamExt};
-#[cfg(standalone_heartbeat)]
-use logger_core::log_debug;
-use logger_core::log_warn;
-use protobuf::EnumOrUnknown;
-use redis::cluster_routing::{self, is_readonly_cmd, ResponsePolicy, Routable, RoutingInfo};
+use logger_core::{log_debug, log_info, log_warn};
+use rand::seq::SliceRandom;
+use redis::aio::MultiplexedConnection;
+use redis::cluster_routing::{
+    self, is_readonly_cmd, MultipleNodeRoutingInfo, ResponsePolicy, Routable, RoutingInfo,
+    SingleNodeRoutingInfo, SlotAddr,
+};
 use redis::{RedisError, RedisResult, Value};
-use std::sync::atomic::AtomicUsize;
-use std::sync::Arc;
-#[cfg(standalone_heartbeat)]
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 use tokio::task;
 
+/// The interval between checks of the nodes' roles, used to detect failovers.
+const PRIMARY_DISCOVERY_INTERVAL: Duration = Duration::from_secs(5);
+
 enum ReadFrom {
     Primary,
     PreferReplica {
         latest_read_replica_index: Arc<std::sync::atomic::AtomicUsize>,
     },
+    LowestLatency,
+    AZAffinity {
+        client_az: String,
+        latest_read_replica_index: Arc<std::sync::atomic::AtomicUsize>,
+    },
 }
 
 struct DropWrapper {
-    /// Connection to the primary node in the client.
-    primary_index: usize,
+    /// Connection to the primary node in the client. Updated when another node is promoted to primary.
+    primary_index: AtomicUsize,
     nodes: Vec<ReconnectingConnection>,
     read_from: ReadFrom,
+    /// Held while the nodes' roles are being checked, so that concurrent failures wait for a single check.
+    primary_discovery: tokio::sync::Mutex<()>,
+    /// The number of checks of the nodes' roles that started, so that callers that waited for a check that started after
+    /// they called don't start another one.
+    primary_discoveries_started: AtomicU64,
+    statistics: ClientStatistics,
+}
+
+impl DropWrapper {
+    fn get_primary_index(&self) -> usize {
+        self.primary_index.load(Ordering::Acquire)
+    }
+
+    /// Checks the roles of the connected nodes, and switches to a new primary if the current one was demoted.
+    /// If a check is already in progress, waits for it to finish. A check that started before this call might have missed
+    /// the failover, so it's followed by another check, which callers that are still waiting share.
+    async fn update_primary(&self) {
+        let started_before_call = self.primary_discoveries_started.load(Ordering::Acquire);
+        let _discovery = self.primary_discovery.lock().await;
+        if self.primary_discoveries_started.load(Ordering::Acquire) != started_before_call {
+            return;
+        }
+        self.primary_discoveries_started
+            .fetch_add(1, Ordering::AcqRel);
+        let roles = future::join_all(self.nodes.iter().map(|node| async move {
+            let mut connection = node.try_get_connection().await?;
+            run_with_timeout(
+                DEFAULT_RESPONSE_TIMEOUT,
+                connection.send_packed_command(redis::cmd("INFO").arg("REPLICATION")),
+            )
+            .await
+            .ok()
+            .map(|replication_status| is_primary(&replication_status))
+        }))
+        .await;
+
+        let current_index = self.get_primary_index();
+        if roles[current_index] == Some(true) {
+            return;
+        }
+        // The current primary was demoted or is unreachable, so any node that reports being a primary replaces it.
+        let Some(new_index) = roles.iter().position(|role| *role == Some(true)) else {
+            if roles[current_index] == Some(false) {
+                log_warn(
+                    "primary discovery",
+                    "The primary was demoted, but no other node reports being a primary",
+                );
+            }
+            return;
+        };
+        // The primary is only changed by checks, which hold the lock.
+        self.primary_index.store(new_index, Ordering::Release);
+        log_info(
+            "primary discovery",
+            format!("Switched primary from node {current_index} to node {new_index}"),
+        );
+    }
 }
 
 impl Drop for DropWrapper {
@@ -43,6 +119,7 @@ pub struct StandaloneClient {
 
 pub enum StandaloneClientConnectionError {
     NoAddressesProvided,
+    InvalidTlsConfiguration(RedisError),
     FailedConnection(Vec<(String, RedisError)>),
 }
 
@@ -52,6 +129,9 @@ impl std::fmt::Debug for StandaloneClientConnectionError {
             StandaloneClientConnectionError::NoAddressesProvided => {
                 write!(f, "No addresses provided")
             }
+            StandaloneClientConnectionError::InvalidTlsConfiguration(err) => {
+                write!(f, "Invalid TLS configuration: {err}")
+            }
             StandaloneClientConnectionError::FailedConnection(errs) => {
                 writeln!(f, "Received errors:")?;
                 for (address, error) in errs {
@@ -66,6 +146,16 @@ impl std::fmt::Debug for StandaloneClientConnectionError {
 impl StandaloneClient {
     pub async fn create_client(
         connection_request: ConnectionRequest,
+    ) -> Result<Self, StandaloneClientConnectionError> {
+        Self::create_client_with_auth_provider(connection_request, None, Default::default()).await
+    }
+
+    /// Creates a client whose connections authenticate with tokens from `auth_provider`, if it is set.
+    /// Node latencies are recorded in `statistics`.
+    pub(super) async fn create_client_with_auth_provider(
+        connection_request: ConnectionRequest,
+        auth_provider: Option<Arc<dyn AuthProvider>>,
+        statistics: ClientStatistics,
     ) -> Result<Self, StandaloneClientConnectionError> {
         if connection_request.addresses.is_empty() {
             return Err(StandaloneClientConnectionError::NoAddressesProvided);
@@ -74,6 +164,8 @@ impl StandaloneClient {
         let redis_connection_info = get_redis_connection_info(&connection_request);
 
         let tls_mode = connection_request.tls_mode.enum_value_or_default();
+        let tls_params = get_node_tls_params(&connection_request)
+            .map_err(StandaloneClientConnectionError::InvalidTlsConfiguration)?;
         let node_count = connection_request.addresses.len();
         let mut stream = stream::iter(connection_request.addresses.iter())
             .map(|address| async {
@@ -82,9 +174,11 @@ impl StandaloneClient {
                     &retry_strategy,
                     &redis_connection_info,
                     tls_mode,
+                    &tls_params,
+                    &auth_provider,
                 )
                 .await
-                .map_err(|err| (format!("{}:{}", address.host, address.port), err))
+                .map_err(|err| (format_address(address), err))
             })
             .buffer_unordered(node_count);
 
@@ -95,10 +189,7 @@ impl StandaloneClient {
             match result {
                 Ok((connection, replication_status)) => {
                     nodes.push(connection);
-                    if primary_index.is_none()
-                        && redis::from_redis_value::<String>(&replication_status)
-                            .is_ok_and(|val| val.contains("role:master"))
-                    {
+                    if primary_index.is_none() && is_primary(&replication_status) {
                         primary_index = Some(nodes.len() - 1);
                     }
                 }
@@ -122,30 +213,127 @@ impl StandaloneClient {
                 ),
             );
         }
-        let read_from = get_read_from(&connection_request.read_from);
+        let read_from = get_read_from(&connection_request, &nodes).await;
 
         #[cfg(standalone_heartbeat)]
         for node in nodes.iter() {
             Self::start_heartbeat(node.clone());
         }
 
-        Ok(Self {
-            inner: Arc::new(DropWrapper {
-                primary_index,
-                nodes,
-                read_from,
-            }),
-        })
+        if matches!(read_from, ReadFrom::LowestLatency) {
+            for node in nodes.iter() {
+                Self::start_latency_probe(node.clone());
+            }
+        }
+
+        let inner = Arc::new(DropWrapper {
+            primary_index: AtomicUsize::new(primary_index),
+            nodes,
+            read_from,
+            primary_discovery: tokio::sync::Mutex::new(()),
+            primary_discoveries_started: AtomicU64::new(0),
+            statistics,
+        });
+        if inner.nodes.len() > 1 {
+            Self::start_primary_discovery(Arc::downgrade(&inner));
+        }
+
+        Ok(Self { inner })
     }
 
     fn get_primary_connection(&self) -> &ReconnectingConnection {
-        self.inner.nodes.get(self.inner.primary_index).unwrap()
+        self.inner
+            .nodes
+            .get(self.inner.get_primary_index())
+            .unwrap()
     }
 
+    /// Adds the disconnects and reconnects of the nodes' connections to a snapshot of the client's statistics.
+    pub(super) fn add_connection_events(&self, snapshot: &mut StatisticsSnapshot) {
+        for node in self.inner.nodes.iter() {
+            node.add_connection_events(snapshot);
+        }
+    }
+
+    /// Returns the primary's address as "host:port".
+    pub(super) fn primary_address(&self) -> String {
+        self.get_primary_connection().address()
+    }
+
+    /// Re-points the connections at the nodes that are reported after a failover, where the first address is the new
+    /// primary's. Connections to nodes that are still reported are kept, and connections to nodes that aren't reported
+    /// anymore are re-pointed at the new nodes. The number of connections doesn't change, so new nodes beyond it are
+    /// skipped, except for a new primary, which replaces the connection to the previous primary if no other is free.
+    pub(super) async fn update_nodes(&self, addresses: &[NodeAddress]) {
+        let Some((primary, replicas)) = addresses.split_first() else {
+            return;
+        };
+        // Holding the lock keeps a concurrent check of the nodes' roles from overriding the new primary.
+        let _discovery = self.inner.primary_discovery.lock().await;
+        let current_primary = self.inner.get_primary_index();
+        let reported: Vec<String> = addresses.iter().map(format_address).collect();
+        let mut unreported: Vec<usize> = (0..self.inner.nodes.len())
+            .filter(|index| !reported.contains(&self.inner.nodes[*index].address()))
+            .collect();
+        // The previous primary's connection is re-pointed first, so that it's the one that follows the new primary.
+        unreported.sort_by_key(|index| *index != current_primary);
+        let position = |address: &NodeAddress| {
+            let address = format_address(address);
+            self.inner
+                .nodes
+                .iter()
+                .position(|node| node.address() == address)
+        };
+
+        let primary_index = position(primary).unwrap_or_else(|| {
+            let index = unreported.first().copied().unwrap_or(current_primary);
+            unreported.retain(|unreported_index| *unreported_index != index);
+            self.inner.nodes[index].set_address(primary);
+            index
+        });
+        for replica in replicas {
+            if position(replica).is_some() {
+                continue;
+            }
+            if unreported.is_empty() {
+                log_warn(
+                    "update nodes",
+                    format!(
+                        "No connection is left for the replica {}",
+                        format_address(replica)
+                    ),
+                );
+                continue;
+            }
+            self.inner.nodes[unreported.remove(0)].set_address(replica);
+        }
+        if primary_index != current_primary {
+            self.inner
+                .primary_index
+                .store(primary_index, Ordering::Release);
+            log_info(
+                "update nodes",
+                format!("Switched primary from node {current_primary} to node {primary_index}"),
+            );
+        }
+    }
+
+    /// Opens a connection to the primary that isn't shared with other requests, for commands that change the
+    /// connection's state, such as WATCH.
+    pub(super) async fn get_dedicated_primary_connection(
+        &self,
+    ) -> RedisResult<MultiplexedConnection> {
+        self.get_primary_connection()
+            .get_dedicated_connection()
+            .await
+    }
+
+    /// Returns the next connected replica for which `is_eligible` returns true, or `None` if there's no such replica.
     fn round_robin_read_from_replica(
         &self,
         latest_read_replica_index: &Arc<AtomicUsize>,
-    ) -> &ReconnectingConnection {
+        is_eligible: impl Fn(usize) -> bool,
+    ) -> Option<&ReconnectingConnection> {
         let initial_index = latest_read_replica_index.load(std::sync::atomic::Ordering::Relaxed);
         let mut check_count = 0;
         loop {
@@ -153,10 +341,10 @@ impl StandaloneClient {
 
             // Looped through all replicas, no connected replica was found.
             if check_count > self.inner.nodes.len() {
-                return self.get_primary_connection();
+                return None;
             }
             let index = (initial_index + check_count) % self.inner.nodes.len();
-            if index == self.inner.primary_index {
+            if index == self.inner.get_primary_index() || !is_eligible(index) {
                 continue;
             }
             let Some(connection) = self.inner.nodes.get(index) else {
@@ -169,11 +357,39 @@ impl StandaloneClient {
                     std::sync::atomic::Ordering::Relaxed,
                     std::sync::atomic::Ordering::Relaxed,
                 );
-                return connection;
+                return Some(connection);
             }
         }
     }
 
+    /// Returns a random connected node for which `is_eligible` returns true, or `None` if there's no such node.
+    fn random_connected_node(
+        &self,
+        is_eligible: impl Fn(usize) -> bool,
+    ) -> Option<&ReconnectingConnection> {
+        let nodes: Vec<&ReconnectingConnection> = self
+            .inner
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(index, node)| is_eligible(*index) && node.is_connected())
+            .map(|(_, node)| node)
+            .collect();
+        nodes.choose(&mut rand::thread_rng()).copied()
+    }
+
+    fn read_from_lowest_latency_node(&self) -> &ReconnectingConnection {
+        self.inner
+            .nodes
+            .iter()
+            .filter(|node| node.is_connected())
+            .filter_map(|node| node.latency().average().map(|latency| (latency, node)))
+            .min_by_key(|(latency, _)| *latency)
+            .map(|(_, node)| node)
+            // No latency was measured yet, or all measured nodes are disconnected.
+            .unwrap_or_else(|| self.get_primary_connection())
+    }
+
     fn get_connection(&self, readonly: bool) -> &ReconnectingConnection {
         if self.inner.nodes.len() == 1 || !readonly {
             return self.get_primary_connection();
@@ -183,36 +399,92 @@ impl StandaloneClient {
             ReadFrom::Primary => self.get_primary_connection(),
             ReadFrom::PreferReplica {
                 latest_read_replica_index,
-            } => self.round_robin_read_from_replica(latest_read_replica_index),
+            } => self
+                .round_robin_read_from_replica(latest_read_replica_index, |_| true)
+                .unwrap_or_else(|| self.get_primary_connection()),
+            ReadFrom::LowestLatency => self.read_from_lowest_latency_node(),
+            ReadFrom::AZAffinity {
+                client_az,
+                latest_read_replica_index,
+            } => self
+                .round_robin_read_from_replica(latest_read_replica_index, |index| {
+                    self.inner.nodes[index].availability_zone().as_deref()
+                        == Some(client_az.as_str())
+                })
+                // No replica in the client's zone is connected, so other zones are used.
+                .or_else(|| self.round_robin_read_from_replica(latest_read_replica_index, |_| true))
+                .unwrap_or_else(|| self.get_primary_connection()),
         }
     }
 
     async fn send_request(
+        &self,
         cmd: &redis::Cmd,
         reconnecting_connection: &ReconnectingConnection,
     ) -> RedisResult<Value> {
         let mut connection = reconnecting_connection.get_connection().await?;
+        let start = Instant::now();
         let result = connection.send_packed_command(cmd).await;
         match result {
             Err(err) if err.is_connection_dropped() => {
                 log_warn("send request", format!("received disconnect error `{err}`"));
+                reconnecting_connection.latency().reset();
                 reconnecting_connection.reconnect();
                 Err(err)
             }
+            // Blocking commands wait on the server, so their round-trip time doesn't reflect the node's latency.
+            Ok(_) if !is_blocking_cmd(cmd) => {
+                let latency = start.elapsed();
+                reconnecting_connection.latency().record(latency);
+                self.inner
+                    .statistics
+                    .record_node_latency(&reconnecting_connection.address(), latency);
+                result
+            }
             _ => result,
         }
     }
 
-    async fn send_request_to_all_nodes(
+    fn get_nodes(&self, primaries_only: bool) -> Vec<&ReconnectingConnection> {
+        if primaries_only {
+            vec![self.get_primary_connection()]
+        } else {
+            self.inner.nodes.iter().collect()
+        }
+    }
+
+    /// Sends the command to every node, or only to the primary if `primaries_only` is set, and returns each node's result.
+    /// Each node's request has its own deadline, so a node that times out only fails its own result.
+    pub(super) async fn send_request_to_nodes(
+        &self,
+        cmd: &redis::Cmd,
+        primaries_only: bool,
+        request_timeout: Option<Duration>,
+    ) -> NodeResults {
+        future::join_all(
+            self.get_nodes(primaries_only)
+                .into_iter()
+                .map(|node| async move {
+                    (
+                        node.address(),
+                        send_to_node_with_timeout(request_timeout, self.send_request(cmd, node))
+                            .await,
+                    )
+                }),
+        )
+        .await
+    }
+
+    async fn send_request_to_multiple_nodes(
         &mut self,
         cmd: &redis::Cmd,
+        primaries_only: bool,
         response_policy: Option<ResponsePolicy>,
     ) -> RedisResult<Value> {
         let requests = self
-            .inner
-            .nodes
-            .iter()
-            .map(|node| Self::send_request(cmd, node));
+            .get_nodes(primaries_only)
+            .into_iter()
+            .map(|node| self.send_request(cmd, node));
 
         // TODO - once Value::Error will be merged, these will need to be updated to handle this new value.
         match response_policy {
@@ -249,9 +521,9 @@ impl StandaloneClient {
                 .await
                 .and_then(cluster_routing::combine_array_results),
             Some(ResponsePolicy::Special) | None => {
-                // This is our assumption - if there's no coherent way to aggregate the responses, we just collect them in an array, and pass it to the user.
-                // TODO - once Value::Error is merged, we can use join_all and report separate errors and also pass successes.
-                future::try_join_all(requests).await.map(Value::Array)
+                // There's no coherent way to aggregate the responses, so each node's response is returned under its address, as in cluster mode.
+                // A value can't hold the errors of some of the nodes, so callers that need partial results use `send_request_to_nodes`.
+                node_results_to_map(self.send_request_to_nodes(cmd, primaries_only, None).await)
             }
         }
     }
@@ -262,7 +534,24 @@ impl StandaloneClient {
         readonly: bool,
     ) -> RedisResult<Value> {
         let reconnecting_connection = self.get_connection(readonly);
-        Self::send_request(cmd, reconnecting_connection).await
+        request_tracing::record_node(&reconnecting_connection.address());
+        let result = self.send_request(cmd, reconnecting_connection).await;
+        match result {
+            Err(err) if err.kind() == redis::ErrorKind::ReadOnly => {
+                // The node was demoted. If another node was promoted, whether by this request's check of the nodes'
+                // roles or by a concurrent one, the request is retried on it.
+                self.inner.update_primary().await;
+                let primary = self.get_primary_connection();
+                if primary.address() != reconnecting_connection.address() {
+                    request_tracing::record_retry();
+                    request_tracing::record_node(&primary.address());
+                    self.send_request(cmd, primary).await
+                } else {
+                    Err(err)
+                }
+            }
+            _ => result,
+        }
     }
 
     pub async fn send_command(&mut self, cmd: &redis::Cmd) -> RedisResult<Value> {
@@ -272,12 +561,93 @@ impl StandaloneClient {
 
         if RoutingInfo::is_all_nodes(cmd_bytes.as_slice()) {
             let response_policy = ResponsePolicy::for_command(cmd_bytes.as_slice());
-            return self.send_request_to_all_nodes(cmd, response_policy).await;
+            return self
+                .send_request_to_multiple_nodes(cmd, false, response_policy)
+                .await;
         }
         self.send_request_to_single_node(cmd, is_readonly_cmd(cmd_bytes.as_slice()))
             .await
     }
 
+    /// Sends the command to the nodes that `routing` selects, instead of the nodes that the command is routed to by default.
+    /// Routes to all primaries only reach the primary. Slot routes ignore the slot, and reach the primary or a replica
+    /// according to their slot type, where a route that requires a replica fails if no replica is connected.
+    pub async fn route_command(
+        &mut self,
+        cmd: &redis::Cmd,
+        routing: RoutingInfo,
+    ) -> RedisResult<Value> {
+        match routing {
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random) => {
+                let node = self
+                    .random_connected_node(|_| true)
+                    .unwrap_or_else(|| self.get_primary_connection());
+                request_tracing::record_node(&node.address());
+                self.send_request(cmd, node).await
+            }
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route)) => {
+                match route.slot_addr() {
+                    SlotAddr::Master => self.send_request_to_single_node(cmd, false).await,
+                    SlotAddr::ReplicaOptional => self.send_request_to_single_node(cmd, true).await,
+                    SlotAddr::ReplicaRequired => {
+                        let primary_index = self.inner.get_primary_index();
+                        let Some(replica) =
+                            self.random_connected_node(|index| index != primary_index)
+                        else {
+                            return Err((
+                                redis::ErrorKind::ClientError,
+                                "The command requires a replica, but no replica is connected",
+                            )
+                                .into());
+                        };
+                        request_tracing::record_node(&replica.address());
+                        self.send_request(cmd, replica).await
+                    }
+                }
+            }
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port }) => {
+                self.send_request_to_address(cmd, &host, port).await
+            }
+            RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllNodes, response_policy)) => {
+                self.send_request_to_multiple_nodes(cmd, false, response_policy)
+                    .await
+            }
+            RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllMasters, response_policy)) => {
+                self.send_request_to_multiple_nodes(cmd, true, response_policy)
+                    .await
+            }
+            // The keys of all slots are served by the primary.
+            RoutingInfo::MultiNode((MultipleNodeRoutingInfo::MultiSlot(_), _)) => {
+                self.send_command(cmd).await
+            }
+        }
+    }
+
+    /// Sends the command to the node with the given address, which must be one of the client's nodes.
+    async fn send_request_to_address(
+        &mut self,
+        cmd: &redis::Cmd,
+        host: &str,
+        port: u16,
+    ) -> RedisResult<Value> {
+        let address = format!("{host}:{port}");
+        let Some(node) = self
+            .inner
+            .nodes
+            .iter()
+            .find(|node| node.address() == address)
+        else {
+            return Err((
+                redis::ErrorKind::ClientError,
+                "The address isn't one of the client's nodes",
+                address,
+            )
+                .into());
+        };
+        request_tracing::record_node(&address);
+        self.send_request(cmd, node).await
+    }
+
     pub async fn send_pipeline(
         &mut self,
         pipeline: &redis::Pipeline,
@@ -298,10 +668,75 @@ impl StandaloneClient {
                 reconnecting_connection.reconnect();
                 Err(err)
             }
+            Err(err) if err.kind() == redis::ErrorKind::ReadOnly => {
+                // The transaction isn't retried, since some of its commands might have been applied.
+                self.inner.update_primary().await;
+                Err(err)
+            }
             _ => result,
         }
     }
 
+    /// Re-authenticates the connected nodes with the password, and replaces the password of every node's connection that
+    /// re-authenticated or is reconnecting. Every node is updated even if some of them fail to re-authenticate, and the
+    /// first failure is returned.
+    pub async fn update_password(&self, password: Option<String>) -> RedisResult<()> {
+        future::join_all(
+            self.inner
+                .nodes
+                .iter()
+                .map(|node| node.update_password(password.clone())),
+        )
+        .await
+        .into_iter()
+        .collect()
+    }
+
+    /// Periodically checks the nodes' roles, so that failovers are detected even when no write fails.
+    fn start_primary_discovery(inner: Weak<DropWrapper>) {
+        task::spawn(async move {
+            loop {
+                tokio::time::sleep(PRIMARY_DISCOVERY_INTERVAL).await;
+                let Some(inner) = inner.upgrade() else {
+                    log_debug(
+                        "StandaloneClient",
+                        "primary discovery stopped after client was dropped",
+                    );
+                    return;
+                };
+                inner.update_primary().await;
+            }
+        });
+    }
+
+    /// Periodically measures the node's latency, so that nodes that don't receive requests are still considered for reads.
+    fn start_latency_probe(reconnecting_connection: ReconnectingConnection) {
+        task::spawn(async move {
+            loop {
+                tokio::time::sleep(super::HEARTBEAT_SLEEP_DURATION).await;
+                if reconnecting_connection.is_dropped() {
+                    log_debug(
+                        "StandaloneClient",
+                        "latency probe stopped after connection was dropped",
+                    );
+                    return;
+                }
+
+                let Some(mut connection) = reconnecting_connection.try_get_connection().await
+                else {
+                    // The node can't serve reads while it's reconnecting.
+                    reconnecting_connection.latency().reset();
+                    continue;
+                };
+                let start = Instant::now();
+                match connection.send_packed_command(&redis::cmd("PING")).await {
+                    Ok(_) => reconnecting_connection.latency().record(start.elapsed()),
+                    Err(_) => reconnecting_connection.latency().reset(),
+                }
+            }
+        });
+    }
+
     #[cfg(standalone_heartbeat)]
     fn start_heartbeat(reconnecting_connection: ReconnectingConnection) {
         task::spawn(async move {
@@ -344,12 +779,17 @@ async fn get_connection_and_replication_info(
     retry_strategy: &RetryStrategy,
     connection_info: &redis::RedisConnectionInfo,
     tls_mode: TlsMode,
+    tls_params: &NodeTlsParams,
+    auth_provider: &Option<Arc<dyn AuthProvider>>,
 ) -> Result<(ReconnectingConnection, Value), (ReconnectingConnection, RedisError)> {
     let result = ReconnectingConnection::new(
         address,
         retry_strategy.clone(),
         connection_info.clone(),
         tls_mode,
+        tls_params.clone(),
+        None,
+        auth_provider.clone(),
     )
     .await;
     let reconnecting_connection = match result {
@@ -365,22 +805,50 @@ async fn get_connection_and_replication_info(
         }
     };
 
+    let start = Instant::now();
     match multiplexed_connection
         .send_packed_command(redis::cmd("INFO").arg("REPLICATION"))
         .await
     {
-        Ok(replication_status) => Ok((reconnecting_connection, replication_status)),
+        Ok(replication_status) => {
+            reconnecting_connection.latency().record(start.elapsed());
+            Ok((reconnecting_connection, replication_status))
+        }
         Err(err) => Err((reconnecting_connection, err)),
     }
 }
 
-fn get_read_from(read_from: &EnumOrUnknown<crate::connection_request::ReadFrom>) -> ReadFrom {
-    match read_from.enum_value_or_default() {
+fn is_primary(replication_status: &Value) -> bool {
+    redis::from_redis_value::<String>(replication_status)
+        .is_ok_and(|val| val.contains("role:master"))
+}
+
+async fn get_read_from(
+    connection_request: &ConnectionRequest,
+    nodes: &[ReconnectingConnection],
+) -> ReadFrom {
+    match connection_request.read_from.enum_value_or_default() {
         crate::connection_request::ReadFrom::Primary => ReadFrom::Primary,
         crate::connection_request::ReadFrom::PreferReplica => ReadFrom::PreferReplica {
             latest_read_replica_index: Default::default(),
         },
-        crate::connection_request::ReadFrom::LowestLatency => todo!(),
-        crate::connection_request::ReadFrom::AZAffinity => todo!(),
+        crate::connection_request::ReadFrom::LowestLatency => ReadFrom::LowestLatency,
+        crate::connection_request::ReadFrom::AZAffinity => {
+            let Some(client_az) = chars_to_string_option(&connection_request.client_az) else {
+                log_warn(
+                    "client creation",
+                    "AZAffinity requires a client availability zone, reading from any replica instead",
+                );
+                return ReadFrom::PreferReplica {
+                    latest_read_replica_index: Default::default(),
+                };
+            };
+            // Nodes that aren't connected yet read their zone once they connect.
+            future::join_all(nodes.iter().map(|node| node.discover_availability_zone())).await;
+            ReadFrom::AZAffinity {
+                client_az,
+                latest_read_replica_index: Default::default(),
+            }
+        }
     }
 }
@@ -0,0 +1,845 @@
+use once_cell::sync::Lazy;
+use redis::cluster_routing::Routable;
+use redis::{Cmd, RedisResult, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The upper bounds of the latency histogram buckets, in microseconds. Latencies above the last bound are only counted in the total.
+pub const LATENCY_BUCKET_BOUNDS_MICROS: [u64; 13] = [
+    100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000, 500_000,
+    1_000_000,
+];
+
+/// A snapshot of a latency histogram.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// The number of samples in each bucket of [LATENCY_BUCKET_BOUNDS_MICROS]. Unlike Prometheus buckets, these aren't cumulative.
+    pub bucket_counts: [u64; LATENCY_BUCKET_BOUNDS_MICROS.len()],
+    pub count: u64,
+    pub sum: Duration,
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, latency: Duration) {
+        let micros = latency.as_micros() as u64;
+        if let Some(bucket) = LATENCY_BUCKET_BOUNDS_MICROS
+            .iter()
+            .position(|bound| micros <= *bound)
+        {
+            self.bucket_counts[bucket] += 1;
+        }
+        self.count += 1;
+        self.sum += latency;
+    }
+
+    fn cumulative_counts(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        LATENCY_BUCKET_BOUNDS_MICROS
+            .iter()
+            .zip(self.bucket_counts.iter())
+            .scan(0, |total, (bound, count)| {
+                *total += count;
+                Some((*bound, *total))
+            })
+    }
+}
+
+/// A snapshot of the statistics of every client in the process, or of a single client.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StatisticsSnapshot {
+    /// The number of requests sent, by command name. Commands that aren't known are counted under [OTHER_COMMAND_NAME].
+    pub requests: HashMap<String, u64>,
+    /// The latency of requests, by the address of the node that served them.
+    /// Cluster requests are attributed to the node they're routed to by address, or to the primary that serves their slot
+    /// according to the client's cached topology. Requests to random nodes or to replicas that the cluster connection picks aren't attributed.
+    pub node_latencies: HashMap<String, LatencyHistogram>,
+    /// The number of requests that didn't complete before their timeout.
+    pub timeouts: u64,
+    /// The number of times a node's connection was lost.
+    pub disconnects: u64,
+    /// The number of times a lost connection was recreated.
+    pub reconnects: u64,
+    /// The number of MOVED and ASK redirections that reached the client. Redirections that the cluster connection follows
+    /// by itself aren't visible to the client, so they aren't counted.
+    pub redirects: u64,
+    /// The number of requests that are being handled, by the ID of the socket connection they were received on.
+    /// Only the process-wide statistics count them, since a client doesn't know the socket connections it serves.
+    pub in_flight_requests: HashMap<u64, u64>,
+}
+
+#[derive(Default)]
+struct Statistics {
+    requests: Mutex<HashMap<String, u64>>,
+    node_latencies: Mutex<HashMap<String, LatencyHistogram>>,
+    timeouts: AtomicU64,
+    disconnects: AtomicU64,
+    reconnects: AtomicU64,
+    redirects: AtomicU64,
+    in_flight_requests: Mutex<HashMap<u64, Arc<AtomicU64>>>,
+}
+
+static STATISTICS: Lazy<Statistics> = Lazy::new(Statistics::default);
+static NEXT_SOCKET_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The name that requests for commands that aren't known are counted under, so that arbitrary names don't add entries.
+pub const OTHER_COMMAND_NAME: &str = "OTHER";
+
+/// The commands that are counted by name. Container commands are listed with their subcommands.
+const KNOWN_COMMANDS: &[&str] = &[
+    "ACL CAT",
+    "ACL DELUSER",
+    "ACL DRYRUN",
+    "ACL GENPASS",
+    "ACL GETUSER",
+    "ACL HELP",
+    "ACL LIST",
+    "ACL LOAD",
+    "ACL LOG",
+    "ACL SAVE",
+    "ACL SETUSER",
+    "ACL USERS",
+    "ACL WHOAMI",
+    "APPEND",
+    "ASKING",
+    "AUTH",
+    "BGREWRITEAOF",
+    "BGSAVE",
+    "BITCOUNT",
+    "BITFIELD",
+    "BITFIELD_RO",
+    "BITOP",
+    "BITPOS",
+    "BLMOVE",
+    "BLMPOP",
+    "BLPOP",
+    "BRPOP",
+    "BRPOPLPUSH",
+    "BZMPOP",
+    "BZPOPMAX",
+    "BZPOPMIN",
+    "CLIENT CACHING",
+    "CLIENT GETNAME",
+    "CLIENT GETREDIR",
+    "CLIENT HELP",
+    "CLIENT ID",
+    "CLIENT INFO",
+    "CLIENT KILL",
+    "CLIENT LIST",
+    "CLIENT NO-EVICT",
+    "CLIENT NO-TOUCH",
+    "CLIENT PAUSE",
+    "CLIENT REPLY",
+    "CLIENT SETINFO",
+    "CLIENT SETNAME",
+    "CLIENT TRACKING",
+    "CLIENT TRACKINGINFO",
+    "CLIENT UNBLOCK",
+    "CLIENT UNPAUSE",
+    "CLUSTER ADDSLOTS",
+    "CLUSTER ADDSLOTSRANGE",
+    "CLUSTER BUMPEPOCH",
+    "CLUSTER COUNT-FAILURE-REPORTS",
+    "CLUSTER COUNTKEYSINSLOT",
+    "CLUSTER DELSLOTS",
+    "CLUSTER DELSLOTSRANGE",
+    "CLUSTER FAILOVER",
+    "CLUSTER FLUSHSLOTS",
+    "CLUSTER FORGET",
+    "CLUSTER GETKEYSINSLOT",
+    "CLUSTER HELP",
+    "CLUSTER INFO",
+    "CLUSTER KEYSLOT",
+    "CLUSTER LINKS",
+    "CLUSTER MEET",
+    "CLUSTER MYID",
+    "CLUSTER MYSHARDID",
+    "CLUSTER NODES",
+    "CLUSTER REPLICAS",
+    "CLUSTER REPLICATE",
+    "CLUSTER RESET",
+    "CLUSTER SAVECONFIG",
+    "CLUSTER SET-CONFIG-EPOCH",
+    "CLUSTER SETSLOT",
+    "CLUSTER SHARDS",
+    "CLUSTER SLAVES",
+    "CLUSTER SLOTS",
+    "COMMAND",
+    "COMMAND COUNT",
+    "COMMAND DOCS",
+    "COMMAND GETKEYS",
+    "COMMAND GETKEYSANDFLAGS",
+    "COMMAND HELP",
+    "COMMAND INFO",
+    "COMMAND LIST",
+    "CONFIG GET",
+    "CONFIG HELP",
+    "CONFIG RESETSTAT",
+    "CONFIG REWRITE",
+    "CONFIG SET",
+    "COPY",
+    "DBSIZE",
+    "DECR",
+    "DECRBY",
+    "DEL",
+    "DISCARD",
+    "DUMP",
+    "ECHO",
+    "EVAL",
+    "EVAL_RO",
+    "EVALSHA",
+    "EVALSHA_RO",
+    "EXEC",
+    "EXISTS",
+    "EXPIRE",
+    "EXPIREAT",
+    "EXPIRETIME",
+    "FAILOVER",
+    "FCALL",
+    "FCALL_RO",
+    "FLUSHALL",
+    "FLUSHDB",
+    "FUNCTION DELETE",
+    "FUNCTION DUMP",
+    "FUNCTION FLUSH",
+    "FUNCTION HELP",
+    "FUNCTION KILL",
+    "FUNCTION LIST",
+    "FUNCTION LOAD",
+    "FUNCTION RESTORE",
+    "FUNCTION STATS",
+    "GEOADD",
+    "GEODIST",
+    "GEOHASH",
+    "GEOPOS",
+    "GEORADIUS",
+    "GEORADIUS_RO",
+    "GEORADIUSBYMEMBER",
+    "GEORADIUSBYMEMBER_RO",
+    "GEOSEARCH",
+    "GEOSEARCHSTORE",
+    "GET",
+    "GETBIT",
+    "GETDEL",
+    "GETEX",
+    "GETRANGE",
+    "GETSET",
+    "HDEL",
+    "HELLO",
+    "HEXISTS",
+    "HGET",
+    "HGETALL",
+    "HINCRBY",
+    "HINCRBYFLOAT",
+    "HKEYS",
+    "HLEN",
+    "HMGET",
+    "HMSET",
+    "HRANDFIELD",
+    "HSCAN",
+    "HSET",
+    "HSETNX",
+    "HSTRLEN",
+    "HVALS",
+    "INCR",
+    "INCRBY",
+    "INCRBYFLOAT",
+    "INFO",
+    "KEYS",
+    "LASTSAVE",
+    "LATENCY DOCTOR",
+    "LATENCY GRAPH",
+    "LATENCY HELP",
+    "LATENCY HISTOGRAM",
+    "LATENCY HISTORY",
+    "LATENCY LATEST",
+    "LATENCY RESET",
+    "LCS",
+    "LINDEX",
+    "LINSERT",
+    "LLEN",
+    "LMOVE",
+    "LMPOP",
+    "LOLWUT",
+    "LPOP",
+    "LPOS",
+    "LPUSH",
+    "LPUSHX",
+    "LRANGE",
+    "LREM",
+    "LSET",
+    "LTRIM",
+    "MEMORY DOCTOR",
+    "MEMORY HELP",
+    "MEMORY MALLOC-STATS",
+    "MEMORY PURGE",
+    "MEMORY STATS",
+    "MEMORY USAGE",
+    "MGET",
+    "MIGRATE",
+    "MODULE HELP",
+    "MODULE LIST",
+    "MODULE LOAD",
+    "MODULE LOADEX",
+    "MODULE UNLOAD",
+    "MONITOR",
+    "MOVE",
+    "MSET",
+    "MSETNX",
+    "MULTI",
+    "OBJECT ENCODING",
+    "OBJECT FREQ",
+    "OBJECT HELP",
+    "OBJECT IDLETIME",
+    "OBJECT REFCOUNT",
+    "PERSIST",
+    "PEXPIRE",
+    "PEXPIREAT",
+    "PEXPIRETIME",
+    "PFADD",
+    "PFCOUNT",
+    "PFMERGE",
+    "PING",
+    "PSETEX",
+    "PSUBSCRIBE",
+    "PTTL",
+    "PUBLISH",
+    "PUBSUB CHANNELS",
+    "PUBSUB HELP",
+    "PUBSUB NUMPAT",
+    "PUBSUB NUMSUB",
+    "PUBSUB SHARDCHANNELS",
+    "PUBSUB SHARDNUMSUB",
+    "PUNSUBSCRIBE",
+    "QUIT",
+    "RANDOMKEY",
+    "READONLY",
+    "READWRITE",
+    "RENAME",
+    "RENAMENX",
+    "REPLICAOF",
+    "RESET",
+    "RESTORE",
+    "ROLE",
+    "RPOP",
+    "RPOPLPUSH",
+    "RPUSH",
+    "RPUSHX",
+    "SADD",
+    "SAVE",
+    "SCAN",
+    "SCARD",
+    "SCRIPT DEBUG",
+    "SCRIPT EXISTS",
+    "SCRIPT FLUSH",
+    "SCRIPT HELP",
+    "SCRIPT KILL",
+    "SCRIPT LOAD",
+    "SDIFF",
+    "SDIFFSTORE",
+    "SELECT",
+    "SET",
+    "SETBIT",
+    "SETEX",
+    "SETNX",
+    "SETRANGE",
+    "SHUTDOWN",
+    "SINTER",
+    "SINTERCARD",
+    "SINTERSTORE",
+    "SISMEMBER",
+    "SLAVEOF",
+    "SLOWLOG GET",
+    "SLOWLOG HELP",
+    "SLOWLOG LEN",
+    "SLOWLOG RESET",
+    "SMEMBERS",
+    "SMISMEMBER",
+    "SMOVE",
+    "SORT",
+    "SORT_RO",
+    "SPOP",
+    "SPUBLISH",
+    "SRANDMEMBER",
+    "SREM",
+    "SSCAN",
+    "SSUBSCRIBE",
+    "STRLEN",
+    "SUBSCRIBE",
+    "SUNION",
+    "SUNIONSTORE",
+    "SUNSUBSCRIBE",
+    "SWAPDB",
+    "TIME",
+    "TOUCH",
+    "TTL",
+    "TYPE",
+    "UNLINK",
+    "UNSUBSCRIBE",
+    "UNWATCH",
+    "WAIT",
+    "WAITAOF",
+    "WATCH",
+    "XACK",
+    "XADD",
+    "XAUTOCLAIM",
+    "XCLAIM",
+    "XDEL",
+    "XGROUP CREATE",
+    "XGROUP CREATECONSUMER",
+    "XGROUP DELCONSUMER",
+    "XGROUP DESTROY",
+    "XGROUP HELP",
+    "XGROUP SETID",
+    "XINFO CONSUMERS",
+    "XINFO GROUPS",
+    "XINFO HELP",
+    "XINFO STREAM",
+    "XLEN",
+    "XPENDING",
+    "XRANGE",
+    "XREAD",
+    "XREADGROUP",
+    "XREVRANGE",
+    "XSETID",
+    "XTRIM",
+    "ZADD",
+    "ZCARD",
+    "ZCOUNT",
+    "ZDIFF",
+    "ZDIFFSTORE",
+    "ZINCRBY",
+    "ZINTER",
+    "ZINTERCARD",
+    "ZINTERSTORE",
+    "ZLEXCOUNT",
+    "ZMPOP",
+    "ZMSCORE",
+    "ZPOPMAX",
+    "ZPOPMIN",
+    "ZRANDMEMBER",
+    "ZRANGE",
+    "ZRANGEBYLEX",
+    "ZRANGEBYSCORE",
+    "ZRANGESTORE",
+    "ZRANK",
+    "ZREM",
+    "ZREMRANGEBYLEX",
+    "ZREMRANGEBYRANK",
+    "ZREMRANGEBYSCORE",
+    "ZREVRANGE",
+    "ZREVRANGEBYLEX",
+    "ZREVRANGEBYSCORE",
+    "ZREVRANK",
+    "ZSCAN",
+    "ZSCORE",
+    "ZUNION",
+    "ZUNIONSTORE",
+];
+
+static KNOWN_COMMAND_SET: Lazy<HashSet<&'static str>> =
+    Lazy::new(|| KNOWN_COMMANDS.iter().copied().collect());
+
+/// Returns the command's name, including the subcommand of container commands such as `CONFIG GET`,
+/// or [OTHER_COMMAND_NAME] if the command isn't known.
+pub(super) fn command_name(cmd: &Cmd) -> String {
+    cmd.command()
+        .and_then(|name| {
+            let name = std::str::from_utf8(&name).ok()?;
+            KNOWN_COMMAND_SET.get(name).copied()
+        })
+        .unwrap_or(OTHER_COMMAND_NAME)
+        .to_string()
+}
+
+impl Statistics {
+    fn record_request(&self, cmd: &Cmd) {
+        *self
+            .requests
+            .lock()
+            .unwrap()
+            .entry(command_name(cmd))
+            .or_default() += 1;
+    }
+
+    fn record_result<T>(&self, result: &RedisResult<T>) {
+        let Err(err) = result else {
+            return;
+        };
+        if err.is_timeout() {
+            self.timeouts.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn record_node_latency(&self, node: &str, latency: Duration) {
+        let mut node_latencies = self.node_latencies.lock().unwrap();
+        match node_latencies.get_mut(node) {
+            Some(histogram) => histogram.record(latency),
+            None => {
+                let mut histogram = LatencyHistogram::default();
+                histogram.record(latency);
+                node_latencies.insert(node.to_string(), histogram);
+            }
+        }
+    }
+
+    fn snapshot(&self) -> StatisticsSnapshot {
+        StatisticsSnapshot {
+            requests: self.requests.lock().unwrap().clone(),
+            node_latencies: self.node_latencies.lock().unwrap().clone(),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            disconnects: self.disconnects.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            redirects: self.redirects.load(Ordering::Relaxed),
+            in_flight_requests: self
+                .in_flight_requests
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(socket_id, count)| (*socket_id, count.load(Ordering::Relaxed)))
+                .collect(),
+        }
+    }
+}
+
+/// The statistics of a single client. Everything that is recorded for the client is also recorded in the process-wide statistics.
+#[derive(Clone, Default)]
+pub(super) struct ClientStatistics(Arc<Statistics>);
+
+impl ClientStatistics {
+    /// Counts a request for the given command.
+    pub(super) fn record_request(&self, cmd: &Cmd) {
+        STATISTICS.record_request(cmd);
+        self.0.record_request(cmd);
+    }
+
+    /// Counts the request if it timed out.
+    pub(super) fn record_result<T>(&self, result: &RedisResult<T>) {
+        STATISTICS.record_result(result);
+        self.0.record_result(result);
+    }
+
+    pub(super) fn record_node_latency(&self, node: &str, latency: Duration) {
+        STATISTICS.record_node_latency(node, latency);
+        self.0.record_node_latency(node, latency);
+    }
+
+    pub(super) fn record_redirect(&self) {
+        STATISTICS.redirects.fetch_add(1, Ordering::Relaxed);
+        self.0.redirects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns a snapshot of the client's statistics. The connection events of the client's connections are added by the caller.
+    pub(super) fn snapshot(&self) -> StatisticsSnapshot {
+        self.0.snapshot()
+    }
+}
+
+pub(super) fn record_disconnect() {
+    STATISTICS.disconnects.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(super) fn record_reconnect() {
+    STATISTICS.reconnects.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Returns a snapshot of the request counts, latencies, and connection events of every client in the process.
+pub fn get_statistics() -> StatisticsSnapshot {
+    STATISTICS.snapshot()
+}
+
+/// Counts the requests that are being handled for a single socket connection.
+/// The socket's count is removed from the statistics once the counter is dropped.
+pub(crate) struct InFlightRequests {
+    socket_id: u64,
+    count: Arc<AtomicU64>,
+}
+
+impl InFlightRequests {
+    pub(crate) fn new() -> Self {
+        let socket_id = NEXT_SOCKET_ID.fetch_add(1, Ordering::Relaxed);
+        let count = Arc::new(AtomicU64::new(0));
+        STATISTICS
+            .in_flight_requests
+            .lock()
+            .unwrap()
+            .insert(socket_id, count.clone());
+        Self { socket_id, count }
+    }
+
+    /// Counts a request until the returned guard is dropped.
+    pub(crate) fn start_request(&self) -> InFlightRequestGuard {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        InFlightRequestGuard {
+            count: self.count.clone(),
+        }
+    }
+}
+
+impl Drop for InFlightRequests {
+    fn drop(&mut self) {
+        STATISTICS
+            .in_flight_requests
+            .lock()
+            .unwrap()
+            .remove(&self.socket_id);
+    }
+}
+
+pub(crate) struct InFlightRequestGuard {
+    count: Arc<AtomicU64>,
+}
+
+impl Drop for InFlightRequestGuard {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn bulk_string(value: impl Into<String>) -> Value {
+    Value::BulkString(value.into().into_bytes())
+}
+
+fn int(value: u64) -> Value {
+    Value::Int(value as i64)
+}
+
+/// Escapes a Prometheus label value.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Returns the entries sorted by key, so that the output is stable.
+fn sorted<K: Ord + Clone, V>(map: &HashMap<K, V>) -> Vec<(K, &V)> {
+    let mut entries: Vec<_> = map
+        .iter()
+        .map(|(key, value)| (key.clone(), value))
+        .collect();
+    entries.sort_by(|(first, _), (second, _)| first.cmp(second));
+    entries
+}
+
+impl StatisticsSnapshot {
+    /// Renders the snapshot in the Prometheus text exposition format.
+    pub fn to_prometheus(&self) -> String {
+        let mut output = String::new();
+        // Writing to a String can't fail.
+        let _ = self.write_prometheus(&mut output);
+        output
+    }
+
+    fn write_prometheus(&self, output: &mut String) -> std::fmt::Result {
+        writeln!(
+            output,
+            "# HELP glide_requests_total Requests sent, by command."
+        )?;
+        writeln!(output, "# TYPE glide_requests_total counter")?;
+        for (command, count) in sorted(&self.requests) {
+            writeln!(
+                output,
+                "glide_requests_total{{command=\"{}\"}} {count}",
+                escape_label(&command)
+            )?;
+        }
+
+        writeln!(
+            output,
+            "# HELP glide_node_latency_seconds Request latency, by node."
+        )?;
+        writeln!(output, "# TYPE glide_node_latency_seconds histogram")?;
+        for (node, histogram) in sorted(&self.node_latencies) {
+            let node = escape_label(&node);
+            for (bound, count) in histogram.cumulative_counts() {
+                writeln!(
+                    output,
+                    "glide_node_latency_seconds_bucket{{node=\"{node}\",le=\"{}\"}} {count}",
+                    bound as f64 / 1_000_000.0
+                )?;
+            }
+            writeln!(
+                output,
+                "glide_node_latency_seconds_bucket{{node=\"{node}\",le=\"+Inf\"}} {}",
+                histogram.count
+            )?;
+            writeln!(
+                output,
+                "glide_node_latency_seconds_sum{{node=\"{node}\"}} {}",
+                histogram.sum.as_secs_f64()
+            )?;
+            writeln!(
+                output,
+                "glide_node_latency_seconds_count{{node=\"{node}\"}} {}",
+                histogram.count
+            )?;
+        }
+
+        for (name, help, value) in [
+            (
+                "glide_timeouts_total",
+                "Requests that timed out.",
+                self.timeouts,
+            ),
+            (
+                "glide_disconnects_total",
+                "Node connections that were lost.",
+                self.disconnects,
+            ),
+            (
+                "glide_reconnects_total",
+                "Node connections that were recreated.",
+                self.reconnects,
+            ),
+            (
+                "glide_redirects_total",
+                "MOVED and ASK redirections that reached the client.",
+                self.redirects,
+            ),
+        ] {
+            writeln!(output, "# HELP {name} {help}")?;
+            writeln!(output, "# TYPE {name} counter")?;
+            writeln!(output, "{name} {value}")?;
+        }
+
+        writeln!(
+            output,
+            "# HELP glide_in_flight_requests Requests being handled, by socket connection."
+        )?;
+        writeln!(output, "# TYPE glide_in_flight_requests gauge")?;
+        for (socket_id, count) in sorted(&self.in_flight_requests) {
+            writeln!(
+                output,
+                "glide_in_flight_requests{{socket=\"{socket_id}\"}} {count}"
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Converts the snapshot into a map, so that it can be returned like a command's response.
+    pub fn to_value(&self) -> Value {
+        let requests = sorted(&self.requests)
+            .into_iter()
+            .map(|(command, count)| (bulk_string(command), int(*count)))
+            .collect();
+        let node_latencies = sorted(&self.node_latencies)
+            .into_iter()
+            .map(|(node, histogram)| {
+                let buckets = histogram
+                    .cumulative_counts()
+                    .map(|(bound, count)| (int(bound), int(count)))
+                    .collect();
+                let histogram = Value::Map(vec![
+                    (bulk_string("count"), int(histogram.count)),
+                    (
+                        bulk_string("sum_micros"),
+                        int(histogram.sum.as_micros() as u64),
+                    ),
+                    (bulk_string("buckets"), Value::Map(buckets)),
+                ]);
+                (bulk_string(node), histogram)
+            })
+            .collect();
+        let in_flight_requests = sorted(&self.in_flight_requests)
+            .into_iter()
+            .map(|(socket_id, count)| (int(socket_id), int(*count)))
+            .collect();
+        Value::Map(vec![
+            (bulk_string("requests"), Value::Map(requests)),
+            (bulk_string("node_latencies"), Value::Map(node_latencies)),
+            (bulk_string("timeouts"), int(self.timeouts)),
+            (bulk_string("disconnects"), int(self.disconnects)),
+            (bulk_string("reconnects"), int(self.reconnects)),
+            (bulk_string("redirects"), int(self.redirects)),
+            (
+                bulk_string("in_flight_requests"),
+                Value::Map(in_flight_requests),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::ErrorKind;
+
+    #[test]
+    fn latencies_are_counted_in_the_first_bucket_that_fits() {
+        let statistics = Statistics::default();
+        statistics.record_node_latency("a:1", Duration::from_micros(100));
+        statistics.record_node_latency("a:1", Duration::from_micros(101));
+        statistics.record_node_latency("a:1", Duration::from_secs(2));
+
+        let histogram = statistics.snapshot().node_latencies["a:1"].clone();
+        assert_eq!(histogram.bucket_counts[0], 1);
+        assert_eq!(histogram.bucket_counts[1], 1);
+        assert_eq!(histogram.bucket_counts.iter().sum::<u64>(), 2);
+        assert_eq!(histogram.count, 3);
+        assert_eq!(histogram.sum, Duration::from_micros(2_000_201));
+    }
+
+    #[test]
+    fn only_timeouts_are_counted() {
+        let statistics = Statistics::default();
+        statistics.record_result::<()>(&Err(
+            std::io::Error::from(std::io::ErrorKind::TimedOut).into()
+        ));
+        statistics.record_result::<()>(&Err((ErrorKind::ResponseError, "ERR").into()));
+        statistics.record_result(&Ok(()));
+
+        assert_eq!(statistics.snapshot().timeouts, 1);
+    }
+
+    #[test]
+    fn unknown_commands_share_a_name() {
+        let statistics = Statistics::default();
+        statistics.record_request(&redis::cmd("get"));
+        statistics.record_request(redis::cmd("CONFIG").arg("get").arg("maxmemory"));
+        statistics.record_request(&redis::cmd("NOT_A_COMMAND"));
+        statistics.record_request(redis::cmd("CONFIG").arg("NOT_A_SUBCOMMAND"));
+
+        let requests = statistics.snapshot().requests;
+        assert_eq!(requests["GET"], 1);
+        assert_eq!(requests["CONFIG GET"], 1);
+        assert_eq!(requests[OTHER_COMMAND_NAME], 2);
+        assert_eq!(requests.len(), 3);
+    }
+
+    #[test]
+    fn prometheus_histograms_are_cumulative() {
+        let statistics = Statistics::default();
+        statistics.record_request(&redis::cmd("GET"));
+        statistics.record_node_latency("a:1", Duration::from_micros(50));
+        statistics.record_node_latency("a:1", Duration::from_micros(200));
+
+        let output = statistics.snapshot().to_prometheus();
+        assert!(output.contains("glide_requests_total{command=\"GET\"} 1\n"));
+        assert!(
+            output.contains("glide_node_latency_seconds_bucket{node=\"a:1\",le=\"0.0001\"} 1\n")
+        );
+        assert!(
+            output.contains("glide_node_latency_seconds_bucket{node=\"a:1\",le=\"0.00025\"} 2\n")
+        );
+        assert!(output.contains("glide_node_latency_seconds_bucket{node=\"a:1\",le=\"+Inf\"} 2\n"));
+        assert!(output.contains("glide_node_latency_seconds_count{node=\"a:1\"} 2\n"));
+        assert!(output.contains("glide_timeouts_total 0\n"));
+        assert!(output.contains("glide_redirects_total 0\n"));
+    }
+
+    #[test]
+    fn client_statistics_are_also_counted_in_the_process() {
+        let first = ClientStatistics::default();
+        let second = ClientStatistics::default();
+        let before = get_statistics().redirects;
+        first.record_redirect();
+        first.record_redirect();
+        second.record_redirect();
+
+        assert_eq!(first.snapshot().redirects, 2);
+        assert_eq!(second.snapshot().redirects, 1);
+        assert!(get_statistics().redirects >= before + 3);
+    }
+}
@@ -0,0 +1,99 @@
+use redis::aio::MultiplexedConnection;
+use redis::{ConnectionAddr, ConnectionInfo, ErrorKind, RedisError, RedisResult};
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+/// The TLS parameters of connections to the nodes of standalone and sentinel clients.
+#[derive(Clone, Default)]
+pub(super) struct NodeTlsParams {
+    /// The custom certificates, or `None` if the system's root certificates are used.
+    pub(super) params: Option<redis::TlsConnParams>,
+    /// Set if the server's certificate is verified against a configured server name, instead of the node's host.
+    pub(super) server_name: Option<ServerNameConnector>,
+}
+
+fn tls_error(description: &'static str, err: impl ToString) -> RedisError {
+    (ErrorKind::InvalidClientConfig, description, err.to_string()).into()
+}
+
+/// Opens TLS connections that send the configured server name (SNI), and verify the server's certificate against it,
+/// instead of against the host of the node's address. The connections are opened here, since redis-rs takes the server
+/// name from the address.
+#[derive(Clone)]
+pub(super) struct ServerNameConnector {
+    server_name: ServerName<'static>,
+    connector: TlsConnector,
+}
+
+impl ServerNameConnector {
+    pub(super) fn new(
+        server_name: &str,
+        certificates: Option<&redis::TlsCertificates>,
+    ) -> RedisResult<Self> {
+        let server_name = ServerName::try_from(server_name.to_string())
+            .map_err(|err| tls_error("Invalid TLS server name", err))?;
+        let mut root_store = RootCertStore::empty();
+        match certificates.and_then(|certificates| certificates.root_cert.as_deref()) {
+            Some(mut root_certs) => {
+                for certificate in rustls_pemfile::certs(&mut root_certs) {
+                    root_store
+                        .add(certificate?)
+                        .map_err(|err| tls_error("Invalid root certificate", err))?;
+                }
+            }
+            None => {
+                // Like redis-rs, certificates of the system that rustls can't parse are skipped.
+                for certificate in rustls_native_certs::load_native_certs()? {
+                    let _ = root_store.add(certificate);
+                }
+            }
+        }
+        let builder = ClientConfig::builder().with_root_certificates(root_store);
+        let config = match certificates.and_then(|certificates| certificates.client_tls.as_ref()) {
+            Some(client_tls) => {
+                let client_cert = rustls_pemfile::certs(&mut client_tls.client_cert.as_slice())
+                    .collect::<Result<Vec<_>, _>>()?;
+                let client_key =
+                    rustls_pemfile::private_key(&mut client_tls.client_key.as_slice())?
+                        .ok_or_else(|| {
+                            tls_error("Invalid client key", "no private key found".to_string())
+                        })?;
+                builder
+                    .with_client_auth_cert(client_cert, client_key)
+                    .map_err(|err| tls_error("Invalid client certificate", err))?
+            }
+            None => builder.with_no_client_auth(),
+        };
+        Ok(Self {
+            server_name,
+            connector: TlsConnector::from(Arc::new(config)),
+        })
+    }
+
+    /// Opens a connection to the TLS address of `connection_info`, and authenticates it like redis-rs does.
+    pub(super) async fn connect(
+        &self,
+        connection_info: &ConnectionInfo,
+    ) -> RedisResult<MultiplexedConnection> {
+        let ConnectionAddr::TcpTls { host, port, .. } = &connection_info.addr else {
+            return Err((
+                ErrorKind::InvalidClientConfig,
+                "A TLS server name requires a TLS address",
+                connection_info.addr.to_string(),
+            )
+                .into());
+        };
+        let stream = TcpStream::connect((host.as_str(), *port)).await?;
+        let stream = self
+            .connector
+            .connect(self.server_name.clone(), stream)
+            .await?;
+        let (connection, driver) =
+            MultiplexedConnection::new(&connection_info.redis, stream).await?;
+        tokio::spawn(driver);
+        Ok(connection)
+    }
+}
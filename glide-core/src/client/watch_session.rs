@@ -0,0 +1,97 @@
+use super::cluster_scan::parse_primaries;
+use super::value_conversion::{convert_to_expected_type, expected_type_for_cmd};
+use super::{run_with_timeout, Client, ClusterNodeSettings};
+use redis::aio::MultiplexedConnection;
+use redis::cluster_async::ClusterConnection;
+use redis::cluster_routing::{Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr};
+use redis::{Cmd, ErrorKind, RedisResult, Value};
+use std::time::Duration;
+
+/// Opens a connection to the primary that serves the slot, according to the primary itself.
+pub(super) async fn connect_to_slot_primary(
+    connection: &mut ClusterConnection,
+    node_settings: &ClusterNodeSettings,
+    slot: u16,
+) -> RedisResult<MultiplexedConnection> {
+    let slots = connection
+        .route_command(
+            redis::cmd("CLUSTER").arg("SLOTS"),
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                slot,
+                SlotAddr::Master,
+            ))),
+        )
+        .await?;
+    let primary = parse_primaries(&slots)?
+        .into_iter()
+        .find(|primary| {
+            primary
+                .slots
+                .iter()
+                .any(|(start, end)| (*start..=*end).contains(&slot))
+        })
+        .ok_or_else(|| {
+            redis::RedisError::from((
+                ErrorKind::ClientError,
+                "No primary serves the slot of the watched keys",
+                slot.to_string(),
+            ))
+        })?;
+    redis::Client::open(node_settings.connection_info(&primary.host, primary.port))?
+        .get_multiplexed_async_connection()
+        .await
+}
+
+/// Keys that are watched on a connection of their own. Every command that is sent through the session runs on that
+/// connection, so reads of the watched keys happen after WATCH, and the transaction's EXEC checks them.
+/// The keys stay watched until the transaction is executed, or until every clone of the session is dropped,
+/// which closes the connection.
+#[derive(Clone)]
+pub struct WatchSession {
+    connection: MultiplexedConnection,
+    request_timeout: Duration,
+}
+
+impl WatchSession {
+    pub(super) async fn start(
+        mut connection: MultiplexedConnection,
+        keys: &[Vec<u8>],
+        request_timeout: Duration,
+    ) -> RedisResult<Self> {
+        let mut watch = redis::cmd("WATCH");
+        for key in keys {
+            watch.arg(key.as_slice());
+        }
+        connection.send_packed_command(&watch).await?;
+        Ok(Self {
+            connection,
+            request_timeout,
+        })
+    }
+
+    /// Sends a command on the session's connection. Like changes from any other connection, a command that modifies
+    /// a watched key aborts the session's transaction.
+    pub async fn send_command(&mut self, cmd: &Cmd) -> RedisResult<Value> {
+        let expected_type = expected_type_for_cmd(cmd);
+        run_with_timeout(
+            self.request_timeout,
+            self.connection.send_packed_command(cmd),
+        )
+        .await
+        .and_then(|value| convert_to_expected_type(value, expected_type))
+    }
+
+    /// Executes the pipeline as a transaction on the session's connection, which ends the session.
+    /// If a watched key was modified since it was watched, the transaction isn't executed, and an error with the
+    /// `WATCHABORTED` code is returned.
+    pub async fn exec(mut self, pipeline: &redis::Pipeline) -> RedisResult<Value> {
+        let command_count = pipeline.cmd_iter().count();
+        let offset = command_count + 1;
+        let values = run_with_timeout(
+            self.request_timeout,
+            self.connection.send_packed_commands(pipeline, offset, 1),
+        )
+        .await?;
+        Client::get_transaction_values(pipeline, values, command_count, offset)
+    }
+}
@@ -1,36 +1,44 @@
 use super::rotating_buffer::RotatingBuffer;
-use crate::client::Client;
+use crate::client::{
+    AuthProvider, AuthToken, Client, ClusterScanArgs, InFlightRequests, NodeResults, RequestSpan,
+    WatchSession, WATCH_ABORTED_ERROR_CODE,
+};
 use crate::connection_request::ConnectionRequest;
 use crate::redis_request::{
-    command, redis_request, Command, RedisRequest, RequestType, Routes, ScriptInvocation,
-    SlotTypes, Transaction,
+    command, redis_request, Batch, ClusterScan, Command, GetStatistics, RedisRequest, RequestType,
+    Routes, ScriptInvocation, SlotTypes, Transaction, UpdatePassword, Watch,
 };
 use crate::response;
 use crate::response::Response;
 use crate::retry_strategies::get_fixed_interval_backoff;
+use bytes::Bytes;
 use directories::BaseDirs;
 use dispose::{Disposable, Dispose};
 use futures::stream::StreamExt;
 use logger_core::{log_debug, log_error, log_info, log_trace, log_warn};
-use protobuf::Message;
+use protobuf::{Chars, Message};
 use redis::cluster_routing::{
     MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
 };
 use redis::cluster_routing::{ResponsePolicy, Routable};
-use redis::RedisError;
-use redis::{cmd, Cmd, Value};
+use redis::{cmd, Cmd, ErrorKind, PushInfo, Value};
+use redis::{RedisError, RedisResult};
 use signal_hook::consts::signal::*;
 use signal_hook_tokio::Signals;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use std::{env, str};
 use std::{io, thread};
 use thiserror::Error;
 use tokio::io::ErrorKind::AddrInUse;
 use tokio::net::{UnixListener, UnixStream};
 use tokio::runtime::Builder;
-use tokio::sync::mpsc::{channel, Sender};
-use tokio::sync::Mutex;
+use tokio::sync::mpsc::{channel, unbounded_channel, Sender, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{oneshot, Mutex};
 use tokio::task;
 use tokio_retry::Retry;
 use tokio_util::task::LocalPoolHandle;
@@ -40,6 +48,9 @@ use PipeListeningResult::*;
 /// The socket file name
 const SOCKET_FILE_NAME: &str = "glide-socket";
 
+/// How long the wrapper has to answer a token request.
+const AUTH_TOKEN_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// The maximum length of a request's arguments to be passed as a vector of
 /// strings instead of a pointer
 pub const MAX_REQUEST_ARGS_LENGTH: usize = 2_i32.pow(12) as usize; // TODO: find the right number
@@ -174,6 +185,85 @@ async fn write_closing_error(
     write_to_writer(response, writer).await
 }
 
+/// Moves the value to the heap and leaks it. The wrapper should use `Box::from_raw` to recreate the box, use the value, and drop the allocation.
+fn leak_value(value: Value) -> u64 {
+    let pointer = Box::leak(Box::new(value));
+    pointer as *mut redis::Value as u64
+}
+
+/// Returns the type of an error that isn't a disconnect or a timeout.
+/// Errors that the client recognizes have their own kind, and other server errors are told apart by their code.
+fn get_request_error_type(err: &RedisError) -> response::RequestErrorType {
+    use response::RequestErrorType;
+    match err.kind() {
+        redis::ErrorKind::ExecAbortError => RequestErrorType::ExecAbort,
+        redis::ErrorKind::AuthenticationFailed => RequestErrorType::NoAuth,
+        redis::ErrorKind::ReadOnly => RequestErrorType::ReadOnly,
+        redis::ErrorKind::CrossSlot => RequestErrorType::CrossSlot,
+        redis::ErrorKind::BusyLoadingError => RequestErrorType::Loading,
+        redis::ErrorKind::NoScriptError => RequestErrorType::NoScript,
+        redis::ErrorKind::Moved => RequestErrorType::Moved,
+        redis::ErrorKind::Ask => RequestErrorType::Ask,
+        redis::ErrorKind::TryAgain => RequestErrorType::TryAgain,
+        redis::ErrorKind::ClusterDown => RequestErrorType::ClusterDown,
+        _ => match err.code() {
+            Some(WATCH_ABORTED_ERROR_CODE) => RequestErrorType::WatchAborted,
+            Some("WRONGTYPE") => RequestErrorType::WrongType,
+            Some("NOAUTH") | Some("WRONGPASS") => RequestErrorType::NoAuth,
+            Some("NOPERM") => RequestErrorType::NoPermission,
+            Some("OOM") => RequestErrorType::OutOfMemory,
+            Some("BUSY") => RequestErrorType::Busy,
+            _ => RequestErrorType::Unspecified,
+        },
+    }
+}
+
+/// Converts the error to a response. `address` is the node that the error came from, if it's known. For MOVED and ASK
+/// errors, the node that the request was redirected to is reported instead.
+fn get_request_error(err: RedisError, address: Option<String>) -> response::RequestError {
+    let error_message = err.to_string();
+    log_warn("received error", error_message.as_str());
+    let mut request_error = response::RequestError::default();
+    if err.is_connection_dropped() {
+        request_error.type_ = response::RequestErrorType::Disconnect.into();
+        request_error.message =
+            format!("Received connection error `{error_message}`. Will attempt to reconnect")
+                .into();
+    } else if err.is_timeout() {
+        request_error.type_ = response::RequestErrorType::Timeout.into();
+        request_error.message = error_message.into();
+    } else {
+        request_error.type_ = get_request_error_type(&err).into();
+        request_error.message = error_message.into();
+    }
+    if let Some(code) = err.code() {
+        request_error.code = code.into();
+    }
+    if let Some((redirect_address, _slot)) = err.redirect_node() {
+        request_error.address = redirect_address.to_string().into();
+    } else if let Some(address) = address {
+        request_error.address = address.into();
+    }
+    request_error
+}
+
+fn get_error_response(
+    err: RedisError,
+    address: Option<String>,
+) -> Option<response::response::Value> {
+    if err.is_connection_refusal() {
+        let error_message = err.to_string();
+        log_error("response error", &error_message);
+        Some(response::response::Value::ClosingError(
+            error_message.into(),
+        ))
+    } else {
+        Some(response::response::Value::RequestError(get_request_error(
+            err, address,
+        )))
+    }
+}
+
 /// Create response and write it to the writer
 async fn write_result(
     resp_result: ClientUsageResult<Value>,
@@ -189,10 +279,7 @@ async fn write_result(
         Ok(value) => {
             if value != Value::Nil {
                 // Since null values don't require any additional data, they can be sent without any extra effort.
-                // Move the value to the heap and leak it. The wrapper should use `Box::from_raw` to recreate the box, use the value, and drop the allocation.
-                let pointer = Box::leak(Box::new(value));
-                let raw_pointer = pointer as *mut redis::Value;
-                Some(response::response::Value::RespPointer(raw_pointer as u64))
+                Some(response::response::Value::RespPointer(leak_value(value)))
             } else {
                 None
             }
@@ -203,41 +290,202 @@ async fn write_result(
                 error_message.into(),
             ))
         }
-        Err(ClienUsageError::RedisError(err)) => {
-            let error_message = err.to_string();
-            if err.is_connection_refusal() {
-                log_error("response error", &error_message);
-                Some(response::response::Value::ClosingError(
-                    error_message.into(),
-                ))
-            } else {
-                log_warn("received error", error_message.as_str());
-                let mut request_error = response::RequestError::default();
-                if err.is_connection_dropped() {
-                    request_error.type_ = response::RequestErrorType::Disconnect.into();
-                    request_error.message = format!(
-                        "Received connection error `{error_message}`. Will attempt to reconnect"
-                    )
-                    .into();
-                } else if err.is_timeout() {
-                    request_error.type_ = response::RequestErrorType::Timeout.into();
-                    request_error.message = error_message.into();
-                } else {
-                    request_error.type_ = match err.kind() {
-                        redis::ErrorKind::ExecAbortError => {
-                            response::RequestErrorType::ExecAbort.into()
-                        }
-                        _ => response::RequestErrorType::Unspecified.into(),
-                    };
-                    request_error.message = error_message.into();
-                }
-                Some(response::response::Value::RequestError(request_error))
-            }
+        Err(ClienUsageError::RedisError(err)) => get_error_response(err, None),
+        Err(ClienUsageError::NodeError { error, address }) => {
+            get_error_response(error, Some(address))
         }
     };
     write_to_writer(response, writer).await
 }
 
+/// Create a response with the result of each command in the batch and write it to the writer
+async fn write_batch_results(
+    results: Vec<RedisResult<Value>>,
+    callback_index: u32,
+    writer: &Rc<Writer>,
+) -> Result<(), io::Error> {
+    let mut batch_response = response::BatchResponse::new();
+    batch_response.entries = results
+        .into_iter()
+        .map(|result| {
+            let mut entry = response::batch_response::Entry::new();
+            entry.value = match result {
+                Ok(Value::Nil) => None,
+                Ok(value) => Some(response::batch_response::entry::Value::RespPointer(
+                    leak_value(value),
+                )),
+                Err(err) => Some(response::batch_response::entry::Value::RequestError(
+                    get_request_error(err, None),
+                )),
+            };
+            entry
+        })
+        .collect();
+    let mut response = Response::new();
+    response.callback_idx = callback_index;
+    response.value = Some(response::response::Value::BatchResponse(batch_response));
+    write_to_writer(response, writer).await
+}
+
+/// Create a response with the result of each node and write it to the writer
+async fn write_node_results(
+    results: NodeResults,
+    callback_index: u32,
+    writer: &Rc<Writer>,
+) -> Result<(), io::Error> {
+    let mut multi_node_response = response::MultiNodeResponse::new();
+    multi_node_response.entries = results
+        .into_iter()
+        .map(|(address, result)| {
+            let mut entry = response::multi_node_response::Entry::new();
+            entry.value = match result {
+                Ok(Value::Nil) => None,
+                Ok(value) => Some(response::multi_node_response::entry::Value::RespPointer(
+                    leak_value(value),
+                )),
+                Err(err) => Some(response::multi_node_response::entry::Value::RequestError(
+                    get_request_error(err, Some(address.clone())),
+                )),
+            };
+            entry.address = address.into();
+            entry
+        })
+        .collect();
+    let mut response = Response::new();
+    response.callback_idx = callback_index;
+    response.value = Some(response::response::Value::MultiNodeResponse(
+        multi_node_response,
+    ));
+    write_to_writer(response, writer).await
+}
+
+/// Write a push notification, such as a Pub/Sub message, to the writer. Only wrappers that enabled push notifications
+/// in their connection request receive them, and they're responsible for freeing the pointer.
+async fn write_push_notification(push: PushInfo, writer: &Rc<Writer>) -> Result<(), io::Error> {
+    let mut response = Response::new();
+    let pointer = leak_value(Value::Push {
+        kind: push.kind,
+        data: push.data,
+    });
+    response.value = Some(response::response::Value::PushPointer(pointer));
+    write_to_writer(response, writer).await
+}
+
+async fn forward_push_notifications(
+    mut push_receiver: UnboundedReceiver<PushInfo>,
+    writer: Rc<Writer>,
+) {
+    while let Some(push) = push_receiver.recv().await {
+        if let Err(err) = write_push_notification(push, &writer).await {
+            log_warn("push notification", format!("failed to write: {err}"));
+        }
+    }
+}
+
+/// Requests auth tokens from the wrapper. The first token is passed in the connection request, since the wrapper
+/// can't answer token requests before the client is created. Tokens are reused by all of the client's connections
+/// until they're due for a refresh.
+struct SocketAuthProvider {
+    /// The latest token and when it should be refreshed. The lock is held while a new token is requested, so concurrent callers wait for it.
+    current_token: Mutex<(AuthToken, Option<Instant>)>,
+    token_request_sender: UnboundedSender<u32>,
+    pending_requests: std::sync::Mutex<HashMap<u32, oneshot::Sender<AuthToken>>>,
+    next_request_id: AtomicU32,
+}
+
+impl SocketAuthProvider {
+    fn new(first_token: AuthToken, token_request_sender: UnboundedSender<u32>) -> Self {
+        let refresh_deadline = first_token.refresh_deadline();
+        Self {
+            current_token: Mutex::new((first_token, refresh_deadline)),
+            token_request_sender,
+            pending_requests: Default::default(),
+            next_request_id: AtomicU32::new(0),
+        }
+    }
+
+    async fn request_token(&self) -> RedisResult<AuthToken> {
+        let request_id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        self.pending_requests
+            .lock()
+            .unwrap()
+            .insert(request_id, sender);
+        if self.token_request_sender.send(request_id).is_err() {
+            self.pending_requests.lock().unwrap().remove(&request_id);
+            return Err(io::Error::from(io::ErrorKind::BrokenPipe).into());
+        }
+        let token = tokio::time::timeout(AUTH_TOKEN_REQUEST_TIMEOUT, receiver).await;
+        self.pending_requests.lock().unwrap().remove(&request_id);
+        match token {
+            Ok(Ok(token)) if !token.token.is_empty() => Ok(token),
+            Ok(Ok(_)) => Err((
+                redis::ErrorKind::AuthenticationFailed,
+                "The wrapper failed to provide an auth token",
+            )
+                .into()),
+            Ok(Err(_)) | Err(_) => Err((
+                redis::ErrorKind::AuthenticationFailed,
+                "The wrapper didn't answer the auth token request",
+            )
+                .into()),
+        }
+    }
+
+    /// Passes a token that the wrapper sent to the request that asked for it.
+    fn receive_token(&self, request_id: u32, token: &crate::redis_request::AuthToken) {
+        let Some(sender) = self.pending_requests.lock().unwrap().remove(&request_id) else {
+            log_warn(
+                "auth token",
+                format!("received a token for an unknown request {request_id}"),
+            );
+            return;
+        };
+        let _ = sender.send(AuthToken {
+            token: token.token.to_string(),
+            lifetime: (token.lifetime > 0).then(|| Duration::from_millis(token.lifetime as u64)),
+        });
+    }
+}
+
+impl AuthProvider for SocketAuthProvider {
+    fn get_token(&self) -> redis::RedisFuture<'_, AuthToken> {
+        Box::pin(async move {
+            let mut current_token = self.current_token.lock().await;
+            let (token, refresh_deadline) = &*current_token;
+            if !refresh_deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+                return Ok(token.clone());
+            }
+            let token = self.request_token().await?;
+            *current_token = (token.clone(), token.refresh_deadline());
+            Ok(token)
+        })
+    }
+}
+
+/// Write a request for a new auth token to the writer.
+async fn write_auth_token_request(request_id: u32, writer: &Rc<Writer>) -> Result<(), io::Error> {
+    let mut response = Response::new();
+    response.value = Some(response::response::Value::AuthTokenRequest(
+        response::AuthTokenRequest {
+            request_id,
+            ..Default::default()
+        },
+    ));
+    write_to_writer(response, writer).await
+}
+
+async fn forward_auth_token_requests(
+    mut token_request_receiver: UnboundedReceiver<u32>,
+    writer: Rc<Writer>,
+) {
+    while let Some(request_id) = token_request_receiver.recv().await {
+        if let Err(err) = write_auth_token_request(request_id, &writer).await {
+            log_warn("auth token", format!("failed to request a token: {err}"));
+        }
+    }
+}
+
 async fn write_to_writer(response: Response, writer: &Rc<Writer>) -> Result<(), io::Error> {
     let mut vec = writer.accumulated_outputs.take();
     let encode_result = response.write_length_delimited_to_vec(&mut vec);
@@ -338,6 +586,19 @@ fn get_command(request: &Command) -> Option<Cmd> {
         RequestType::Zcard => Some(cmd("ZCARD")),
         RequestType::Zcount => Some(cmd("ZCOUNT")),
         RequestType::ZIncrBy => Some(cmd("ZINCRBY")),
+        RequestType::Subscribe => Some(cmd("SUBSCRIBE")),
+        RequestType::PSubscribe => Some(cmd("PSUBSCRIBE")),
+        RequestType::Unsubscribe => Some(cmd("UNSUBSCRIBE")),
+        RequestType::PUnsubscribe => Some(cmd("PUNSUBSCRIBE")),
+        RequestType::BLPop => Some(cmd("BLPOP")),
+        RequestType::BRPop => Some(cmd("BRPOP")),
+        RequestType::BLMove => Some(cmd("BLMOVE")),
+        RequestType::BLMPop => Some(cmd("BLMPOP")),
+        RequestType::BZPopMin => Some(cmd("BZPOPMIN")),
+        RequestType::BZPopMax => Some(cmd("BZPOPMAX")),
+        RequestType::XRead => Some(cmd("XREAD")),
+        RequestType::XReadGroup => Some(cmd("XREADGROUP")),
+        RequestType::Wait => Some(cmd("WAIT")),
     }
 }
 
@@ -361,6 +622,17 @@ fn get_redis_command(command: &Command) -> Result<Cmd, ClienUsageError> {
                 cmd.arg(arg.as_bytes());
             }
         }
+        Some(command::Args::BinaryArgsArray(args_vec)) => {
+            for arg in args_vec.args.iter() {
+                cmd.arg(arg.as_ref());
+            }
+        }
+        Some(command::Args::BinaryArgsVecPointer(pointer)) => {
+            let res = *unsafe { Box::from_raw(*pointer as *mut Vec<Vec<u8>>) };
+            for arg in res {
+                cmd.arg(arg);
+            }
+        }
         None => {
             return Err(ClienUsageError::InternalError(
                 "Failed to get request arguemnts, no arguments are set".to_string(),
@@ -376,40 +648,203 @@ async fn send_command(
     mut client: Client,
     routing: Option<RoutingInfo>,
 ) -> ClientUsageResult<Value> {
+    match client.send_command_with_node(&cmd, routing).await {
+        (Ok(value), _) => Ok(value),
+        (Err(error), Some(address)) => Err(ClienUsageError::NodeError { error, address }),
+        (Err(error), None) => Err(error.into()),
+    }
+}
+
+async fn send_command_to_nodes(
+    cmd: Cmd,
+    mut client: Client,
+    routing: Option<RoutingInfo>,
+) -> ClientUsageResult<NodeResults> {
+    let Some(RoutingInfo::MultiNode((nodes, _))) =
+        routing.or_else(|| RoutingInfo::for_routable(&cmd))
+    else {
+        return Err(RedisError::from((
+            ErrorKind::ClientError,
+            "Per-node results require a route to all nodes or all primaries",
+        ))
+        .into());
+    };
     client
-        .send_command(&cmd, routing)
+        .send_command_to_nodes(&cmd, &nodes)
         .await
         .map_err(|err| err.into())
 }
 
+/// Returns the binary arguments if they're set, and otherwise the string arguments.
+fn get_script_args(args: Vec<Chars>, binary_args: Vec<Bytes>) -> Vec<Bytes> {
+    if !binary_args.is_empty() {
+        return binary_args;
+    }
+    args.iter()
+        .map(|arg| Bytes::copy_from_slice(arg.as_bytes()))
+        .collect()
+}
+
 async fn invoke_script(
     script: ScriptInvocation,
     mut client: Client,
     routing: Option<RoutingInfo>,
 ) -> ClientUsageResult<Value> {
+    let keys = get_script_args(script.keys, script.binary_keys);
+    let args = get_script_args(script.args, script.binary_args);
     client
-        .invoke_script(&script.hash, script.keys, script.args, routing)
+        .invoke_script(&script.hash, keys, args, routing)
         .await
         .map_err(|err| err.into())
 }
 
+fn get_transaction_pipeline(request: Transaction) -> ClientUsageResult<redis::Pipeline> {
+    let mut pipeline = redis::Pipeline::with_capacity(request.commands.capacity());
+    pipeline.atomic();
+    for command in request.commands {
+        pipeline.add_command(get_redis_command(&command)?);
+    }
+    Ok(pipeline)
+}
+
 async fn send_transaction(
     request: Transaction,
     mut client: Client,
     routing: Option<RoutingInfo>,
 ) -> ClientUsageResult<Value> {
+    let pipeline = get_transaction_pipeline(request)?;
+    client
+        .send_transaction(&pipeline, routing)
+        .await
+        .map_err(|err| err.into())
+}
+
+/// The watch sessions that were started on a socket, by their ids. A session ends when it's removed from here and dropped,
+/// which happens once its transaction is executed, when the wrapper unwatches it, or when the socket is closed.
+#[derive(Default)]
+struct WatchSessions {
+    sessions: RefCell<HashMap<u64, WatchSession>>,
+    /// The last id that was given to a session. Ids start at 1, since 0 means that a request doesn't run in a session.
+    last_id: Cell<u64>,
+}
+
+impl WatchSessions {
+    fn insert(&self, session: WatchSession) -> u64 {
+        let id = self.last_id.get() + 1;
+        self.last_id.set(id);
+        self.sessions.borrow_mut().insert(id, session);
+        id
+    }
+
+    fn get(&self, id: u64) -> ClientUsageResult<WatchSession> {
+        self.sessions
+            .borrow()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| unknown_watch_session(id))
+    }
+
+    fn remove(&self, id: u64) -> ClientUsageResult<WatchSession> {
+        self.sessions
+            .borrow_mut()
+            .remove(&id)
+            .ok_or_else(|| unknown_watch_session(id))
+    }
+}
+
+fn unknown_watch_session(id: u64) -> ClienUsageError {
+    RedisError::from((
+        ErrorKind::ClientError,
+        "The watch session doesn't exist, or has already ended",
+        id.to_string(),
+    ))
+    .into()
+}
+
+async fn watch(
+    request: Watch,
+    mut client: Client,
+    watch_sessions: &WatchSessions,
+) -> ClientUsageResult<Value> {
+    let keys: Vec<Vec<u8>> = request.keys.iter().map(|key| key.to_vec()).collect();
+    let session = client.watch(&keys).await?;
+    Ok(Value::Int(watch_sessions.insert(session) as i64))
+}
+
+/// Runs a request on the connection of its watch session.
+async fn send_in_watch_session(
+    command: Option<redis_request::Command>,
+    session_id: u64,
+    watch_sessions: &WatchSessions,
+) -> ClientUsageResult<Value> {
+    match command {
+        Some(redis_request::Command::SingleCommand(command)) => {
+            let cmd = get_redis_command(&command)?;
+            let mut session = watch_sessions.get(session_id)?;
+            session.send_command(&cmd).await.map_err(|err| err.into())
+        }
+        Some(redis_request::Command::Transaction(transaction)) => {
+            let pipeline = get_transaction_pipeline(transaction)?;
+            let session = watch_sessions.remove(session_id)?;
+            session.exec(&pipeline).await.map_err(|err| err.into())
+        }
+        Some(redis_request::Command::Unwatch(_)) => {
+            watch_sessions.remove(session_id)?;
+            Ok(Value::Okay)
+        }
+        _ => Err(ClienUsageError::InternalError(
+            "Only single commands, transactions and unwatch requests can be sent in a watch session"
+                .to_string(),
+        )),
+    }
+}
+
+async fn send_batch(
+    request: Batch,
+    mut client: Client,
+    routing: Option<RoutingInfo>,
+) -> ClientUsageResult<Vec<RedisResult<Value>>> {
     let mut pipeline = redis::Pipeline::with_capacity(request.commands.capacity());
-    pipeline.atomic();
     for command in request.commands {
         pipeline.add_command(get_redis_command(&command)?);
     }
 
+    Ok(client.send_batch(&pipeline, routing).await)
+}
+
+async fn cluster_scan(request: ClusterScan, mut client: Client) -> ClientUsageResult<Value> {
+    let args = ClusterScanArgs {
+        match_pattern: (!request.match_pattern.is_empty()).then(|| request.match_pattern.to_vec()),
+        count: (request.count > 0).then_some(request.count),
+        object_type: (!request.object_type.is_empty()).then(|| request.object_type.to_string()),
+    };
     client
-        .send_transaction(&pipeline, routing)
+        .cluster_scan(&request.cursor, &args)
+        .await
+        .map_err(|err| err.into())
+}
+
+async fn update_password(request: UpdatePassword, mut client: Client) -> ClientUsageResult<Value> {
+    let password = (!request.password.is_empty()).then(|| request.password.to_string());
+    client
+        .update_password(password)
         .await
         .map_err(|err| err.into())
 }
 
+fn get_statistics(request: GetStatistics, client: &Client) -> ClientUsageResult<Value> {
+    let statistics = if request.client_only {
+        client.statistics()
+    } else {
+        crate::client::get_statistics()
+    };
+    Ok(if request.prometheus_format {
+        Value::BulkString(statistics.to_prometheus().into_bytes())
+    } else {
+        statistics.to_value()
+    })
+}
+
 fn get_slot_addr(slot_type: &protobuf::EnumOrUnknown<SlotTypes>) -> ClientUsageResult<SlotAddr> {
     slot_type
         .enum_value()
@@ -422,6 +857,8 @@ fn get_slot_addr(slot_type: &protobuf::EnumOrUnknown<SlotTypes>) -> ClientUsageR
         })
 }
 
+/// Multi-node routes keep the response policy of the command. Commands without a policy, such as INFO,
+/// are answered with a map of each node's address to its response.
 fn get_route(
     route: Option<Box<Routes>>,
     cmd: Option<&Cmd>,
@@ -471,13 +908,64 @@ fn get_route(
                 get_slot_addr(&slot_id_route.slot_type)?,
             )),
         ))),
+        Value::ByAddressRoute(by_address_route) => {
+            let port = u16::try_from(by_address_route.port).map_err(|_| {
+                RedisError::from((
+                    ErrorKind::ClientError,
+                    "Invalid port in by-address route",
+                    by_address_route.port.to_string(),
+                ))
+            })?;
+            Ok(Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::ByAddress {
+                    host: by_address_route.host.to_string(),
+                    port,
+                },
+            )))
+        }
     }
 }
 
-fn handle_request(request: RedisRequest, client: Client, writer: Rc<Writer>) {
-    task::spawn_local(async move {
+fn handle_request(
+    request: RedisRequest,
+    mut client: Client,
+    in_flight_requests: &InFlightRequests,
+    watch_sessions: Rc<WatchSessions>,
+    writer: Rc<Writer>,
+) {
+    if request.request_timeout > 0 {
+        client = client.with_request_timeout(Duration::from_millis(request.request_timeout as u64));
+    }
+    let in_flight_request = in_flight_requests.start_request();
+    let span = RequestSpan::socket_request(request.callback_idx);
+    task::spawn_local(span.instrument(async move {
+        let _in_flight_request = in_flight_request;
+        if request.watch_session != 0 {
+            let result =
+                send_in_watch_session(request.command, request.watch_session, &watch_sessions)
+                    .await;
+            let _res = write_result(result, request.callback_idx, &writer).await;
+            return;
+        }
         let result = match request.command {
             Some(action) => match action {
+                redis_request::Command::SingleCommand(command) if request.per_node_results => {
+                    let results = match get_redis_command(&command) {
+                        Ok(cmd) => match get_route(request.route.0, Some(&cmd)) {
+                            Ok(routes) => send_command_to_nodes(cmd, client, routes).await,
+                            Err(e) => Err(e),
+                        },
+                        Err(e) => Err(e),
+                    };
+                    match results {
+                        Ok(results) => {
+                            let _res =
+                                write_node_results(results, request.callback_idx, &writer).await;
+                            return;
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
                 redis_request::Command::SingleCommand(command) => {
                     match get_redis_command(&command) {
                         Ok(cmd) => match get_route(request.route.0, Some(&cmd)) {
@@ -499,6 +987,36 @@ fn handle_request(request: RedisRequest, client: Client, writer: Rc<Writer>) {
                         Err(e) => Err(e),
                     }
                 }
+                redis_request::Command::Batch(batch) => match get_route(request.route.0, None) {
+                    Ok(routes) => match send_batch(batch, client, routes).await {
+                        Ok(results) => {
+                            let _res =
+                                write_batch_results(results, request.callback_idx, &writer).await;
+                            return;
+                        }
+                        Err(e) => Err(e),
+                    },
+                    Err(e) => Err(e),
+                },
+                redis_request::Command::ClusterScan(scan) => cluster_scan(scan, client).await,
+                redis_request::Command::ReleaseClusterScanCursor(release) => {
+                    client.release_cluster_scan_cursor(&release.cursor);
+                    Ok(Value::Okay)
+                }
+                redis_request::Command::UpdatePassword(update) => {
+                    update_password(update, client).await
+                }
+                redis_request::Command::GetStatistics(request) => get_statistics(request, &client),
+                redis_request::Command::Watch(request) => {
+                    watch(request, client, &watch_sessions).await
+                }
+                redis_request::Command::Unwatch(_) => Err(ClienUsageError::InternalError(
+                    "Received an unwatch request without a watch session".to_string(),
+                )),
+                redis_request::Command::AuthToken(_) => Err(ClienUsageError::InternalError(
+                    "Received an auth token, but the client doesn't use token authentication"
+                        .to_string(),
+                )),
             },
             None => Err(ClienUsageError::InternalError(
                 "Received empty request".to_string(),
@@ -506,16 +1024,31 @@ fn handle_request(request: RedisRequest, client: Client, writer: Rc<Writer>) {
         };
 
         let _res = write_result(result, request.callback_idx, &writer).await;
-    });
+    }));
 }
 
 async fn handle_requests(
     received_requests: Vec<RedisRequest>,
     client: &Client,
+    auth_provider: Option<&SocketAuthProvider>,
+    in_flight_requests: &InFlightRequests,
+    watch_sessions: &Rc<WatchSessions>,
     writer: &Rc<Writer>,
 ) {
     for request in received_requests {
-        handle_request(request, client.clone(), writer.clone())
+        if let (Some(redis_request::Command::AuthToken(token)), Some(auth_provider)) =
+            (&request.command, auth_provider)
+        {
+            auth_provider.receive_token(token.request_id, token);
+            continue;
+        }
+        handle_request(
+            request,
+            client.clone(),
+            in_flight_requests,
+            watch_sessions.clone(),
+            writer.clone(),
+        )
     }
     // Yield to ensure that the subtasks aren't starved.
     task::yield_now().await;
@@ -526,28 +1059,61 @@ pub fn close_socket(socket_path: &String) {
     let _ = std::fs::remove_file(socket_path);
 }
 
+/// Returns an auth provider that requests tokens from the wrapper, if the request enables token authentication.
+fn get_socket_auth_provider(
+    request: &ConnectionRequest,
+    token_request_sender: UnboundedSender<u32>,
+) -> Option<Arc<SocketAuthProvider>> {
+    let info = request
+        .authentication_info
+        .as_ref()
+        .filter(|info| info.token_auth)?;
+    let first_token = AuthToken {
+        token: info.password.to_string(),
+        lifetime: (info.token_lifetime > 0)
+            .then(|| Duration::from_millis(info.token_lifetime as u64)),
+    };
+    Some(Arc::new(SocketAuthProvider::new(
+        first_token,
+        token_request_sender,
+    )))
+}
+
 async fn create_client(
     writer: &Rc<Writer>,
     request: ConnectionRequest,
-) -> Result<Client, ClientCreationError> {
-    let client = match Client::new(request).await {
+    push_sender: UnboundedSender<PushInfo>,
+    token_request_sender: UnboundedSender<u32>,
+) -> Result<(Client, Option<Arc<SocketAuthProvider>>), ClientCreationError> {
+    let auth_provider = get_socket_auth_provider(&request, token_request_sender);
+    // Push notifications are only written to the socket if the wrapper can handle them.
+    let push_sender = request.push_notifications.then_some(push_sender);
+    let result = match &auth_provider {
+        Some(auth_provider) => {
+            Client::new_with_auth_provider(request, push_sender, auth_provider.clone()).await
+        }
+        None => Client::new(request, push_sender).await,
+    };
+    let client = match result {
         Ok(client) => client,
         Err(err) => return Err(ClientCreationError::ConnectionError(err)),
     };
     write_result(Ok(Value::Okay), 0, writer).await?;
-    Ok(client)
+    Ok((client, auth_provider))
 }
 
 async fn wait_for_connection_configuration_and_create_client(
     client_listener: &mut UnixStreamListener,
     writer: &Rc<Writer>,
-) -> Result<Client, ClientCreationError> {
+    push_sender: UnboundedSender<PushInfo>,
+    token_request_sender: UnboundedSender<u32>,
+) -> Result<(Client, Option<Arc<SocketAuthProvider>>), ClientCreationError> {
     // Wait for the server's address
     match client_listener.next_values::<ConnectionRequest>().await {
         Closed(reason) => Err(ClientCreationError::SocketListenerClosed(reason)),
         ReceivedValues(mut received_requests) => {
             if let Some(request) = received_requests.pop() {
-                create_client(writer, request).await
+                create_client(writer, request, push_sender, token_request_sender).await
             } else {
                 Err(ClientCreationError::UnhandledError(
                     "No received requests".to_string(),
@@ -560,15 +1126,26 @@ async fn wait_for_connection_configuration_and_create_client(
 async fn read_values_loop(
     mut client_listener: UnixStreamListener,
     client: &Client,
+    auth_provider: Option<&SocketAuthProvider>,
     writer: Rc<Writer>,
 ) -> ClosingReason {
+    let in_flight_requests = InFlightRequests::new();
+    let watch_sessions = Rc::new(WatchSessions::default());
     loop {
         match client_listener.next_values().await {
             Closed(reason) => {
                 return reason;
             }
             ReceivedValues(received_requests) => {
-                handle_requests(received_requests, client, &writer).await;
+                handle_requests(
+                    received_requests,
+                    client,
+                    auth_provider,
+                    &in_flight_requests,
+                    &watch_sessions,
+                    &writer,
+                )
+                .await;
             }
         }
     }
@@ -587,9 +1164,15 @@ async fn listen_on_client_stream(socket: UnixStream) {
         accumulated_outputs,
         closing_sender: sender,
     });
-    let client_creation =
-        wait_for_connection_configuration_and_create_client(&mut client_listener, &writer);
-    let client = match client_creation.await {
+    let (push_sender, push_receiver) = unbounded_channel();
+    let (token_request_sender, token_request_receiver) = unbounded_channel();
+    let client_creation = wait_for_connection_configuration_and_create_client(
+        &mut client_listener,
+        &writer,
+        push_sender,
+        token_request_sender,
+    );
+    let (client, auth_provider) = match client_creation.await {
         Ok(conn) => conn,
         Err(ClientCreationError::SocketListenerClosed(ClosingReason::ReadSocketClosed)) => {
             // This isn't an error - it can happen when a new wrapper-client creates a connection in order to check whether something already listens on the socket.
@@ -613,8 +1196,14 @@ async fn listen_on_client_stream(socket: UnixStream) {
         }
     };
     log_info("connection", "new connection started");
+    let push_forwarding =
+        task::spawn_local(forward_push_notifications(push_receiver, writer.clone()));
+    let token_request_forwarding = task::spawn_local(forward_auth_token_requests(
+        token_request_receiver,
+        writer.clone(),
+    ));
     tokio::select! {
-            reader_closing = read_values_loop(client_listener, &client, writer.clone()) => {
+            reader_closing = read_values_loop(client_listener, &client, auth_provider.as_deref(), writer.clone()) => {
                 if let ClosingReason::UnhandledError(err) = reader_closing {
                     let _res = write_closing_error(ClosingError{err_message: err.to_string()}, u32::MAX, &writer).await;
                 };
@@ -628,6 +1217,8 @@ async fn listen_on_client_stream(socket: UnixStream) {
                 }
             }
     }
+    push_forwarding.abort();
+    token_request_forwarding.abort();
     log_trace("client closing", "closing connection");
 }
 
@@ -771,6 +1362,9 @@ enum ClientCreationError {
 enum ClienUsageError {
     #[error("Redis error: {0}")]
     RedisError(#[from] RedisError),
+    /// An error of the node with the given address.
+    #[error("Redis error from {address}: {error}")]
+    NodeError { error: RedisError, address: String },
     /// An error that stems from wrong behavior of the client.
     #[error("Internal error: {0}")]
     InternalError(String),
@@ -867,3 +1461,101 @@ where
 {
     start_socket_listener_internal(init_callback, None);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use response::RequestErrorType;
+
+    fn server_error(code: &str) -> RedisError {
+        redis::make_extension_error(code.to_string(), Some("server error".to_string()))
+    }
+
+    #[test]
+    fn error_kinds_and_codes_are_mapped_to_their_type() {
+        let errors = [
+            (
+                RedisError::from((ErrorKind::ExecAbortError, "error")),
+                RequestErrorType::ExecAbort,
+            ),
+            (
+                RedisError::from((ErrorKind::AuthenticationFailed, "error")),
+                RequestErrorType::NoAuth,
+            ),
+            (
+                RedisError::from((ErrorKind::ReadOnly, "error")),
+                RequestErrorType::ReadOnly,
+            ),
+            (
+                RedisError::from((ErrorKind::CrossSlot, "error")),
+                RequestErrorType::CrossSlot,
+            ),
+            (
+                RedisError::from((ErrorKind::BusyLoadingError, "error")),
+                RequestErrorType::Loading,
+            ),
+            (
+                RedisError::from((ErrorKind::NoScriptError, "error")),
+                RequestErrorType::NoScript,
+            ),
+            (
+                RedisError::from((ErrorKind::Moved, "error")),
+                RequestErrorType::Moved,
+            ),
+            (
+                RedisError::from((ErrorKind::Ask, "error")),
+                RequestErrorType::Ask,
+            ),
+            (
+                RedisError::from((ErrorKind::TryAgain, "error")),
+                RequestErrorType::TryAgain,
+            ),
+            (
+                RedisError::from((ErrorKind::ClusterDown, "error")),
+                RequestErrorType::ClusterDown,
+            ),
+            (
+                server_error(WATCH_ABORTED_ERROR_CODE),
+                RequestErrorType::WatchAborted,
+            ),
+            (server_error("WRONGTYPE"), RequestErrorType::WrongType),
+            (server_error("NOAUTH"), RequestErrorType::NoAuth),
+            (server_error("WRONGPASS"), RequestErrorType::NoAuth),
+            (server_error("NOPERM"), RequestErrorType::NoPermission),
+            (server_error("OOM"), RequestErrorType::OutOfMemory),
+            (server_error("BUSY"), RequestErrorType::Busy),
+            (server_error("UNKNOWNCODE"), RequestErrorType::Unspecified),
+            (
+                RedisError::from((ErrorKind::ResponseError, "error")),
+                RequestErrorType::Unspecified,
+            ),
+        ];
+        for (err, expected_type) in errors {
+            assert_eq!(get_request_error_type(&err), expected_type, "{err}");
+        }
+    }
+
+    #[test]
+    fn redirections_report_their_target_instead_of_the_serving_node() {
+        let moved = RedisError::from((
+            ErrorKind::Moved,
+            "An error was signalled by the server",
+            "1234 redirected:6380".to_string(),
+        ));
+        let request_error = get_request_error(moved, Some("serving:6379".to_string()));
+        assert_eq!(request_error.address.as_ref(), "redirected:6380");
+        assert_eq!(request_error.code.as_ref(), "MOVED");
+
+        let request_error =
+            get_request_error(server_error("WRONGTYPE"), Some("serving:6379".to_string()));
+        assert_eq!(request_error.address.as_ref(), "serving:6379");
+        assert_eq!(request_error.code.as_ref(), "WRONGTYPE");
+        assert_eq!(
+            request_error.type_.enum_value(),
+            Ok(RequestErrorType::WrongType)
+        );
+
+        let request_error = get_request_error(server_error("WRONGTYPE"), None);
+        assert!(request_error.address.is_empty());
+    }
+}
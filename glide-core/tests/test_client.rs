@@ -3,7 +3,7 @@ mod utilities;
 #[cfg(test)]
 pub(crate) mod shared_client_tests {
     use super::*;
-    use glide_core::client::Client;
+    use glide_core::client::{Client, WATCH_ABORTED_ERROR_CODE};
     use redis::RedisConnectionInfo;
     use redis::Value;
     use rstest::rstest;
@@ -35,10 +35,10 @@ pub(crate) mod shared_client_tests {
             // TODO - this is a patch, handling the situation where the new server
             // still isn't available to connection. This should be fixed in [RedisServer].
             let client = repeat_try_create(|| async {
-                Client::new(create_connection_request(
-                    &[connection_addr.clone()],
-                    &configuration,
-                ))
+                Client::new(
+                    create_connection_request(&[connection_addr.clone()], &configuration),
+                    None,
+                )
                 .await
                 .ok()
             })
@@ -227,6 +227,187 @@ pub(crate) mod shared_client_tests {
         });
     }
 
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_update_password_reauthenticates_and_reconnects(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        const USERNAME: &str = "AuthorizedUsername";
+        const NEW_PASSWORD: &str = "RotatedPassword";
+        block_on_all(async {
+            let test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    use_tls: true,
+                    connection_info: Some(redis::RedisConnectionInfo {
+                        password: Some("ReallySecurePassword".to_string()),
+                        username: Some(USERNAME.to_string()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await;
+            let mut client = test_basics.client;
+            let mut set_password_cmd = redis::cmd("ACL");
+            set_password_cmd
+                .arg("SETUSER")
+                .arg(USERNAME)
+                .arg("resetpass")
+                .arg(format!(">{NEW_PASSWORD}"));
+            client
+                .send_command(
+                    &set_password_cmd,
+                    Some(redis::cluster_routing::RoutingInfo::MultiNode((
+                        redis::cluster_routing::MultipleNodeRoutingInfo::AllNodes,
+                        None,
+                    ))),
+                )
+                .await
+                .unwrap();
+            // Clones share the client's connections, so clones made before the update use the new password too.
+            let clone_before_update = client.clone();
+
+            let result = client
+                .update_password(Some(NEW_PASSWORD.to_string()))
+                .await
+                .unwrap();
+            assert_eq!(result, Value::Okay);
+
+            // The connections must authenticate with the new password after reconnecting.
+            kill_connection(&mut client).await;
+            let key = generate_random_string(6);
+            let mut set_cmd = redis::cmd("SET");
+            set_cmd.arg(&key).arg("value");
+            let result = repeat_try_create(|| async {
+                let mut client = clone_before_update.clone();
+                client.send_command(&set_cmd, None).await.ok()
+            })
+            .await;
+            assert_eq!(result, Value::Okay);
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_update_password_keeps_previous_password_when_auth_fails(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        const USERNAME: &str = "AuthorizedUsername";
+        block_on_all(async {
+            let test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    use_tls: true,
+                    connection_info: Some(redis::RedisConnectionInfo {
+                        password: Some("ReallySecurePassword".to_string()),
+                        username: Some(USERNAME.to_string()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await;
+            let mut client = test_basics.client;
+
+            let result = client
+                .update_password(Some("WrongPassword".to_string()))
+                .await;
+            assert!(result.is_err());
+
+            // The connections must still authenticate with the previous password after reconnecting.
+            kill_connection(&mut client).await;
+            let key = generate_random_string(6);
+            let mut set_cmd = redis::cmd("SET");
+            set_cmd.arg(&key).arg("value");
+            let result = repeat_try_create(|| async {
+                let mut client = client.clone();
+                client.send_command(&set_cmd, None).await.ok()
+            })
+            .await;
+            assert_eq!(result, Value::Okay);
+        });
+    }
+
+    struct StaticAuthProvider {
+        password: String,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl glide_core::client::AuthProvider for StaticAuthProvider {
+        fn get_token(&self) -> redis::RedisFuture<'_, glide_core::client::AuthToken> {
+            self.calls
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Box::pin(async move {
+                Ok(glide_core::client::AuthToken {
+                    token: self.password.clone(),
+                    lifetime: None,
+                })
+            })
+        }
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_authenticate_with_auth_provider(#[values(false, true)] use_cluster: bool) {
+        const USERNAME: &str = "AuthorizedUsername";
+        const PASSWORD: &str = "ReallySecurePassword";
+        block_on_all(async {
+            let configuration = TestConfiguration {
+                use_tls: true,
+                connection_info: Some(redis::RedisConnectionInfo {
+                    password: Some(PASSWORD.to_string()),
+                    username: Some(USERNAME.to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            let test_basics = setup_test_basics(use_cluster, configuration).await;
+            let addresses = match &test_basics.server {
+                BackingServer::Standalone(server) => {
+                    vec![server.as_ref().unwrap().get_client_addr()]
+                }
+                BackingServer::Cluster(cluster) => cluster.as_ref().unwrap().get_server_addresses(),
+            };
+            let mut connection_request = create_connection_request(
+                &addresses,
+                &TestConfiguration {
+                    use_tls: true,
+                    cluster_mode: if use_cluster {
+                        ClusterMode::Enabled
+                    } else {
+                        ClusterMode::Disabled
+                    },
+                    request_timeout: Some(10000),
+                    ..Default::default()
+                },
+            );
+            connection_request.authentication_info =
+                protobuf::MessageField::some(glide_core::connection_request::AuthenticationInfo {
+                    username: USERNAME.into(),
+                    token_auth: true,
+                    ..Default::default()
+                });
+            let auth_provider = std::sync::Arc::new(StaticAuthProvider {
+                password: PASSWORD.to_string(),
+                calls: Default::default(),
+            });
+
+            let client =
+                Client::new_with_auth_provider(connection_request, None, auth_provider.clone())
+                    .await
+                    .unwrap();
+            assert!(
+                auth_provider
+                    .calls
+                    .load(std::sync::atomic::Ordering::Relaxed)
+                    > 0
+            );
+            let key = generate_random_string(6);
+            send_set_and_get(client, key.to_string()).await;
+        });
+    }
+
     #[rstest]
     #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
     fn test_request_timeout(#[values(false, true)] use_cluster: bool) {
@@ -241,8 +422,15 @@ pub(crate) mod shared_client_tests {
             )
             .await;
 
-            let mut cmd = redis::Cmd::new();
-            cmd.arg("BLPOP").arg("foo").arg(0); // 0 timeout blocks indefinitely
+            // The script keeps the server busy for 20 milliseconds.
+            let mut cmd = redis::cmd("EVAL");
+            cmd.arg(
+                "local start = redis.call('TIME')
+                repeat
+                    local now = redis.call('TIME')
+                until (now[1] - start[1]) * 1000000 + (now[2] - start[2]) > 20000",
+            )
+            .arg(0);
             let result = test_basics.client.send_command(&cmd, None).await;
             assert!(result.is_err());
             let err = result.unwrap_err();
@@ -250,6 +438,159 @@ pub(crate) mod shared_client_tests {
         });
     }
 
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_request_timeout_override_isnt_extended_for_blocking_commands(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        block_on_all(async {
+            let test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let mut cmd = redis::Cmd::new();
+            cmd.arg("BLPOP").arg(generate_random_string(10)).arg(0);
+            let mut client = test_basics
+                .client
+                .with_request_timeout(std::time::Duration::from_millis(1));
+            let result = client.send_command(&cmd, None).await;
+            let err = result.unwrap_err();
+            assert!(err.is_timeout(), "{err}");
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_blocking_command_doesnt_hold_up_other_commands(
+        #[values(false, true)] use_cluster: bool,
+        #[values("0", "5")] blocking_timeout: &str,
+    ) {
+        block_on_all(async {
+            let test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            // If BLPOP blocked the shared connection, LPUSH would wait behind it on the server until the request timed out.
+            // With a timeout of 0, BLPOP waits for longer than the request timeout, since commands that block indefinitely
+            // have no deadline.
+            let key = generate_random_string(10);
+            let mut blpop = redis::Cmd::new();
+            blpop.arg("BLPOP").arg(&key).arg(blocking_timeout);
+            let mut lpush = redis::Cmd::new();
+            lpush.arg("LPUSH").arg(&key).arg("foo");
+            let mut blocking_client = test_basics.client.clone();
+            let mut client = test_basics.client;
+            let (blpop_result, lpush_result) =
+                futures::join!(blocking_client.send_command(&blpop, None), async {
+                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                    client.send_command(&lpush, None).await
+                });
+            assert_eq!(lpush_result.unwrap(), Value::Int(1));
+            assert_eq!(
+                blpop_result.unwrap(),
+                Value::Array(vec![
+                    Value::BulkString(key.into_bytes()),
+                    Value::BulkString(b"foo".to_vec()),
+                ])
+            );
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_statistics_count_requests_and_timeouts(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let mut client = test_basics.client;
+            // The statistics are shared with the other tests in the process, so only increases are checked.
+            let before = glide_core::client::get_statistics();
+
+            let mut cmd = redis::cmd("ECHO");
+            cmd.arg("foo");
+            client.send_command(&cmd, None).await.unwrap();
+            // A keyed request is attributed to its node in cluster mode as well.
+            let mut cmd = redis::cmd("GET");
+            cmd.arg(generate_random_string(6));
+            client.send_command(&cmd, None).await.unwrap();
+            let mut cmd = redis::cmd("BLPOP");
+            cmd.arg(generate_random_string(6)).arg(0);
+            let result = client
+                .clone()
+                .with_request_timeout(std::time::Duration::from_millis(1))
+                .send_command(&cmd, None)
+                .await;
+            assert!(result.unwrap_err().is_timeout());
+
+            let after = glide_core::client::get_statistics();
+            let echo_count = |statistics: &glide_core::client::StatisticsSnapshot| {
+                statistics.requests.get("ECHO").copied().unwrap_or_default()
+            };
+            assert!(echo_count(&after) > echo_count(&before));
+            assert!(after.timeouts > before.timeouts);
+            assert!(!after.node_latencies.is_empty());
+            assert!(after
+                .to_prometheus()
+                .contains("# TYPE glide_requests_total counter"));
+
+            // The client's own statistics only count its requests, including those of its clones.
+            let client_statistics = client.statistics();
+            assert_eq!(client_statistics.requests["ECHO"], 1);
+            assert_eq!(client_statistics.requests["GET"], 1);
+            assert_eq!(client_statistics.requests["BLPOP"], 1);
+            assert_eq!(client_statistics.timeouts, 1);
+            assert_eq!(client_statistics.redirects, 0);
+            assert!(client_statistics.in_flight_requests.is_empty());
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_blocking_command_extends_request_timeout(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    request_timeout: Some(1),
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let mut cmd = redis::Cmd::new();
+            cmd.arg("BLPOP").arg(generate_random_string(10)).arg(0.5);
+            let result = test_basics.client.send_command(&cmd, None).await;
+            assert_eq!(result.unwrap(), Value::Nil);
+
+            let mut cmd = redis::Cmd::new();
+            cmd.arg("XREAD")
+                .arg("BLOCK")
+                .arg(500)
+                .arg("STREAMS")
+                .arg(generate_random_string(10))
+                .arg("$");
+            let result = test_basics.client.send_command(&cmd, None).await;
+            assert_eq!(result.unwrap(), Value::Nil);
+        });
+    }
+
     #[rstest]
     #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
     fn test_request_transaction_timeout(#[values(false, true)] use_cluster: bool) {
@@ -273,6 +614,342 @@ pub(crate) mod shared_client_tests {
         });
     }
 
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_request_timeout_override(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            // The script keeps the server busy for 20 milliseconds, which is within the client's request timeout.
+            let mut cmd = redis::cmd("EVAL");
+            cmd.arg(
+                "local start = redis.call('TIME')
+                repeat
+                    local now = redis.call('TIME')
+                until (now[1] - start[1]) * 1000000 + (now[2] - start[2]) > 20000",
+            )
+            .arg(0);
+            let mut client = test_basics.client;
+            let mut overridden_client = client
+                .clone()
+                .with_request_timeout(std::time::Duration::from_millis(1));
+
+            let err = overridden_client
+                .send_command(&cmd, None)
+                .await
+                .unwrap_err();
+            assert!(err.is_timeout(), "{err}");
+            let mut pipeline = redis::pipe();
+            pipeline.add_command(cmd.clone());
+            let err = overridden_client
+                .send_transaction(&pipeline, None)
+                .await
+                .unwrap_err();
+            assert!(err.is_timeout(), "{err}");
+
+            // The override only applies to the requests of the client that it was set on.
+            assert!(client.send_command(&cmd, None).await.is_ok());
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_batch_returns_results_in_order(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            // The keys are spread across slots, so a cluster client sends them to different nodes.
+            let keys: Vec<_> = (0..3).map(|_| generate_random_string(10)).collect();
+            let mut pipeline = redis::pipe();
+            pipeline
+                .set(&keys[0], "foo")
+                .get(&keys[0])
+                .set(&keys[1], "bar")
+                .get(&keys[1])
+                .get(&keys[2]);
+            let results = test_basics.client.send_batch(&pipeline, None).await;
+
+            assert_eq!(results.len(), 5);
+            assert_eq!(results[0].as_ref().unwrap(), &Value::Okay);
+            assert_eq!(
+                results[1].as_ref().unwrap(),
+                &Value::BulkString(b"foo".to_vec())
+            );
+            assert_eq!(results[2].as_ref().unwrap(), &Value::Okay);
+            assert_eq!(
+                results[3].as_ref().unwrap(),
+                &Value::BulkString(b"bar".to_vec())
+            );
+            assert_eq!(results[4].as_ref().unwrap(), &Value::Nil);
+
+            // The commands of a key are pipelined to the same node, and only the command that failed gets an error.
+            let mut pipeline = redis::pipe();
+            pipeline
+                .set(&keys[2], "foo")
+                .lpush(&keys[2], "bar")
+                .get(&keys[2]);
+            let results = test_basics.client.send_batch(&pipeline, None).await;
+
+            assert_eq!(results.len(), 3);
+            assert_eq!(results[0].as_ref().unwrap(), &Value::Okay);
+            let err = results[1].as_ref().unwrap_err();
+            assert_eq!(err.code(), Some("WRONGTYPE"), "{err}");
+            assert_eq!(
+                results[2].as_ref().unwrap(),
+                &Value::BulkString(b"foo".to_vec())
+            );
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_send_command_to_nodes_returns_each_node_result(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let mut cmd = redis::cmd("ECHO");
+            cmd.arg("foo");
+            let results = test_basics
+                .client
+                .send_command_to_nodes(
+                    &cmd,
+                    &redis::cluster_routing::MultipleNodeRoutingInfo::AllMasters,
+                )
+                .await
+                .unwrap();
+            assert!(!results.is_empty());
+            if !use_cluster {
+                assert_eq!(results.len(), 1);
+            }
+            for (address, result) in results {
+                assert!(address.contains(':'), "{address}");
+                assert_eq!(result.unwrap(), Value::BulkString(b"foo".to_vec()));
+            }
+
+            // The nodes' errors are returned per node, instead of failing the request.
+            let cmd = redis::cmd("NOT_A_COMMAND");
+            let results = test_basics
+                .client
+                .send_command_to_nodes(
+                    &cmd,
+                    &redis::cluster_routing::MultipleNodeRoutingInfo::AllNodes,
+                )
+                .await
+                .unwrap();
+            assert!(!results.is_empty());
+            assert!(results.iter().all(|(_, result)| result.is_err()));
+
+            // Each node's request times out on its own, instead of failing the request.
+            let mut client = test_basics
+                .client
+                .with_request_timeout(std::time::Duration::from_millis(100));
+            let mut cmd = redis::cmd("BLPOP");
+            cmd.arg(generate_random_string(10)).arg(1);
+            let results = client
+                .send_command_to_nodes(
+                    &cmd,
+                    &redis::cluster_routing::MultipleNodeRoutingInfo::AllMasters,
+                )
+                .await
+                .unwrap();
+            assert!(!results.is_empty());
+            for (address, result) in results {
+                let err = result.unwrap_err();
+                assert!(err.is_timeout(), "{address}: {err}");
+            }
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_route_by_address(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let results = test_basics
+                .client
+                .send_command_to_nodes(
+                    &redis::cmd("PING"),
+                    &redis::cluster_routing::MultipleNodeRoutingInfo::AllNodes,
+                )
+                .await
+                .unwrap();
+            let (address, _) = results.last().unwrap();
+            let (host, port) = address.rsplit_once(':').unwrap();
+            let by_address = |host: &str, port: u16| {
+                Some(redis::cluster_routing::RoutingInfo::SingleNode(
+                    redis::cluster_routing::SingleNodeRoutingInfo::ByAddress {
+                        host: host.to_string(),
+                        port,
+                    },
+                ))
+            };
+
+            let mut cmd = redis::cmd("ECHO");
+            cmd.arg("foo");
+            let result = test_basics
+                .client
+                .send_command(&cmd, by_address(host, port.parse().unwrap()))
+                .await;
+            assert_eq!(result.unwrap(), Value::BulkString(b"foo".to_vec()));
+
+            let err = test_basics
+                .client
+                .send_command(&cmd, by_address("unknown-host", 1))
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::ClientError, "{err}");
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_multi_node_route_without_response_policy_returns_value_per_address(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let mut cmd = redis::cmd("ECHO");
+            cmd.arg("foo");
+            let result = test_basics
+                .client
+                .send_command(
+                    &cmd,
+                    Some(redis::cluster_routing::RoutingInfo::MultiNode((
+                        redis::cluster_routing::MultipleNodeRoutingInfo::AllNodes,
+                        None,
+                    ))),
+                )
+                .await
+                .unwrap();
+            let Value::Map(values) = result else {
+                panic!("Unexpected value {result:?}");
+            };
+            assert!(!values.is_empty());
+            for (address, value) in values {
+                let address = redis::from_redis_value::<String>(&address).unwrap();
+                assert!(address.contains(':'), "{address}");
+                assert_eq!(value, Value::BulkString(b"foo".to_vec()));
+            }
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_standalone_replica_route_fails_without_replicas() {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                false,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            // The standalone server has no replicas.
+            let err = test_basics
+                .client
+                .send_command(
+                    redis::cmd("ECHO").arg("foo"),
+                    Some(redis::cluster_routing::RoutingInfo::SingleNode(
+                        redis::cluster_routing::SingleNodeRoutingInfo::SpecificNode(
+                            redis::cluster_routing::Route::new(
+                                0,
+                                redis::cluster_routing::SlotAddr::ReplicaRequired,
+                            ),
+                        ),
+                    )),
+                )
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::ClientError, "{err}");
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_watched_transaction(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let key = generate_random_string(10);
+            let mut session = test_basics
+                .client
+                .watch(&[key.as_bytes().to_vec()])
+                .await
+                .unwrap();
+            let mut get = redis::cmd("GET");
+            get.arg(&key);
+            assert_eq!(session.send_command(&get).await.unwrap(), Value::Nil);
+            let mut pipeline = redis::pipe();
+            pipeline.atomic().set(&key, "foo").get(&key);
+            assert_eq!(
+                session.exec(&pipeline).await.unwrap(),
+                Value::Array(vec![Value::Okay, Value::BulkString(b"foo".to_vec())])
+            );
+
+            // A change to the key through the client, after it was watched, aborts the session's transaction.
+            let session = test_basics
+                .client
+                .watch(&[key.as_bytes().to_vec()])
+                .await
+                .unwrap();
+            let mut set = redis::cmd("SET");
+            set.arg(&key).arg("bar");
+            test_basics.client.send_command(&set, None).await.unwrap();
+            let err = session.exec(&pipeline).await.unwrap_err();
+            assert_eq!(err.code(), Some(WATCH_ABORTED_ERROR_CODE), "{err}");
+            let value = test_basics.client.send_command(&get, None).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"bar".to_vec()));
+        });
+    }
+
     #[rstest]
     #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
     fn test_client_name_after_reconnection(#[values(false, true)] use_cluster: bool) {
@@ -363,4 +1040,93 @@ pub(crate) mod shared_client_tests {
             );
         });
     }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_receive_pubsub_messages(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let addresses = if use_cluster {
+                get_shared_cluster_addresses(false)
+            } else {
+                vec![get_shared_server_address(false)]
+            };
+            let (push_sender, mut push_receiver) = tokio::sync::mpsc::unbounded_channel();
+            let mut subscriber = Client::new(
+                create_connection_request(
+                    &addresses,
+                    &TestConfiguration {
+                        cluster_mode: if use_cluster {
+                            ClusterMode::Enabled
+                        } else {
+                            ClusterMode::Disabled
+                        },
+                        request_timeout: Some(10000),
+                        ..Default::default()
+                    },
+                ),
+                Some(push_sender),
+            )
+            .await
+            .unwrap();
+
+            let channel = generate_random_string(10);
+            let mut subscribe = redis::cmd("SUBSCRIBE");
+            subscribe.arg(&channel);
+            let result = subscriber.send_command(&subscribe, None).await;
+            assert_eq!(result, Ok(Value::Okay));
+
+            let mut publish = redis::cmd("PUBLISH");
+            publish.arg(&channel).arg("foo");
+            test_basics
+                .client
+                .send_command(&publish, None)
+                .await
+                .unwrap();
+
+            loop {
+                let push = push_receiver.recv().await.unwrap();
+                if push.kind == redis::PushKind::Message {
+                    assert_eq!(
+                        push.data,
+                        vec![
+                            Value::BulkString(channel.into_bytes()),
+                            Value::BulkString(b"foo".to_vec()),
+                        ]
+                    );
+                    break;
+                }
+            }
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_subscription_requires_push_sender(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let mut subscribe = redis::cmd("SUBSCRIBE");
+            subscribe.arg("foo");
+            let result = test_basics.client.send_command(&subscribe, None).await;
+            assert_eq!(
+                result.unwrap_err().kind(),
+                redis::ErrorKind::InvalidClientConfig
+            );
+        });
+    }
 }
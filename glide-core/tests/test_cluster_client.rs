@@ -2,9 +2,10 @@ mod utilities;
 
 #[cfg(test)]
 mod cluster_client_tests {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
 
     use super::*;
+    use glide_core::client::{ClusterScanArgs, FINISHED_SCAN_CURSOR};
     use glide_core::connection_request::ReadFrom;
     use redis::cluster_routing::{
         MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
@@ -212,4 +213,101 @@ mod cluster_client_tests {
             assert_eq!(replicas, 1);
         });
     }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_cluster_scan_returns_matching_keys_from_every_primary() {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics_internal(TestConfiguration {
+                cluster_mode: ClusterMode::Enabled,
+                shared_server: true,
+                ..Default::default()
+            })
+            .await;
+
+            let prefix = generate_random_string(10);
+            let mut expected_keys = HashSet::new();
+            for index in 0..100 {
+                let key = format!("{prefix}:{index}");
+                let mut cmd = redis::cmd("SET");
+                cmd.arg(key.as_str()).arg("value");
+                test_basics.client.send_command(&cmd, None).await.unwrap();
+                expected_keys.insert(key);
+            }
+            let mut cmd = redis::cmd("HSET");
+            cmd.arg(format!("{prefix}:hash")).arg("field").arg("value");
+            test_basics.client.send_command(&cmd, None).await.unwrap();
+
+            let args = ClusterScanArgs {
+                match_pattern: Some(format!("{prefix}:*").into_bytes()),
+                count: Some(10),
+                object_type: Some("string".to_string()),
+            };
+            let mut cursor = FINISHED_SCAN_CURSOR.to_string();
+            let mut keys = HashSet::new();
+            loop {
+                let result = test_basics
+                    .client
+                    .cluster_scan(&cursor, &args)
+                    .await
+                    .unwrap();
+                let (next_cursor, found_keys): (String, Vec<String>) =
+                    redis::from_redis_value(&result).unwrap();
+                keys.extend(found_keys);
+                if next_cursor == FINISHED_SCAN_CURSOR {
+                    break;
+                }
+                cursor = next_cursor;
+            }
+            assert_eq!(keys, expected_keys);
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_cluster_scan_cursor_belongs_to_its_client_until_released() {
+        block_on_all(async {
+            let configuration = || TestConfiguration {
+                cluster_mode: ClusterMode::Enabled,
+                shared_server: true,
+                ..Default::default()
+            };
+            let mut test_basics = setup_test_basics_internal(configuration()).await;
+            let mut other_basics = setup_test_basics_internal(configuration()).await;
+            // Enough keys that a scan isn't finished after its first step.
+            let prefix = generate_random_string(10);
+            for index in 0..100 {
+                let mut cmd = redis::cmd("SET");
+                cmd.arg(format!("{prefix}:{index}")).arg("value");
+                test_basics.client.send_command(&cmd, None).await.unwrap();
+            }
+
+            let args = ClusterScanArgs {
+                count: Some(1),
+                ..Default::default()
+            };
+            let result = test_basics
+                .client
+                .cluster_scan(FINISHED_SCAN_CURSOR, &args)
+                .await
+                .unwrap();
+            let (cursor, _): (String, Vec<String>) = redis::from_redis_value(&result).unwrap();
+            assert_ne!(cursor, FINISHED_SCAN_CURSOR);
+
+            // Another client doesn't know the cursor.
+            assert!(other_basics
+                .client
+                .cluster_scan(&cursor, &args)
+                .await
+                .is_err());
+
+            test_basics.client.release_cluster_scan_cursor(&cursor);
+            let err = test_basics
+                .client
+                .cluster_scan(&cursor, &args)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::ClientError, "{err}");
+        });
+    }
 }
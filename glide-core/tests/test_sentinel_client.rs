@@ -0,0 +1,111 @@
+mod utilities;
+
+#[cfg(test)]
+mod sentinel_client_tests {
+    use super::*;
+    use glide_core::client::Client;
+    use redis::Value;
+    use rstest::rstest;
+    use utilities::sentinel::{RedisSentinelSetup, MASTER_NAME};
+    use utilities::*;
+
+    async fn setup_sentinel_client(setup: &RedisSentinelSetup) -> Client {
+        wait_for_server_to_become_ready(&setup.primary.get_client_addr()).await;
+        wait_for_server_to_become_ready(&setup.replica.get_client_addr()).await;
+        // The sentinel might not know the master until it reads its configuration.
+        repeat_try_create(|| async { Client::new(setup.connection_request(), None).await.ok() })
+            .await
+    }
+
+    #[rstest]
+    #[timeout(LONG_STANDALONE_TEST_TIMEOUT)]
+    fn test_sentinel_send_set_and_get() {
+        block_on_all(async {
+            let setup = RedisSentinelSetup::new();
+            let client = setup_sentinel_client(&setup).await;
+            send_set_and_get(client, generate_random_string(10)).await;
+        });
+    }
+
+    /// Failovers take a few seconds, so this waits longer than `repeat_try_create`. The test timeout bounds the wait.
+    async fn wait_for<T, Fut>(f: impl Fn() -> Fut) -> T
+    where
+        Fut: std::future::Future<Output = Option<T>>,
+    {
+        loop {
+            if let Some(value) = f().await {
+                return value;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    }
+
+    #[rstest]
+    #[timeout(LONG_STANDALONE_TEST_TIMEOUT)]
+    fn test_sentinel_writes_to_new_primary_after_failover() {
+        block_on_all(async {
+            let setup = RedisSentinelSetup::new();
+            let mut client = setup_sentinel_client(&setup).await;
+            let key = generate_random_string(10);
+            let mut set_command = redis::cmd("SET");
+            set_command.arg(key.as_str()).arg("before");
+            assert_eq!(
+                client.send_command(&set_command, None).await.unwrap(),
+                Value::Okay
+            );
+
+            let sentinel = redis::Client::open(redis::ConnectionInfo {
+                addr: setup.sentinel_address(),
+                redis: Default::default(),
+            })
+            .unwrap();
+            let sentinel_connection = sentinel.get_multiplexed_async_connection().await.unwrap();
+            // The failover is refused until the sentinel discovers the replica.
+            wait_for(|| {
+                let mut sentinel_connection = sentinel_connection.clone();
+                async move {
+                    sentinel_connection
+                        .send_packed_command(
+                            redis::cmd("SENTINEL").arg("FAILOVER").arg(MASTER_NAME),
+                        )
+                        .await
+                        .ok()
+                }
+            })
+            .await;
+
+            let replica_address = setup.replica.get_client_addr();
+            wait_for(|| {
+                let mut sentinel_connection = sentinel_connection.clone();
+                let replica_address = replica_address.clone();
+                async move {
+                    let master = sentinel_connection
+                        .send_packed_command(
+                            redis::cmd("SENTINEL")
+                                .arg("GET-MASTER-ADDR-BY-NAME")
+                                .arg(MASTER_NAME),
+                        )
+                        .await
+                        .ok()?;
+                    let (host, port): (String, u16) = redis::from_redis_value(&master).ok()?;
+                    (redis::ConnectionAddr::Tcp(host, port) == replica_address).then_some(())
+                }
+            })
+            .await;
+
+            let mut set_command = redis::cmd("SET");
+            set_command.arg(key.as_str()).arg("after");
+            // Writes fail until the client switches to the new primary.
+            wait_for(|| {
+                let mut client = client.clone();
+                let set_command = set_command.clone();
+                async move { client.send_command(&set_command, None).await.ok() }
+            })
+            .await;
+            assert_eq!(
+                send_get(&mut client, key.as_str()).await.unwrap(),
+                Value::BulkString(b"after".to_vec())
+            );
+        });
+    }
+}
@@ -17,8 +17,9 @@ mod socket_listener {
     use crate::utilities::mocks::{Mock, ServerMock};
 
     use super::*;
-    use glide_core::redis_request::command::{Args, ArgsArray};
-    use glide_core::redis_request::{Command, Transaction};
+    use glide_core::redis_request::command::{Args, ArgsArray, BinaryArgsArray};
+    use glide_core::redis_request::{Batch, Command, Transaction};
+    use glide_core::response::batch_response::entry as batch_entry;
     use glide_core::response::{response, ConstantResponse, Response};
     use glide_core::scripts_container::add_script;
     use protobuf::{EnumOrUnknown, Message};
@@ -227,9 +228,11 @@ mod socket_listener {
         buffer: &mut Vec<u8>,
         callback_index: u32,
         commands_components: Vec<CommandComponents>,
+        watch_session: u64,
     ) -> u32 {
         let mut request = RedisRequest::new();
         request.callback_idx = callback_index;
+        request.watch_session = watch_session;
         let mut transaction = Transaction::new();
         transaction.commands.reserve(commands_components.len());
 
@@ -282,7 +285,7 @@ mod socket_listener {
     ) {
         // Send the server address
         const CALLBACK_INDEX: u32 = 0;
-        let connection_request = create_connection_request(
+        let mut connection_request = create_connection_request(
             addresses,
             &TestConfiguration {
                 use_tls,
@@ -291,6 +294,8 @@ mod socket_listener {
                 ..Default::default()
             },
         );
+        // The tests read push notifications and free their values, like a wrapper that handles them.
+        connection_request.push_notifications = true;
         let approx_message_length =
             APPROX_RESP_HEADER_LEN + connection_request.compute_size() as usize;
         let mut buffer = Vec::with_capacity(approx_message_length);
@@ -577,6 +582,66 @@ mod socket_listener {
         );
     }
 
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_socket_batch_reports_each_command_error(
+        #[values(true, false)] use_cluster: bool,
+    ) {
+        let mut test_basics = setup_test_basics(false, true, use_cluster);
+
+        const CALLBACK_INDEX: u32 = 100;
+        let key = generate_random_string(KEY_LENGTH);
+        let mut request = RedisRequest::new();
+        request.callback_idx = CALLBACK_INDEX;
+        let mut batch = Batch::new();
+        for args in [
+            vec!["SET".to_string(), key.clone(), "foo".to_string()],
+            vec!["LPUSH".to_string(), key.clone(), "bar".to_string()],
+            vec!["GET".to_string(), key],
+        ] {
+            batch.commands.push(get_command(CommandComponents {
+                args,
+                request_type: RequestType::CustomCommand.into(),
+                args_pointer: false,
+            }));
+        }
+        request.command = Some(redis_request::redis_request::Command::Batch(batch));
+        let mut buffer = Vec::with_capacity(APPROX_RESP_HEADER_LEN * 4);
+        write_message(&mut buffer, request);
+        test_basics.socket.write_all(&buffer).unwrap();
+
+        let response = ResponseReader::default().read_response(&mut test_basics.socket);
+        assert_eq!(response.callback_idx, CALLBACK_INDEX);
+        let Some(response::Value::BatchResponse(batch_response)) = response.value else {
+            panic!("Unexpected response {:?}", response.value);
+        };
+        let entries = batch_response.entries;
+        assert_eq!(entries.len(), 3);
+        // The commands are pipelined to the key's node, and only the command that failed gets an error.
+        match entries[0].value {
+            Some(batch_entry::Value::RespPointer(pointer)) => {
+                assert_value(pointer, Some(Value::Okay))
+            }
+            ref value => panic!("Unexpected entry {value:?}"),
+        }
+        assert!(
+            matches!(
+                entries[1].value,
+                Some(batch_entry::Value::RequestError(ref err)) if err.message.contains("WRONGTYPE")
+                    && err.type_ == glide_core::response::RequestErrorType::WrongType.into()
+                    && &*err.code == "WRONGTYPE"
+            ),
+            "{:?}",
+            entries[1]
+        );
+        match entries[2].value {
+            Some(batch_entry::Value::RespPointer(pointer)) => {
+                assert_value(pointer, Some(Value::BulkString(b"foo".to_vec())))
+            }
+            ref value => panic!("Unexpected entry {value:?}"),
+        }
+    }
+
     #[rstest]
     #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
     fn test_socket_pass_manual_route_to_all_primaries() {
@@ -635,6 +700,28 @@ mod socket_listener {
         assert_null_response(&buffer, CALLBACK_INDEX);
     }
 
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_request_timeout_overrides_client_timeout() {
+        const CALLBACK_INDEX: u32 = 99;
+        let mut test_basics = setup_test_basics(false, true, false);
+        let key = generate_random_string(KEY_LENGTH);
+        let mut buffer = Vec::with_capacity(key.len() * 2);
+        // The blocking command outlasts the default request timeout.
+        let mut request = get_command_request(
+            CALLBACK_INDEX,
+            vec!["BLPOP".to_string(), key, "1".to_string()],
+            RequestType::CustomCommand.into(),
+            false,
+        );
+        request.request_timeout = 5000;
+        write_message(&mut buffer, request);
+        test_basics.socket.write_all(&buffer).unwrap();
+
+        let _size = read_from_socket(&mut buffer, &mut test_basics.socket);
+        assert_null_response(&buffer, CALLBACK_INDEX);
+    }
+
     #[rstest]
     #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
     fn test_socket_report_error() {
@@ -962,7 +1049,7 @@ mod socket_listener {
             },
         ];
         let mut buffer = Vec::with_capacity(200);
-        write_transaction_request(&mut buffer, CALLBACK_INDEX, commands);
+        write_transaction_request(&mut buffer, CALLBACK_INDEX, commands, 0);
         socket.write_all(&buffer).unwrap();
 
         let _size = read_from_socket(&mut buffer, &mut socket);
@@ -982,28 +1069,104 @@ mod socket_listener {
 
     #[rstest]
     #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
-    fn test_send_script(#[values(true, false)] use_cluster: bool) {
+    fn test_watch_session_reports_abort(#[values(true, false)] use_cluster: bool) {
+        let test_basics = setup_test_basics(false, true, use_cluster);
+        let mut socket = test_basics.socket;
+        let key = generate_random_string(KEY_LENGTH);
+        let mut buffer = Vec::with_capacity(200);
+
+        let mut request = RedisRequest::new();
+        request.callback_idx = 0;
+        request.command = Some(redis_request::redis_request::Command::Watch(
+            redis_request::Watch {
+                keys: vec![key.clone().into_bytes().into()],
+                ..Default::default()
+            },
+        ));
+        write_message(&mut buffer, request);
+        socket.write_all(&buffer).unwrap();
+        let _size = read_from_socket(&mut buffer, &mut socket);
+        // Session ids start at 1 on every socket.
+        assert_response(&buffer, 0, 0, Some(Value::Int(1)), ResponseType::Value);
+
+        // The key is modified outside of the session, after it was watched.
+        buffer.clear();
+        write_command_request(
+            &mut buffer,
+            1,
+            vec![key.clone(), "foo".to_string()],
+            RequestType::SetString.into(),
+            false,
+        );
+        socket.write_all(&buffer).unwrap();
+        let _size = read_from_socket(&mut buffer, &mut socket);
+        assert_ok_response(&buffer, 1);
+
+        let commands = vec![CommandComponents {
+            args: vec!["SET".to_string(), key, "bar".to_string()],
+            args_pointer: false,
+            request_type: RequestType::CustomCommand.into(),
+        }];
+        buffer.clear();
+        write_transaction_request(&mut buffer, 2, commands, 1);
+        socket.write_all(&buffer).unwrap();
+        let _size = read_from_socket(&mut buffer, &mut socket);
+        let response = assert_error_response(&buffer, 2, ResponseType::RequestError);
+        assert_eq!(
+            response.request_error().type_.enum_value(),
+            Ok(glide_core::response::RequestErrorType::WatchAborted)
+        );
+
+        // The transaction ended the session.
+        buffer.clear();
+        let mut request = RedisRequest::new();
+        request.callback_idx = 3;
+        request.watch_session = 1;
+        request.command = Some(redis_request::redis_request::Command::Unwatch(
+            Default::default(),
+        ));
+        write_message(&mut buffer, request);
+        socket.write_all(&buffer).unwrap();
+        let _size = read_from_socket(&mut buffer, &mut socket);
+        assert_error_response(&buffer, 3, ResponseType::RequestError);
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_send_script(
+        #[values(true, false)] use_cluster: bool,
+        #[values(false, true)] binary_args: bool,
+    ) {
         let mut test_basics = setup_test_basics(false, true, use_cluster);
         let socket = &mut test_basics.socket;
         const CALLBACK_INDEX: u32 = 100;
         const VALUE_LENGTH: usize = 10;
         let key = generate_random_string(KEY_LENGTH);
-        let value = generate_random_string(VALUE_LENGTH);
+        let mut value = generate_random_string(VALUE_LENGTH).into_bytes();
+        if binary_args {
+            value.extend_from_slice(&[0, 0xff, 0xfe]);
+        }
         let script = r#"redis.call("SET", KEYS[1], ARGV[1]); return redis.call("GET", KEYS[1])"#;
         let hash = add_script(script);
 
         let approx_message_length = hash.len() + value.len() + key.len() + APPROX_RESP_HEADER_LEN;
         let mut buffer = Vec::with_capacity(approx_message_length);
 
+        let mut invocation = redis_request::ScriptInvocation {
+            hash: hash.into(),
+            ..Default::default()
+        };
+        if binary_args {
+            invocation.binary_keys = vec![key.into_bytes().into()];
+            invocation.binary_args = vec![value.clone().into()];
+        } else {
+            invocation.keys = vec![key.into()];
+            invocation.args = vec![String::from_utf8(value.clone()).unwrap().into()];
+        }
         let mut request = RedisRequest::new();
         request.callback_idx = CALLBACK_INDEX;
         request.command = Some(redis_request::redis_request::Command::ScriptInvocation(
-            redis_request::ScriptInvocation {
-                hash: hash.into(),
-                keys: vec![key.into()],
-                args: vec![value.clone().into()],
-                ..Default::default()
-            },
+            invocation,
         ));
 
         write_header(&mut buffer, request.compute_size() as u32);
@@ -1016,8 +1179,187 @@ mod socket_listener {
             &buffer,
             0,
             CALLBACK_INDEX,
-            Some(Value::BulkString(value.into_bytes())),
+            Some(Value::BulkString(value)),
             ResponseType::Value,
         );
     }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_socket_handle_binary_args(
+        #[values(false, true)] args_pointer: bool,
+        #[values(true, false)] use_cluster: bool,
+    ) {
+        let mut test_basics = setup_test_basics(false, true, use_cluster);
+
+        const CALLBACK1_INDEX: u32 = 100;
+        const CALLBACK2_INDEX: u32 = 101;
+        let key = generate_random_string(KEY_LENGTH).into_bytes();
+        let value = vec![b'a', 0, 0xff, 0xfe];
+        let get_binary_command = |request_type: RequestType, args: Vec<Vec<u8>>| {
+            let mut command = Command::new();
+            command.request_type = request_type.into();
+            command.args = Some(if args_pointer {
+                Args::BinaryArgsVecPointer(Box::leak(Box::new(args)) as *mut Vec<Vec<u8>> as u64)
+            } else {
+                let mut args_array = BinaryArgsArray::new();
+                args_array.args = args.into_iter().map(|arg| arg.into()).collect();
+                Args::BinaryArgsArray(args_array)
+            });
+            command
+        };
+
+        let mut buffer = Vec::with_capacity(key.len() + value.len() + APPROX_RESP_HEADER_LEN);
+        let mut request = RedisRequest::new();
+        request.callback_idx = CALLBACK1_INDEX;
+        request.command = Some(redis_request::redis_request::Command::SingleCommand(
+            get_binary_command(RequestType::SetString, vec![key.clone(), value.clone()]),
+        ));
+        write_message(&mut buffer, request);
+        test_basics.socket.write_all(&buffer).unwrap();
+
+        let _size = read_from_socket(&mut buffer, &mut test_basics.socket);
+        assert_ok_response(&buffer, CALLBACK1_INDEX);
+
+        buffer.clear();
+        let mut request = RedisRequest::new();
+        request.callback_idx = CALLBACK2_INDEX;
+        request.command = Some(redis_request::redis_request::Command::SingleCommand(
+            get_binary_command(RequestType::GetString, vec![key]),
+        ));
+        write_message(&mut buffer, request);
+        test_basics.socket.write_all(&buffer).unwrap();
+
+        let _size = read_from_socket(&mut buffer, &mut test_basics.socket);
+        assert_response(
+            &buffer,
+            0,
+            CALLBACK2_INDEX,
+            Some(Value::BulkString(value)),
+            ResponseType::Value,
+        );
+    }
+
+    /// Reads responses from the socket, and keeps the bytes of a response that wasn't fully read yet for the next read.
+    #[derive(Default)]
+    struct ResponseReader {
+        pending: Vec<u8>,
+    }
+
+    impl ResponseReader {
+        /// Reads until at least one response is complete, and returns every complete response.
+        fn read_responses(&mut self, socket: &mut UnixStream) -> Vec<Response> {
+            let mut responses = Vec::new();
+            while responses.is_empty() {
+                let mut chunk = [0_u8; 1024];
+                let size = socket.read(&mut chunk).unwrap();
+                assert!(size > 0, "The socket was closed");
+                self.pending.extend_from_slice(&chunk[..size]);
+                let mut cursor = 0;
+                // The header itself may also be split between reads.
+                while let Some((message_length, header_bytes)) =
+                    u32::decode_var(&self.pending[cursor..])
+                {
+                    let message_end = cursor + header_bytes + message_length as usize;
+                    if message_end > self.pending.len() {
+                        break;
+                    }
+                    responses.push(decode_response(
+                        &self.pending,
+                        cursor + header_bytes,
+                        message_length as usize,
+                    ));
+                    cursor = message_end;
+                }
+                self.pending.drain(..cursor);
+            }
+            responses
+        }
+
+        /// Reads a single response, for requests that get exactly one response and no push notifications.
+        fn read_response(&mut self, socket: &mut UnixStream) -> Response {
+            let mut responses = self.read_responses(socket);
+            assert_eq!(responses.len(), 1, "{responses:?}");
+            responses.pop().unwrap()
+        }
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_socket_receive_pubsub_message(#[values(true, false)] use_cluster: bool) {
+        let mut test_basics = setup_test_basics(false, true, use_cluster);
+        const SUBSCRIBE_CALLBACK_INDEX: u32 = 100;
+        const PUBLISH_CALLBACK_INDEX: u32 = 101;
+        let channel = generate_random_string(KEY_LENGTH);
+        let mut reader = ResponseReader::default();
+
+        let mut buffer = Vec::with_capacity(100);
+        write_command_request(
+            &mut buffer,
+            SUBSCRIBE_CALLBACK_INDEX,
+            vec![channel.clone()],
+            RequestType::Subscribe.into(),
+            false,
+        );
+        test_basics.socket.write_all(&buffer).unwrap();
+
+        let mut subscribed = false;
+        while !subscribed {
+            for response in reader.read_responses(&mut test_basics.socket) {
+                match response.value {
+                    Some(response::Value::ConstantResponse(_)) => {
+                        assert_eq!(response.callback_idx, SUBSCRIBE_CALLBACK_INDEX);
+                        subscribed = true;
+                    }
+                    // The subscription confirmation is also sent as a push notification.
+                    Some(response::Value::PushPointer(pointer)) => {
+                        drop(unsafe { Box::from_raw(pointer as *mut Value) });
+                    }
+                    _ => panic!("Unexpected response {response:?}"),
+                }
+            }
+        }
+
+        buffer.clear();
+        write_command_request(
+            &mut buffer,
+            PUBLISH_CALLBACK_INDEX,
+            vec!["PUBLISH".to_string(), channel.clone(), "foo".to_string()],
+            RequestType::CustomCommand.into(),
+            false,
+        );
+        test_basics.socket.write_all(&buffer).unwrap();
+
+        loop {
+            let mut received_message = false;
+            for response in reader.read_responses(&mut test_basics.socket) {
+                match response.value {
+                    Some(response::Value::RespPointer(pointer)) => {
+                        assert_eq!(response.callback_idx, PUBLISH_CALLBACK_INDEX);
+                        drop(unsafe { Box::from_raw(pointer as *mut Value) });
+                    }
+                    Some(response::Value::PushPointer(pointer)) => {
+                        let value = unsafe { Box::from_raw(pointer as *mut Value) };
+                        let Value::Push { kind, data } = *value else {
+                            panic!("Unexpected push value {value:?}");
+                        };
+                        if kind == redis::PushKind::Message {
+                            assert_eq!(
+                                data,
+                                vec![
+                                    Value::BulkString(channel.clone().into_bytes()),
+                                    Value::BulkString(b"foo".to_vec()),
+                                ]
+                            );
+                            received_message = true;
+                        }
+                    }
+                    _ => panic!("Unexpected response {response:?}"),
+                }
+            }
+            if received_message {
+                break;
+            }
+        }
+    }
 }
@@ -5,9 +5,18 @@ mod standalone_client_tests {
     use crate::utilities::mocks::{Mock, ServerMock};
 
     use super::*;
-    use glide_core::{client::StandaloneClient, connection_request::ReadFrom};
+    use glide_core::{
+        client::{StandaloneClient, StandaloneClientConnectionError},
+        connection_request::{
+            ConnectionRequest, ConnectionRetryStrategy, ReadFrom, TlsConfiguration, TlsMode,
+        },
+    };
+    use redis::cluster_routing::{
+        MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
+    };
     use redis::{FromRedisValue, Value};
     use rstest::rstest;
+    use std::collections::{HashMap, HashSet};
     use utilities::*;
 
     #[rstest]
@@ -256,6 +265,366 @@ mod standalone_client_tests {
         });
     }
 
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_read_from_lowest_latency_node() {
+        let mocks = create_primary_mock_with_replicas(3);
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+        for mock in mocks.iter() {
+            for _ in 0..3 {
+                mock.add_response(&cmd, "$-1\r\n".to_string());
+            }
+        }
+        let addresses: Vec<redis::ConnectionAddr> =
+            mocks.iter().flat_map(|mock| mock.get_addresses()).collect();
+        let mut connection_request =
+            create_connection_request(addresses.as_slice(), &Default::default());
+        connection_request.read_from = ReadFrom::LowestLatency.into();
+
+        block_on_all(async {
+            let mut client = StandaloneClient::create_client(connection_request)
+                .await
+                .unwrap();
+            for _ in 0..3 {
+                let result = client.send_command(&cmd).await.unwrap();
+                assert_eq!(result, Value::Nil);
+            }
+        });
+
+        // The latencies of the mocks are similar, so any node might be chosen, but every read must be served exactly once.
+        let total_reads: u16 = mocks
+            .iter()
+            .map(|mock| mock.get_number_of_received_commands())
+            .sum();
+        assert_eq!(total_reads, 3);
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_read_from_replica_in_client_availability_zone() {
+        let mocks = create_primary_mock_with_replicas(3);
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+        for mock in mocks.iter() {
+            for _ in 0..3 {
+                mock.add_response(&cmd, "$-1\r\n".to_string());
+            }
+        }
+        let addresses: Vec<redis::ConnectionAddr> =
+            mocks.iter().flat_map(|mock| mock.get_addresses()).collect();
+        let mut connection_request =
+            create_connection_request(addresses.as_slice(), &Default::default());
+        connection_request.read_from = ReadFrom::AZAffinity.into();
+        connection_request.client_az = "zone-a".into();
+        for (index, address) in connection_request.addresses.iter_mut().enumerate() {
+            address.availability_zone = if index == 2 { "zone-a" } else { "zone-b" }.into();
+        }
+
+        block_on_all(async {
+            let mut client = StandaloneClient::create_client(connection_request)
+                .await
+                .unwrap();
+            for _ in 0..3 {
+                assert_eq!(client.send_command(&cmd).await, Ok(Value::Nil));
+            }
+        });
+
+        let reads: Vec<_> = mocks
+            .iter()
+            .map(|mock| mock.get_number_of_received_commands())
+            .collect();
+        assert_eq!(reads, vec![0, 0, 3, 0]);
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_read_from_replica_in_client_availability_zone_after_it_connects() {
+        fn create_mock_in_zone(
+            role: &str,
+            zone: &str,
+            listener: std::net::TcpListener,
+        ) -> ServerMock {
+            let responses = HashMap::from([
+                (
+                    "*1\r\n$4\r\nPING\r\n".to_string(),
+                    Value::BulkString(b"PONG".to_vec()),
+                ),
+                (
+                    "*2\r\n$4\r\nINFO\r\n$11\r\nREPLICATION\r\n".to_string(),
+                    Value::BulkString(format!("role:{role}\r\n").into_bytes()),
+                ),
+                (
+                    "*2\r\n$4\r\nINFO\r\n$6\r\nSERVER\r\n".to_string(),
+                    Value::BulkString(format!("availability_zone:{zone}\r\n").into_bytes()),
+                ),
+            ]);
+            ServerMock::new_with_listener(responses, listener)
+        }
+        let primary = create_mock_in_zone("master", "zone-b", get_listener_on_available_port());
+        let other_zone_replica =
+            create_mock_in_zone("slave", "zone-b", get_listener_on_available_port());
+        // The replica in the client's zone isn't reachable when the client is created, so its zone is unknown.
+        let same_zone_port = get_available_port();
+        let mut addresses = primary.get_addresses();
+        addresses.extend(other_zone_replica.get_addresses());
+        addresses.push(redis::ConnectionAddr::Tcp(
+            "localhost".to_string(),
+            same_zone_port,
+        ));
+        let mut connection_request =
+            create_connection_request(addresses.as_slice(), &Default::default());
+        connection_request.read_from = ReadFrom::AZAffinity.into();
+        connection_request.client_az = "zone-a".into();
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+
+        block_on_all(async {
+            let mut client = StandaloneClient::create_client(connection_request)
+                .await
+                .unwrap();
+            other_zone_replica.add_response(&cmd, "$-1\r\n".to_string());
+            assert_eq!(client.send_command(&cmd).await, Ok(Value::Nil));
+            assert_eq!(other_zone_replica.get_number_of_received_commands(), 1);
+
+            let same_zone_replica = create_mock_in_zone(
+                "slave",
+                "zone-a",
+                std::net::TcpListener::bind(("127.0.0.1", same_zone_port)).unwrap(),
+            );
+            // Once the client reconnects to the replica and reads its zone, the replica serves the reads.
+            while same_zone_replica.get_number_of_received_commands() == 0 {
+                other_zone_replica.add_response(&cmd, "$-1\r\n".to_string());
+                same_zone_replica.add_response(&cmd, "$-1\r\n".to_string());
+                assert_eq!(client.send_command(&cmd).await, Ok(Value::Nil));
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            let other_zone_reads = other_zone_replica.get_number_of_received_commands();
+            for _ in 0..3 {
+                same_zone_replica.add_response(&cmd, "$-1\r\n".to_string());
+                assert_eq!(client.send_command(&cmd).await, Ok(Value::Nil));
+            }
+            assert_eq!(same_zone_replica.get_number_of_received_commands(), 4);
+            assert_eq!(
+                other_zone_replica.get_number_of_received_commands(),
+                other_zone_reads
+            );
+        });
+        assert_eq!(primary.get_number_of_received_commands(), 0);
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_switch_primary_after_readonly_error() {
+        fn replication_response(role: &str) -> String {
+            let info = format!("role:{role}\r\n");
+            format!("${}\r\n{info}\r\n", info.len())
+        }
+
+        let mut constant_responses = std::collections::HashMap::new();
+        constant_responses.insert(
+            "*1\r\n$4\r\nPING\r\n".to_string(),
+            Value::BulkString(b"PONG".to_vec()),
+        );
+        let old_primary = ServerMock::new(constant_responses.clone());
+        let new_primary = ServerMock::new(constant_responses);
+        let info_cmd = redis::cmd("INFO").arg("REPLICATION").clone();
+        let mut set_cmd = redis::cmd("SET");
+        set_cmd.arg("foo").arg("bar");
+
+        old_primary.add_response(&info_cmd, replication_response("master"));
+        old_primary.add_response(
+            &set_cmd,
+            "-READONLY You can't write against a read only replica.\r\n".to_string(),
+        );
+        old_primary.add_response(&info_cmd, replication_response("slave"));
+        new_primary.add_response(&info_cmd, replication_response("slave"));
+        new_primary.add_response(&info_cmd, replication_response("master"));
+        new_primary.add_response(&set_cmd, "+OK\r\n".to_string());
+
+        let addresses: Vec<redis::ConnectionAddr> = [&old_primary, &new_primary]
+            .iter()
+            .flat_map(|mock| mock.get_addresses())
+            .collect();
+        let connection_request =
+            create_connection_request(addresses.as_slice(), &Default::default());
+
+        block_on_all(async {
+            let mut client = StandaloneClient::create_client(connection_request)
+                .await
+                .unwrap();
+            let result = client.send_command(&set_cmd).await.unwrap();
+            assert_eq!(result, Value::Okay);
+        });
+
+        assert_eq!(old_primary.get_number_of_received_commands(), 3);
+        assert_eq!(new_primary.get_number_of_received_commands(), 3);
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_concurrent_writes_wait_for_primary_switch() {
+        const CONCURRENT_WRITES: usize = 20;
+        let old_primary = RedisServer::new(ServerType::Tcp { tls: false });
+        let new_primary = RedisServer::new(ServerType::Tcp { tls: false });
+        let addresses = [old_primary.get_client_addr(), new_primary.get_client_addr()];
+
+        block_on_all(async {
+            for address in addresses.iter() {
+                wait_for_server_to_become_ready(address).await;
+            }
+            set_replica_of(&addresses[1], Some(&addresses[0])).await;
+            let connection_request = create_connection_request(&addresses, &Default::default());
+            let client = StandaloneClient::create_client(connection_request)
+                .await
+                .unwrap();
+
+            set_replica_of(&addresses[1], None).await;
+            set_replica_of(&addresses[0], Some(&addresses[1])).await;
+
+            // Every write fails on the demoted primary. The writes that fail while the nodes' roles are being checked wait
+            // for the check, and are then retried on the new primary too.
+            let results = futures::future::join_all((0..CONCURRENT_WRITES).map(|index| {
+                let mut client = client.clone();
+                async move {
+                    let mut set_cmd = redis::cmd("SET");
+                    set_cmd.arg(format!("key{index}")).arg("value");
+                    client.send_command(&set_cmd).await
+                }
+            }))
+            .await;
+            for result in results {
+                assert_eq!(result, Ok(Value::Okay));
+            }
+        });
+    }
+
+    /// Starts a primary and a replica, and connects to both with a client that prefers reading from the replica.
+    async fn create_client_with_replica(
+        primary: &RedisServer,
+        replica: &RedisServer,
+    ) -> StandaloneClient {
+        let addresses = [primary.get_client_addr(), replica.get_client_addr()];
+        for address in addresses.iter() {
+            wait_for_server_to_become_ready(address).await;
+        }
+        set_replica_of(&addresses[1], Some(&addresses[0])).await;
+        let mut connection_request = create_connection_request(&addresses, &Default::default());
+        connection_request.read_from = ReadFrom::PreferReplica.into();
+        StandaloneClient::create_client(connection_request)
+            .await
+            .unwrap()
+    }
+
+    /// Returns the role that the node reported in its response to ROLE.
+    fn role(value: Value) -> String {
+        let response = Vec::<Value>::from_redis_value(&value).unwrap();
+        String::from_redis_value(&response[0]).unwrap()
+    }
+
+    fn node_address(address: &redis::ConnectionAddr) -> String {
+        let redis::ConnectionAddr::Tcp(host, port) = address else {
+            panic!("Expected a TCP address, got {address}");
+        };
+        format!("{host}:{port}")
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_replica_optional_route_is_served_by_replica() {
+        let primary = RedisServer::new(ServerType::Tcp { tls: false });
+        let replica = RedisServer::new(ServerType::Tcp { tls: false });
+
+        block_on_all(async {
+            let mut client = create_client_with_replica(&primary, &replica).await;
+            let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                0,
+                SlotAddr::ReplicaOptional,
+            )));
+            for _ in 0..3 {
+                let value = client
+                    .route_command(&redis::cmd("ROLE"), routing.clone())
+                    .await
+                    .unwrap();
+                assert_eq!(role(value), "slave");
+            }
+
+            let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                0,
+                SlotAddr::Master,
+            )));
+            let value = client
+                .route_command(&redis::cmd("ROLE"), routing)
+                .await
+                .unwrap();
+            assert_eq!(role(value), "master");
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_random_route_is_served_by_every_node() {
+        const REQUESTS: usize = 50;
+        let primary = RedisServer::new(ServerType::Tcp { tls: false });
+        let replica = RedisServer::new(ServerType::Tcp { tls: false });
+
+        block_on_all(async {
+            let mut client = create_client_with_replica(&primary, &replica).await;
+            let mut roles = HashSet::new();
+            for _ in 0..REQUESTS {
+                let value = client
+                    .route_command(
+                        &redis::cmd("ROLE"),
+                        RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random),
+                    )
+                    .await
+                    .unwrap();
+                roles.insert(role(value));
+            }
+            // Both nodes serve random requests, so the chance that one of them served none of the requests is negligible.
+            assert_eq!(
+                roles,
+                HashSet::from(["master".to_string(), "slave".to_string()])
+            );
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_all_nodes_route_returns_response_of_each_node() {
+        let primary = RedisServer::new(ServerType::Tcp { tls: false });
+        let replica = RedisServer::new(ServerType::Tcp { tls: false });
+
+        block_on_all(async {
+            let mut client = create_client_with_replica(&primary, &replica).await;
+            let value = client
+                .route_command(
+                    &redis::cmd("ROLE"),
+                    RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllNodes, None)),
+                )
+                .await
+                .unwrap();
+            let roles = HashMap::<String, Value>::from_redis_value(&value)
+                .unwrap()
+                .into_iter()
+                .map(|(address, value)| (address, role(value)))
+                .collect::<HashMap<_, _>>();
+            assert_eq!(
+                roles,
+                HashMap::from([
+                    (
+                        node_address(&primary.get_client_addr()),
+                        "master".to_string()
+                    ),
+                    (
+                        node_address(&replica.get_client_addr()),
+                        "slave".to_string()
+                    ),
+                ])
+            );
+        });
+    }
+
     #[rstest]
     #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
     fn test_send_acl_request_to_all_nodes() {
@@ -326,4 +695,197 @@ mod standalone_client_tests {
             assert!(client_info.contains("db=4"));
         });
     }
+
+    fn create_tls_server(
+        tempdir: &tempfile::TempDir,
+        require_client_cert: bool,
+    ) -> (RedisServer, TlsFilePaths) {
+        let tls_paths = build_keys_and_certs_for_tls(tempdir);
+        let server = RedisServer::new_with_addr_tls_modules_and_spawner(
+            redis::ConnectionAddr::TcpTls {
+                host: "127.0.0.1".to_string(),
+                port: get_available_port(),
+                insecure: false,
+                tls_params: None,
+            },
+            Some(tls_paths.clone()),
+            &[],
+            |cmd| {
+                if require_client_cert {
+                    cmd.arg("--tls-auth-clients").arg("yes");
+                }
+                cmd.spawn()
+                    .unwrap_or_else(|err| panic!("Failed to run {cmd:?}: {err}"))
+            },
+        );
+        (server, tls_paths)
+    }
+
+    fn create_tls_connection_request(
+        server: &RedisServer,
+        tls_configuration: TlsConfiguration,
+    ) -> ConnectionRequest {
+        let mut connection_request =
+            create_connection_request(&[server.get_client_addr()], &Default::default());
+        connection_request.tls_mode = TlsMode::SecureTls.into();
+        connection_request.tls_configuration = protobuf::MessageField::some(tls_configuration);
+        connection_request
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_verify_server_with_custom_root_certificate() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let (server, tls_paths) = create_tls_server(&tempdir, false);
+        let connection_request = create_tls_connection_request(
+            &server,
+            TlsConfiguration {
+                root_certs: tls_paths.read_ca_cert().into(),
+                ..Default::default()
+            },
+        );
+
+        block_on_all(async move {
+            wait_for_server_to_become_ready(&server.get_client_addr()).await;
+            let mut client = StandaloneClient::create_client(connection_request)
+                .await
+                .unwrap();
+            let result = client.send_command(&redis::cmd("PING")).await.unwrap();
+            assert_eq!(result, Value::SimpleString("PONG".to_string()));
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_connect_with_client_certificate(#[values(false, true)] send_client_cert: bool) {
+        let tempdir = tempfile::tempdir().unwrap();
+        let (server, tls_paths) = create_tls_server(&tempdir, true);
+        let (client_cert, client_key) = if send_client_cert {
+            tls_paths.read_redis_cert_and_key()
+        } else {
+            Default::default()
+        };
+        let mut connection_request = create_tls_connection_request(
+            &server,
+            TlsConfiguration {
+                root_certs: tls_paths.read_ca_cert().into(),
+                client_cert: client_cert.into(),
+                client_key: client_key.into(),
+                ..Default::default()
+            },
+        );
+        connection_request.connection_retry_strategy =
+            protobuf::MessageField::some(ConnectionRetryStrategy {
+                number_of_retries: 1,
+                factor: 1,
+                exponent_base: 2,
+                ..Default::default()
+            });
+
+        block_on_all(async move {
+            wait_for_server_to_become_ready(&server.get_client_addr()).await;
+            let result = StandaloneClient::create_client(connection_request).await;
+            assert_eq!(result.is_ok(), send_client_cert, "{:?}", result.err());
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_verify_server_with_server_name(
+        #[values("127.0.0.1", "redis.example.com")] server_name: &str,
+    ) {
+        let tempdir = tempfile::tempdir().unwrap();
+        let (server, tls_paths) = create_tls_server(&tempdir, false);
+        // The server's certificate is only valid for 127.0.0.1, so connecting through "localhost" only succeeds if the
+        // certificate is verified against the server name.
+        let redis::ConnectionAddr::TcpTls { port, .. } = server.get_client_addr() else {
+            unreachable!()
+        };
+        let mut connection_request = create_connection_request(
+            &[redis::ConnectionAddr::TcpTls {
+                host: "localhost".to_string(),
+                port,
+                insecure: false,
+                tls_params: None,
+            }],
+            &Default::default(),
+        );
+        connection_request.tls_mode = TlsMode::SecureTls.into();
+        connection_request.tls_configuration = protobuf::MessageField::some(TlsConfiguration {
+            root_certs: tls_paths.read_ca_cert().into(),
+            server_name: server_name.into(),
+            ..Default::default()
+        });
+        connection_request.connection_retry_strategy =
+            protobuf::MessageField::some(ConnectionRetryStrategy {
+                number_of_retries: 1,
+                factor: 1,
+                exponent_base: 2,
+                ..Default::default()
+            });
+
+        block_on_all(async move {
+            wait_for_server_to_become_ready(&server.get_client_addr()).await;
+            let result = StandaloneClient::create_client(connection_request).await;
+            assert_eq!(
+                result.is_ok(),
+                server_name == "127.0.0.1",
+                "{:?}",
+                result.err()
+            );
+        });
+    }
+
+    #[rstest]
+    fn test_reject_client_certificate_without_key() {
+        let mut connection_request = create_connection_request(
+            &[redis::ConnectionAddr::Tcp("localhost".to_string(), 6379)],
+            &Default::default(),
+        );
+        connection_request.tls_mode = TlsMode::SecureTls.into();
+        connection_request.tls_configuration = protobuf::MessageField::some(TlsConfiguration {
+            client_cert: b"certificate".to_vec().into(),
+            ..Default::default()
+        });
+
+        block_on_all(async move {
+            let result = StandaloneClient::create_client(connection_request).await;
+            assert!(matches!(
+                result,
+                Err(StandaloneClientConnectionError::InvalidTlsConfiguration(_))
+            ));
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_connect_and_reconnect_through_unix_socket() {
+        let server = RedisServer::new(ServerType::Unix);
+        let connection_request =
+            create_connection_request(&[server.get_client_addr()], &Default::default());
+
+        block_on_all(async move {
+            wait_for_server_to_become_ready(&server.get_client_addr()).await;
+            let mut client = StandaloneClient::create_client(connection_request)
+                .await
+                .unwrap();
+            let mut set_command = redis::cmd("SET");
+            set_command.arg("unix_socket_key").arg("value");
+            assert_eq!(
+                client.send_command(&set_command).await.unwrap(),
+                Value::Okay
+            );
+
+            kill_connection(&mut client).await;
+
+            let mut get_command = redis::cmd("GET");
+            get_command.arg("unix_socket_key");
+            let get_result = repeat_try_create(|| async {
+                let mut client = client.clone();
+                client.send_command(&get_command).await.ok()
+            })
+            .await;
+            assert_eq!(get_result, Value::BulkString(b"value".to_vec()));
+        });
+    }
 }
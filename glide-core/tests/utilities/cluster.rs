@@ -254,7 +254,7 @@ pub async fn setup_test_basics_internal(mut configuration: TestConfiguration) ->
     configuration.request_timeout = configuration.request_timeout.or(Some(10000));
     let connection_request = create_connection_request(&addresses, &configuration);
 
-    let client = Client::new(connection_request).await.unwrap();
+    let client = Client::new(connection_request, None).await.unwrap();
     ClusterTestBasics { cluster, client }
 }
 
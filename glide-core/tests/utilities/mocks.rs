@@ -1,5 +1,5 @@
 use futures_intrusive::sync::ManualResetEvent;
-use redis::{Cmd, ConnectionAddr, Value};
+use redis::{Cmd, ConnectionAddr, Pipeline, Value};
 use std::collections::HashMap;
 use std::io;
 use std::net::TcpListener;
@@ -100,6 +100,8 @@ pub trait Mock {
 
     fn add_response(&self, request: &Cmd, response: String);
 
+    fn add_pipeline_response(&self, pipeline: &Pipeline, response: String);
+
     fn get_number_of_received_commands(&self) -> u16;
 }
 
@@ -175,6 +177,14 @@ impl Mock for ServerMock {
         });
     }
 
+    fn add_pipeline_response(&self, pipeline: &Pipeline, response: String) {
+        let expected_message = String::from_utf8(pipeline.get_packed_pipeline()).unwrap();
+        let _ = self.request_sender.send(MockedRequest {
+            expected_message,
+            response,
+        });
+    }
+
     fn get_number_of_received_commands(&self) -> u16 {
         self.received_commands.load(Ordering::Acquire)
     }
@@ -19,6 +19,7 @@ use tempfile::TempDir;
 
 pub mod cluster;
 pub mod mocks;
+pub mod sentinel;
 
 pub(crate) const SHORT_STANDALONE_TEST_TIMEOUT: Duration = Duration::from_millis(10_000);
 pub(crate) const LONG_STANDALONE_TEST_TIMEOUT: Duration = Duration::from_millis(20_000);
@@ -338,6 +339,21 @@ pub struct TlsFilePaths {
     ca_crt: PathBuf,
 }
 
+impl TlsFilePaths {
+    /// The PEM-encoded certificate of the CA that signed the server's certificate.
+    pub fn read_ca_cert(&self) -> Vec<u8> {
+        fs::read(&self.ca_crt).expect("failed to read CA cert")
+    }
+
+    /// A PEM-encoded certificate and key signed by the CA, which can also be used as a client certificate.
+    pub fn read_redis_cert_and_key(&self) -> (Vec<u8>, Vec<u8>) {
+        (
+            fs::read(&self.redis_crt).expect("failed to read redis cert"),
+            fs::read(&self.redis_key).expect("failed to read redis key"),
+        )
+    }
+}
+
 pub fn build_keys_and_certs_for_tls(tempdir: &TempDir) -> TlsFilePaths {
     // Based on shell script in redis's server tests
     // https://github.com/redis/redis/blob/8c291b97b95f2e011977b522acf77ead23e26f55/utils/gen-test-certs.sh
@@ -391,8 +407,12 @@ pub fn build_keys_and_certs_for_tls(tempdir: &TempDir) -> TlsFilePaths {
         .expect("failed to create CA cert");
 
     // Build x509v3 extensions file
-    fs::write(&ext_file, b"keyUsage = digitalSignature, keyEncipherment")
-        .expect("failed to create x509v3 extensions file");
+    // The IP address allows verifying the certificate of servers that are reached through 127.0.0.1.
+    fs::write(
+        &ext_file,
+        b"keyUsage = digitalSignature, keyEncipherment\nsubjectAltName = IP:127.0.0.1",
+    )
+    .expect("failed to create x509v3 extensions file");
 
     // Read redis key
     let mut key_cmd = process::Command::new("openssl")
@@ -505,7 +525,9 @@ pub fn get_address_info(address: &ConnectionAddr) -> NodeAddress {
             address_info.host = host.to_string().into();
             address_info.port = *port as u32;
         }
-        ConnectionAddr::Unix(_) => unreachable!("Unix connection not tested"),
+        ConnectionAddr::Unix(path) => {
+            address_info.unix_socket_path = path.to_string_lossy().to_string().into();
+        }
     }
     address_info
 }
@@ -605,6 +627,25 @@ pub async fn setup_acl(addr: &ConnectionAddr, connection_info: &RedisConnectionI
     connection.send_packed_command(&cmd).await.unwrap();
 }
 
+/// Makes the server a replica of `primary`, or a primary if `primary` is `None`.
+pub async fn set_replica_of(addr: &ConnectionAddr, primary: Option<&ConnectionAddr>) {
+    let client = redis::Client::open(redis::ConnectionInfo {
+        addr: addr.clone(),
+        redis: RedisConnectionInfo::default(),
+    })
+    .unwrap();
+    let mut connection =
+        repeat_try_create(|| async { client.get_multiplexed_async_connection().await.ok() }).await;
+
+    let mut cmd = redis::cmd("REPLICAOF");
+    match primary {
+        Some(ConnectionAddr::Tcp(host, port)) => cmd.arg(host).arg(port),
+        Some(primary) => panic!("Replication is only set up over TCP, not {primary}"),
+        None => cmd.arg("NO").arg("ONE"),
+    };
+    connection.send_packed_command(&cmd).await.unwrap();
+}
+
 #[derive(Eq, PartialEq, Default)]
 pub enum ClusterMode {
     #[default]
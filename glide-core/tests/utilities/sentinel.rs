@@ -0,0 +1,94 @@
+use super::{get_address_info, get_available_port, RedisServer, ServerType};
+use glide_core::connection_request::{self, SentinelConfiguration};
+use redis::ConnectionAddr;
+use std::{fs, process};
+
+pub const MASTER_NAME: &str = "mymaster";
+
+/// A primary, a replica and a single sentinel that monitors them, all running locally.
+pub struct RedisSentinelSetup {
+    pub primary: RedisServer,
+    pub replica: RedisServer,
+    sentinel: process::Child,
+    sentinel_address: ConnectionAddr,
+    _tempdir: tempfile::TempDir,
+}
+
+impl RedisSentinelSetup {
+    pub fn new() -> Self {
+        let primary = RedisServer::new(ServerType::Tcp { tls: false });
+        let ConnectionAddr::Tcp(primary_host, primary_port) = primary.get_client_addr() else {
+            unreachable!("The primary uses TCP without TLS");
+        };
+        let replica_port = get_available_port();
+        let replica = RedisServer::new_with_addr_tls_modules_and_spawner(
+            ConnectionAddr::Tcp(primary_host.clone(), replica_port),
+            None,
+            &[],
+            |cmd| {
+                cmd.arg("--replicaof")
+                    .arg(&primary_host)
+                    .arg(primary_port.to_string())
+                    .spawn()
+                    .unwrap_or_else(|err| panic!("Failed to run {cmd:?}: {err}"))
+            },
+        );
+
+        let tempdir = tempfile::Builder::new()
+            .prefix("sentinel")
+            .tempdir()
+            .expect("failed to create tempdir");
+        let sentinel_port = get_available_port();
+        let config_path = tempdir.path().join("sentinel.conf");
+        // A quorum of 1 and short timeouts let the single sentinel fail over quickly.
+        fs::write(
+            &config_path,
+            format!(
+                "port {sentinel_port}\n\
+                 bind {primary_host}\n\
+                 sentinel monitor {MASTER_NAME} {primary_host} {primary_port} 1\n\
+                 sentinel down-after-milliseconds {MASTER_NAME} 1000\n\
+                 sentinel failover-timeout {MASTER_NAME} 5000\n"
+            ),
+        )
+        .expect("failed to write the sentinel configuration");
+        let sentinel = process::Command::new("redis-server")
+            .arg(&config_path)
+            .arg("--sentinel")
+            .stdout(process::Stdio::null())
+            .stderr(process::Stdio::null())
+            .spawn()
+            .expect("Failed to run redis-server in sentinel mode");
+
+        Self {
+            primary,
+            replica,
+            sentinel,
+            sentinel_address: ConnectionAddr::Tcp(primary_host, sentinel_port),
+            _tempdir: tempdir,
+        }
+    }
+
+    pub fn sentinel_address(&self) -> ConnectionAddr {
+        self.sentinel_address.clone()
+    }
+
+    /// Returns a connection request that discovers the nodes through the sentinel.
+    pub fn connection_request(&self) -> connection_request::ConnectionRequest {
+        let mut connection_request = connection_request::ConnectionRequest::new();
+        connection_request.sentinel_configuration =
+            protobuf::MessageField::some(SentinelConfiguration {
+                sentinel_addresses: vec![get_address_info(&self.sentinel_address)],
+                master_name: MASTER_NAME.into(),
+                ..Default::default()
+            });
+        connection_request
+    }
+}
+
+impl Drop for RedisSentinelSetup {
+    fn drop(&mut self) {
+        let _ = self.sentinel.kill();
+        let _ = self.sentinel.wait();
+    }
+}
@@ -2,6 +2,7 @@ package glide.api;
 
 import glide.managers.CommandManager;
 import glide.managers.ConnectionManager;
+import java.util.concurrent.CompletableFuture;
 import java.util.concurrent.ExecutionException;
 import lombok.AllArgsConstructor;
 
@@ -12,6 +13,20 @@ public abstract class BaseClient implements AutoCloseable {
     protected ConnectionManager connectionManager;
     protected CommandManager commandManager;
 
+    /**
+     * Executes a single command, without checking inputs. Every part of the command, including
+     * subcommands, should be added as a separate value in <code>args</code>.
+     *
+     * @param args Arguments for the custom command, for example <code>
+     *     customCommand("GET", "key")</code>
+     * @return A result from Redis. Bulk strings are returned as <code>byte[]</code> if the client
+     *     was configured with {@link
+     *     glide.api.models.configuration.BaseClientConfiguration#isReturnBytes()}
+     */
+    public CompletableFuture<Object> customCommand(String... args) {
+        return commandManager.customCommand(args);
+    }
+
     /**
      * Closes this resource, relinquishing any underlying resources. This method is invoked
      * automatically on objects managed by the try-with-resources statement.
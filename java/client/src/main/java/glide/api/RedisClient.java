@@ -24,7 +24,7 @@ public class RedisClient extends BaseClient {
     public static CompletableFuture<RedisClient> CreateClient(RedisClientConfiguration config) {
         ChannelHandler channelHandler = buildChannelHandler();
         ConnectionManager connectionManager = buildConnectionManager(channelHandler);
-        CommandManager commandManager = buildCommandManager(channelHandler);
+        CommandManager commandManager = buildCommandManager(channelHandler, config.isReturnBytes());
         // TODO: Support exception throwing, including interrupted exceptions
         return connectionManager
                 .connectToRedis(config)
@@ -40,8 +40,9 @@ public class RedisClient extends BaseClient {
         return new ConnectionManager(channelHandler);
     }
 
-    protected static CommandManager buildCommandManager(ChannelHandler channelHandler) {
-        return new CommandManager(channelHandler);
+    protected static CommandManager buildCommandManager(
+            ChannelHandler channelHandler, boolean returnBytes) {
+        return new CommandManager(channelHandler, returnBytes);
     }
 
     protected RedisClient(ConnectionManager connectionManager, CommandManager commandManager) {
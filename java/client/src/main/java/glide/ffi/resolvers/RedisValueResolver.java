@@ -16,4 +16,14 @@ public class RedisValueResolver {
      * @return A RESP3 value
      */
     public static native Object valueFromPointer(long pointer);
+
+    /**
+     * Resolve a value received from Redis using given C-style pointer. Bulk strings are returned as
+     * <code>byte[]</code> instead of being decoded as UTF-8 strings, including inside arrays, maps
+     * and sets.
+     *
+     * @param pointer A memory pointer from {@link Response}
+     * @return A RESP3 value
+     */
+    public static native Object valueFromPointerBinary(long pointer);
 }
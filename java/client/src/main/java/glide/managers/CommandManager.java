@@ -26,6 +26,21 @@ public class CommandManager {
     /** UDS connection representation. */
     private final ChannelHandler channel;
 
+    /** Whether bulk strings are returned as <code>byte[]</code> by {@link #customCommand}. */
+    private final boolean returnBytes;
+
+    /**
+     * Async (non-blocking) custom command. The response is converted to an <code>Object</code>,
+     * where arrays are returned as <code>Object[]</code>, maps as <code>HashMap</code> and sets as
+     * <code>HashSet</code>.
+     *
+     * @param args The command name and its arguments
+     */
+    public CompletableFuture<Object> customCommand(String[] args) {
+        var request = RequestBuilder.prepareRedisRequest(RequestType.CustomCommand, List.of(args));
+        return channel.write(request, true).thenApplyAsync(this::extractObjectFromGlideRsResponse);
+    }
+
     /**
      * Async (non-blocking) get.<br>
      * See <a href="https://redis.io/commands/get/">REDIS docs for GET</a>.
@@ -67,6 +82,17 @@ public class CommandManager {
      * @return A String from the Redis response, or Ok. Otherwise, returns null
      */
     private String extractValueFromGlideRsResponse(Response response) {
+        Object value = extractObjectFromGlideRsResponse(response);
+        return value == null ? null : value.toString();
+    }
+
+    /**
+     * Check response and extract data from it.
+     *
+     * @param response A response received from rust core lib
+     * @return The converted value of the Redis response, or Ok. Otherwise, returns null
+     */
+    private Object extractObjectFromGlideRsResponse(Response response) {
         if (response.hasRequestError()) {
             RequestError error = response.getRequestError();
             String msg = error.getMessage();
@@ -100,7 +126,9 @@ public class CommandManager {
         }
         if (response.hasRespPointer()) {
             // Return the shared value - which may be a null value
-            return RedisValueResolver.valueFromPointer(response.getRespPointer()).toString();
+            return returnBytes
+                    ? RedisValueResolver.valueFromPointerBinary(response.getRespPointer())
+                    : RedisValueResolver.valueFromPointer(response.getRespPointer());
         }
         // if no response payload is provided, assume null
         return null;
@@ -94,6 +94,10 @@ public class ConnectionManager {
             connectionRequestBuilder.setRequestTimeout(configuration.getRequestTimeout());
         }
 
+        if (configuration.getConnectionUri() != null) {
+            connectionRequestBuilder.setConnectionUri(configuration.getConnectionUri());
+        }
+
         return connectionRequestBuilder;
     }
 
@@ -40,7 +40,9 @@ public class RedisClientCreateTest {
 
         mockedClient.when(RedisClient::buildChannelHandler).thenReturn(channelHandler);
         mockedClient.when(() -> buildConnectionManager(channelHandler)).thenReturn(connectionManager);
-        mockedClient.when(() -> buildCommandManager(channelHandler)).thenReturn(commandManager);
+        mockedClient
+                .when(() -> buildCommandManager(channelHandler, false))
+                .thenReturn(commandManager);
     }
 
     @AfterEach
@@ -7,7 +7,13 @@ use log::error;
 use redis::Value;
 use std::sync::mpsc;
 
-fn redis_value_to_java(mut env: JNIEnv, val: Value) -> JObject {
+/// Converts a value to its Java equivalent. If `binary` is set, bulk strings are returned as `byte[]` instead of being decoded as UTF-8,
+/// including inside arrays, maps and sets.
+fn redis_value_to_java<'local>(
+    env: &mut JNIEnv<'local>,
+    val: Value,
+    binary: bool,
+) -> JObject<'local> {
     match val {
         Value::Nil => JObject::null(),
         Value::SimpleString(str) => JObject::from(env.new_string(str).unwrap()),
@@ -16,6 +22,9 @@ fn redis_value_to_java(mut env: JNIEnv, val: Value) -> JObject {
         Value::Int(num) => env
             .new_object("java/lang/Integer", "(I)V", &[num.into()])
             .unwrap(),
+        Value::BulkString(data) if binary => {
+            JObject::from(env.byte_array_from_slice(&data).unwrap())
+        }
         Value::BulkString(data) => match std::str::from_utf8(data.as_ref()) {
             Ok(val) => JObject::from(env.new_string(val).unwrap()),
             Err(_err) => {
@@ -23,16 +32,63 @@ fn redis_value_to_java(mut env: JNIEnv, val: Value) -> JObject {
                 JObject::null()
             }
         },
-        Value::Array(_array) => {
-            let _ = env.throw("Not implemented");
-            JObject::null()
+        Value::Array(array) => {
+            let java_array = env
+                .new_object_array(array.len() as i32, "java/lang/Object", JObject::null())
+                .unwrap();
+            for (index, item) in array.into_iter().enumerate() {
+                let java_item = redis_value_to_java(env, item, binary);
+                if conversion_failed(env, &java_item) {
+                    return JObject::null();
+                }
+                env.set_object_array_element(&java_array, index as i32, &java_item)
+                    .unwrap();
+                env.delete_local_ref(java_item).unwrap();
+            }
+            JObject::from(java_array)
+        }
+        Value::Map(map) => {
+            let java_map = env.new_object("java/util/HashMap", "()V", &[]).unwrap();
+            for (key, value) in map {
+                let java_key = redis_value_to_java(env, key, binary);
+                let java_value = redis_value_to_java(env, value, binary);
+                if conversion_failed(env, &java_key) || conversion_failed(env, &java_value) {
+                    return JObject::null();
+                }
+                env.call_method(
+                    &java_map,
+                    "put",
+                    "(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;",
+                    &[(&java_key).into(), (&java_value).into()],
+                )
+                .unwrap();
+                env.delete_local_ref(java_key).unwrap();
+                env.delete_local_ref(java_value).unwrap();
+            }
+            java_map
         }
-        Value::Map(_map) => todo!(),
         Value::Double(_float) => todo!(),
         Value::Boolean(_bool) => todo!(),
         Value::VerbatimString { format: _, text: _ } => todo!(),
         Value::BigNumber(_num) => todo!(),
-        Value::Set(_array) => todo!(),
+        Value::Set(set) => {
+            let java_set = env.new_object("java/util/HashSet", "()V", &[]).unwrap();
+            for item in set {
+                let java_item = redis_value_to_java(env, item, binary);
+                if conversion_failed(env, &java_item) {
+                    return JObject::null();
+                }
+                env.call_method(
+                    &java_set,
+                    "add",
+                    "(Ljava/lang/Object;)Z",
+                    &[(&java_item).into()],
+                )
+                .unwrap();
+                env.delete_local_ref(java_item).unwrap();
+            }
+            java_set
+        }
         Value::Attribute {
             data: _,
             attributes: _,
@@ -41,14 +97,31 @@ fn redis_value_to_java(mut env: JNIEnv, val: Value) -> JObject {
     }
 }
 
+/// Returns whether converting a value threw a Java exception, in which case the collection that holds it isn't converted either.
+fn conversion_failed(env: &mut JNIEnv, java_value: &JObject) -> bool {
+    java_value.is_null() && env.exception_check().unwrap_or(true)
+}
+
 #[no_mangle]
 pub extern "system" fn Java_glide_ffi_resolvers_RedisValueResolver_valueFromPointer<'local>(
-    env: JNIEnv<'local>,
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    pointer: jlong,
+) -> JObject<'local> {
+    let value = unsafe { Box::from_raw(pointer as *mut Value) };
+    redis_value_to_java(&mut env, *value, false)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_glide_ffi_resolvers_RedisValueResolver_valueFromPointerBinary<
+    'local,
+>(
+    mut env: JNIEnv<'local>,
     _class: JClass<'local>,
     pointer: jlong,
 ) -> JObject<'local> {
     let value = unsafe { Box::from_raw(pointer as *mut Value) };
-    redis_value_to_java(env, *value)
+    redis_value_to_java(&mut env, *value, true)
 }
 
 #[no_mangle]
@@ -2,7 +2,7 @@ use byteorder::{LittleEndian, WriteBytesExt};
 use glide_core::start_socket_listener;
 use glide_core::MAX_REQUEST_ARGS_LENGTH;
 #[cfg(feature = "testing_utilities")]
-use napi::bindgen_prelude::BigInt;
+use napi::bindgen_prelude::{BigInt, Buffer};
 use napi::{Env, Error, JsObject, JsUnknown, Result, Status};
 use napi_derive::napi;
 use num_traits::sign::Signed;
@@ -146,7 +146,8 @@ pub fn init(level: Option<Level>, file_name: Option<&str>) -> Level {
     logger_level.into()
 }
 
-fn redis_value_to_js(val: Value, js_env: Env) -> Result<JsUnknown> {
+/// Converts a value to its JS equivalent. If `return_buffers` is set, bulk strings are returned as `Buffer`s instead of being decoded as UTF-8.
+fn redis_value_to_js(val: Value, js_env: Env, return_buffers: bool) -> Result<JsUnknown> {
     match val {
         Value::Nil => js_env.get_null().map(|val| val.into_unknown()),
         Value::SimpleString(str) => js_env
@@ -154,6 +155,9 @@ fn redis_value_to_js(val: Value, js_env: Env) -> Result<JsUnknown> {
             .map(|val| val.into_unknown()),
         Value::Okay => js_env.create_string("OK").map(|val| val.into_unknown()),
         Value::Int(num) => js_env.create_int64(num).map(|val| val.into_unknown()),
+        Value::BulkString(data) if return_buffers => js_env
+            .create_buffer_with_data(data)
+            .map(|val| val.into_raw().into_unknown()),
         Value::BulkString(data) => {
             let str = to_js_result(std::str::from_utf8(data.as_ref()))?;
             js_env.create_string(str).map(|val| val.into_unknown())
@@ -161,7 +165,10 @@ fn redis_value_to_js(val: Value, js_env: Env) -> Result<JsUnknown> {
         Value::Array(array) => {
             let mut js_array_view = js_env.create_array_with_length(array.len())?;
             for (index, item) in array.into_iter().enumerate() {
-                js_array_view.set_element(index as u32, redis_value_to_js(item, js_env)?)?;
+                js_array_view.set_element(
+                    index as u32,
+                    redis_value_to_js(item, js_env, return_buffers)?,
+                )?;
             }
             Ok(js_array_view.into_unknown())
         }
@@ -169,7 +176,7 @@ fn redis_value_to_js(val: Value, js_env: Env) -> Result<JsUnknown> {
             let mut obj = js_env.create_object()?;
             for (key, value) in map {
                 let field_name = String::from_redis_value(&key).map_err(to_js_error)?;
-                let value = redis_value_to_js(value, js_env)?;
+                let value = redis_value_to_js(value, js_env, return_buffers)?;
                 obj.set_named_property(&field_name, value)?;
             }
             Ok(obj.into_unknown())
@@ -196,26 +203,48 @@ fn redis_value_to_js(val: Value, js_env: Env) -> Result<JsUnknown> {
             // TODO - return a set object instead of an array object
             let mut js_array_view = js_env.create_array_with_length(array.len())?;
             for (index, item) in array.into_iter().enumerate() {
-                js_array_view.set_element(index as u32, redis_value_to_js(item, js_env)?)?;
+                js_array_view.set_element(
+                    index as u32,
+                    redis_value_to_js(item, js_env, return_buffers)?,
+                )?;
             }
             Ok(js_array_view.into_unknown())
         }
         Value::Attribute { data, attributes } => {
             let mut obj = js_env.create_object()?;
-            let value = redis_value_to_js(*data, js_env)?;
+            let value = redis_value_to_js(*data, js_env, return_buffers)?;
             obj.set_named_property("value", value)?;
 
-            let value = redis_value_to_js(Value::Map(attributes), js_env)?;
+            let value = redis_value_to_js(Value::Map(attributes), js_env, return_buffers)?;
             obj.set_named_property("attributes", value)?;
 
             Ok(obj.into_unknown())
         }
-        Value::Push { kind: _, data: _ } => todo!(),
+        Value::Push { kind, data } => {
+            let mut obj = js_env.create_object()?;
+            obj.set_named_property("kind", js_env.create_string_from_std(kind.to_string())?)?;
+            let mut js_array_view = js_env.create_array_with_length(data.len())?;
+            for (index, item) in data.into_iter().enumerate() {
+                js_array_view.set_element(
+                    index as u32,
+                    redis_value_to_js(item, js_env, return_buffers)?,
+                )?;
+            }
+            obj.set_named_property("values", js_array_view)?;
+            Ok(obj.into_unknown())
+        }
     }
 }
 
-#[napi(ts_return_type = "null | string | number | {} | Boolean | BigInt | Set<any> | any[]")]
-pub fn value_from_split_pointer(js_env: Env, high_bits: u32, low_bits: u32) -> Result<JsUnknown> {
+#[napi(
+    ts_return_type = "null | string | Buffer | number | {} | Boolean | BigInt | Set<any> | any[]"
+)]
+pub fn value_from_split_pointer(
+    js_env: Env,
+    high_bits: u32,
+    low_bits: u32,
+    return_buffers: Option<bool>,
+) -> Result<JsUnknown> {
     let mut bytes = [0_u8; 8];
     (&mut bytes[..4])
         .write_u32::<LittleEndian>(low_bits)
@@ -225,7 +254,7 @@ pub fn value_from_split_pointer(js_env: Env, high_bits: u32, low_bits: u32) -> R
         .unwrap();
     let pointer = u64::from_le_bytes(bytes);
     let value = unsafe { Box::from_raw(pointer as *mut Value) };
-    redis_value_to_js(*value, js_env)
+    redis_value_to_js(*value, js_env, return_buffers.unwrap_or_default())
 }
 
 // Pointers are split because JS cannot represent a full usize using its `number` object.
@@ -247,6 +276,15 @@ pub fn create_leaked_string(message: String) -> [u32; 2] {
     split_pointer(pointer)
 }
 
+#[napi(ts_return_type = "[number, number]")]
+/// This function is for tests that require a value allocated on the heap.
+/// Should NOT be used in production.
+#[cfg(feature = "testing_utilities")]
+pub fn create_leaked_bulk_string(data: Buffer) -> [u32; 2] {
+    let pointer = Box::leak(Box::new(Value::BulkString(data.to_vec()))) as *mut Value;
+    split_pointer(pointer)
+}
+
 #[napi(ts_return_type = "[number, number]")]
 pub fn create_leaked_string_vec(message: Vec<String>) -> [u32; 2] {
     let pointer = Box::leak(Box::new(message)) as *mut Vec<String>;
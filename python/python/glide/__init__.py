@@ -10,16 +10,32 @@ from glide.config import (
     ClusterClientConfiguration,
     NodeAddress,
     ReadFrom,
+    AuthToken,
     RedisClientConfiguration,
     RedisCredentials,
+    TokenCredentials,
 )
 from glide.constants import OK
 from glide.exceptions import (
+    AskError,
+    BusyError,
     ClosingError,
+    ClusterDownError,
+    CrossSlotError,
     ExecAbortError,
+    LoadingError,
+    MovedError,
+    NoAuthError,
+    NoPermissionError,
+    NoScriptError,
+    OutOfMemoryError,
+    ReadOnlyError,
     RedisError,
     RequestError,
     TimeoutError,
+    TryAgainError,
+    WatchAbortedError,
+    WrongTypeError,
 )
 from glide.logger import Level as LogLevel
 from glide.logger import Logger
@@ -48,6 +64,8 @@ __all__ = [
     "RedisClient",
     "RedisClusterClient",
     "RedisCredentials",
+    "TokenCredentials",
+    "AuthToken",
     "NodeAddress",
     "Transaction",
     "ClusterTransaction",
@@ -59,9 +77,23 @@ __all__ = [
     "SlotKeyRoute",
     "SlotIdRoute",
     # Exceptions
+    "AskError",
+    "BusyError",
     "ClosingError",
+    "ClusterDownError",
+    "CrossSlotError",
     "ExecAbortError",
+    "LoadingError",
+    "MovedError",
+    "NoAuthError",
+    "NoPermissionError",
+    "NoScriptError",
+    "OutOfMemoryError",
+    "ReadOnlyError",
     "RedisError",
     "RequestError",
     "TimeoutError",
+    "TryAgainError",
+    "WatchAbortedError",
+    "WrongTypeError",
 ]
@@ -12,6 +12,7 @@ TOK = Literal["OK"]
 TResult = Union[
     TOK,
     str,
+    bytes,
     List[str],
     List[List[str]],
     int,
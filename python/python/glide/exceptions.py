@@ -24,9 +24,22 @@ class ClosingError(RedisError):
 class RequestError(RedisError):
     """
     Errors that were reported during a request.
+
+    Attributes:
+        code (Optional[str]): The error code that the server replied with, such as "WRONGTYPE", if the error came from the server.
+        address (Optional[str]): The "host:port" address of the node that the error came from, if it's known.
+            For `MovedError` and `AskError`, this is the node that the request was redirected to.
     """
 
-    pass
+    def __init__(
+        self,
+        message: Optional[str] = None,
+        code: Optional[str] = None,
+        address: Optional[str] = None,
+    ):
+        super().__init__(message)
+        self.code = code
+        self.address = address
 
 
 class TimeoutError(RequestError):
@@ -52,3 +65,115 @@ class ConnectionError(RequestError):
     """
 
     pass
+
+
+class WatchAbortedError(RequestError):
+    """
+    Errors that are thrown when a transaction isn't executed, because a watched key was modified.
+    """
+
+    pass
+
+
+class WrongTypeError(RequestError):
+    """
+    Errors that are thrown when a key holds a value of another type (WRONGTYPE).
+    """
+
+    pass
+
+
+class NoAuthError(RequestError):
+    """
+    Errors that are thrown when the connection isn't authenticated, or its credentials were rejected (NOAUTH, WRONGPASS).
+    """
+
+    pass
+
+
+class NoPermissionError(RequestError):
+    """
+    Errors that are thrown when the user isn't allowed to run the command or access the key (NOPERM).
+    """
+
+    pass
+
+
+class OutOfMemoryError(RequestError):
+    """
+    Errors that are thrown when the server reached its memory limit (OOM).
+    """
+
+    pass
+
+
+class ReadOnlyError(RequestError):
+    """
+    Errors that are thrown when a write is sent to a replica (READONLY).
+    """
+
+    pass
+
+
+class CrossSlotError(RequestError):
+    """
+    Errors that are thrown when the command's keys don't belong to the same slot (CROSSSLOT).
+    """
+
+    pass
+
+
+class BusyError(RequestError):
+    """
+    Errors that are thrown when the server is busy running a script or function (BUSY).
+    """
+
+    pass
+
+
+class LoadingError(RequestError):
+    """
+    Errors that are thrown when the server is loading its dataset (LOADING).
+    """
+
+    pass
+
+
+class NoScriptError(RequestError):
+    """
+    Errors that are thrown when the script isn't loaded on the server (NOSCRIPT).
+    """
+
+    pass
+
+
+class MovedError(RequestError):
+    """
+    Errors that are thrown when the slot moved to another node, and the client ran out of redirections (MOVED).
+    """
+
+    pass
+
+
+class AskError(RequestError):
+    """
+    Errors that are thrown when the slot is being migrated, and the client ran out of redirections (ASK).
+    """
+
+    pass
+
+
+class TryAgainError(RequestError):
+    """
+    Errors that are thrown when the keys are being migrated, and the command can be retried later (TRYAGAIN).
+    """
+
+    pass
+
+
+class ClusterDownError(RequestError):
+    """
+    Errors that are thrown when the cluster can't serve requests (CLUSTERDOWN).
+    """
+
+    pass
@@ -16,7 +16,7 @@ class Level(Enum):
     def is_lower(self, level: Level) -> bool: ...
 
 def start_socket_listener_external(init_callback: Callable) -> None: ...
-def value_from_pointer(pointer: int) -> TResult: ...
+def value_from_pointer(pointer: int, return_bytes: bool = False) -> TResult: ...
 def create_leaked_value(message: str) -> int: ...
 def py_init(level: Optional[Level], file_name: Optional[str]) -> Level: ...
 def py_log(log_level: Level, log_identifier: str, message: str) -> None: ...
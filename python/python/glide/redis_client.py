@@ -6,14 +6,28 @@ import async_timeout
 from glide.async_commands.cluster_commands import ClusterCommands
 from glide.async_commands.core import CoreCommands
 from glide.async_commands.standalone_commands import StandaloneCommands
-from glide.config import BaseClientConfiguration
+from glide.config import BaseClientConfiguration, TokenCredentials
 from glide.constants import DEFAULT_READ_BYTES_SIZE, OK, TRequest, TResult
 from glide.exceptions import (
+    AskError,
+    BusyError,
     ClosingError,
+    ClusterDownError,
     ConnectionError,
+    CrossSlotError,
     ExecAbortError,
+    LoadingError,
+    MovedError,
+    NoAuthError,
+    NoPermissionError,
+    NoScriptError,
+    OutOfMemoryError,
+    ReadOnlyError,
     RequestError,
     TimeoutError,
+    TryAgainError,
+    WatchAbortedError,
+    WrongTypeError,
 )
 from glide.logger import Level as LogLevel
 from glide.logger import Logger as ClientLogger
@@ -31,18 +45,33 @@ from .glide import (
 )
 
 
+REQUEST_ERROR_CLASSES: dict[RequestErrorType.ValueType, type[RequestError]] = {
+    RequestErrorType.Disconnect: ConnectionError,
+    RequestErrorType.ExecAbort: ExecAbortError,
+    RequestErrorType.Timeout: TimeoutError,
+    RequestErrorType.WatchAborted: WatchAbortedError,
+    RequestErrorType.WrongType: WrongTypeError,
+    RequestErrorType.NoAuth: NoAuthError,
+    RequestErrorType.NoPermission: NoPermissionError,
+    RequestErrorType.OutOfMemory: OutOfMemoryError,
+    RequestErrorType.ReadOnly: ReadOnlyError,
+    RequestErrorType.CrossSlot: CrossSlotError,
+    RequestErrorType.Busy: BusyError,
+    RequestErrorType.Loading: LoadingError,
+    RequestErrorType.NoScript: NoScriptError,
+    RequestErrorType.Moved: MovedError,
+    RequestErrorType.Ask: AskError,
+    RequestErrorType.TryAgain: TryAgainError,
+    RequestErrorType.ClusterDown: ClusterDownError,
+}
+
+
 def get_request_error_class(
     error_type: Optional[RequestErrorType.ValueType],
 ) -> type[RequestError]:
-    if error_type == RequestErrorType.Disconnect:
-        return ConnectionError
-    if error_type == RequestErrorType.ExecAbort:
-        return ExecAbortError
-    if error_type == RequestErrorType.Timeout:
-        return TimeoutError
-    if error_type == RequestErrorType.Unspecified:
+    if error_type is None:
         return RequestError
-    return RequestError
+    return REQUEST_ERROR_CLASSES.get(error_type, RequestError)
 
 
 class BaseRedisClient(CoreCommands):
@@ -152,6 +181,13 @@ class BaseRedisClient(CoreCommands):
 
     async def _set_connection_configurations(self) -> None:
         conn_request = self._get_protobuf_conn_request()
+        credentials = self.config.credentials
+        if isinstance(credentials, TokenCredentials):
+            # The first token is passed with the configuration, since the client can't request tokens before it's created.
+            token = await credentials.token_provider()
+            conn_request.authentication_info.password = token.token
+            if token.lifetime:
+                conn_request.authentication_info.token_lifetime = token.lifetime
         response_future: asyncio.Future = self._get_future(0)
         await self._write_or_buffer_request(conn_request)
         await response_future
@@ -235,6 +271,26 @@ class BaseRedisClient(CoreCommands):
             # The list is empty
             return len(self._available_futures)
 
+    async def _send_auth_token(self, request_id: int) -> None:
+        request = RedisRequest()
+        request.auth_token.request_id = request_id
+        credentials = cast(TokenCredentials, self.config.credentials)
+        try:
+            token = await credentials.token_provider()
+            request.auth_token.token = token.token
+            if token.lifetime:
+                request.auth_token.lifetime = token.lifetime
+        except Exception as e:
+            # An empty token tells the client that the provider failed.
+            ClientLogger.log(LogLevel.WARN, "auth token", f"failed to get a token: {e}")
+        await self._write_or_buffer_request(request)
+
+    def _handle_push_notification(self, pointer: int) -> None:
+        value = value_from_pointer(pointer, self.config.return_bytes)
+        callback = self.config.push_notification_callback
+        if callback:
+            callback(value)
+
     async def _reader_loop(self) -> None:
         # Socket reader loop
         remaining_read_bytes = bytearray()
@@ -257,6 +313,15 @@ class BaseRedisClient(CoreCommands):
                     remaining_read_bytes = read_bytes[offset:]
                     break
                 response = cast(Response, response)
+                if response.HasField("push_pointer"):
+                    # Push notifications don't answer a request, and their value is freed when it's read.
+                    self._handle_push_notification(response.push_pointer)
+                    continue
+                if response.HasField("auth_token_request"):
+                    asyncio.create_task(
+                        self._send_auth_token(response.auth_token_request.request_id)
+                    )
+                    continue
                 res_future = self._available_futures.pop(response.callback_idx, None)
                 if not res_future or response.HasField("closing_error"):
                     err_msg = (
@@ -273,10 +338,18 @@ class BaseRedisClient(CoreCommands):
                             response.request_error.type
                         )
                         res_future.set_exception(
-                            error_type(response.request_error.message)
+                            error_type(
+                                response.request_error.message,
+                                response.request_error.code or None,
+                                response.request_error.address or None,
+                            )
                         )
                     elif response.HasField("resp_pointer"):
-                        res_future.set_result(value_from_pointer(response.resp_pointer))
+                        res_future.set_result(
+                            value_from_pointer(
+                                response.resp_pointer, self.config.return_bytes
+                            )
+                        )
                     elif response.HasField("constant_response"):
                         res_future.set_result(OK)
                     else:
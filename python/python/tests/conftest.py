@@ -109,6 +109,7 @@ async def create_client(
     addresses: Optional[List[NodeAddress]] = None,
     client_name: Optional[str] = None,
     protocol: ProtocolVersion = ProtocolVersion.RESP3,
+    return_bytes: bool = False,
 ) -> Union[RedisClient, RedisClusterClient]:
     # Create async socket client
     use_tls = request.config.getoption("--tls")
@@ -122,6 +123,7 @@ async def create_client(
             credentials=credentials,
             client_name=client_name,
             protocol=protocol,
+            return_bytes=return_bytes,
         )
         return await RedisClusterClient.create(cluster_config)
     else:
@@ -135,5 +137,6 @@ async def create_client(
             database_id=database_id,
             client_name=client_name,
             protocol=protocol,
+            return_bytes=return_bytes,
         )
         return await RedisClient.create(config)
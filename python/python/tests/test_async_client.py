@@ -16,7 +16,7 @@ from glide.async_commands.core import (
     ExpiryType,
     InfoSection,
 )
-from glide.config import ProtocolVersion, RedisCredentials
+from glide.config import ProtocolVersion, RedisClientConfiguration, RedisCredentials
 from glide.constants import OK
 from glide.redis_client import RedisClient, RedisClusterClient, TRedisClient
 from glide.routes import (
@@ -247,6 +247,18 @@ class TestRedisClients:
         client_info = await redis_client.custom_command(["CLIENT", "INFO"])
         assert "name=TEST_CLIENT_NAME" in client_info
 
+    async def test_connect_with_uri(self, request):
+        scheme = "rediss" if request.config.getoption("--tls") else "redis"
+        address = pytest.standalone_cluster.nodes_addr[0]
+        config = RedisClientConfiguration.from_uri(
+            f"{scheme}://{address.host}:{address.port}/4?client_name=URI_CLIENT"
+        )
+        redis_client = await RedisClient.create(config)
+        client_info = await redis_client.custom_command(["CLIENT", "INFO"])
+        assert "db=4" in client_info
+        assert "name=URI_CLIENT" in client_info
+        await redis_client.close()
+
     @pytest.mark.parametrize("cluster_mode", [True, False])
     async def test_closed_client_raises_error(self, redis_client: TRedisClient):
         await redis_client.close()
@@ -283,6 +295,22 @@ class TestCommands:
 
         assert int(result["proto"]) == 2
 
+    @pytest.mark.parametrize("cluster_mode", [True, False])
+    async def test_return_bytes(self, cluster_mode, request):
+        redis_client = await create_client(request, cluster_mode, return_bytes=True)
+        key = get_random_string(10)
+        assert await redis_client.set(key, "foo") == OK
+        assert await redis_client.get(key) == b"foo"
+        list_key = get_random_string(10)
+        assert await redis_client.rpush(list_key, ["a", "b"]) == 2
+        assert await redis_client.lrange(list_key, 0, -1) == [b"a", b"b"]
+        # Lua escapes create a bulk string that isn't valid UTF-8.
+        assert (
+            await redis_client.custom_command(["EVAL", "return '\\255\\0'", "0"])
+            == b"\xff\x00"
+        )
+        await redis_client.close()
+
     @pytest.mark.parametrize("cluster_mode", [True, False])
     async def test_conditional_set(self, redis_client: TRedisClient):
         key = get_random_string(10)
@@ -1,7 +1,7 @@
 use glide_core::start_socket_listener;
 use pyo3::exceptions::PyUnicodeDecodeError;
 use pyo3::prelude::*;
-use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PySet};
+use pyo3::types::{PyBool, PyBytes, PyDict, PyFloat, PyList, PySet};
 use pyo3::Python;
 
 use redis::Value;
@@ -66,6 +66,7 @@ fn glide(_py: Python, m: &PyModule) -> PyResult<()> {
     fn iter_to_value<TIterator>(
         py: Python,
         iter: impl IntoIterator<Item = Value, IntoIter = TIterator>,
+        return_bytes: bool,
     ) -> PyResult<Vec<PyObject>>
     where
         TIterator: ExactSizeIterator<Item = Value>,
@@ -74,42 +75,47 @@ fn glide(_py: Python, m: &PyModule) -> PyResult<()> {
         let len = iterator.len();
 
         iterator.try_fold(Vec::with_capacity(len), |mut acc, val| {
-            acc.push(redis_value_to_py(py, val)?);
+            acc.push(redis_value_to_py(py, val, return_bytes)?);
             Ok(acc)
         })
     }
 
-    fn redis_value_to_py(py: Python, val: Value) -> PyResult<PyObject> {
+    /// Bulk strings are returned as `bytes` if `return_bytes` is set, and are decoded as UTF-8 otherwise.
+    fn redis_value_to_py(py: Python, val: Value, return_bytes: bool) -> PyResult<PyObject> {
         match val {
             Value::Nil => Ok(py.None()),
             Value::SimpleString(str) => Ok(str.into_py(py)),
             Value::Okay => Ok("OK".into_py(py)),
             Value::Int(num) => Ok(num.into_py(py)),
+            Value::BulkString(data) if return_bytes => Ok(PyBytes::new(py, &data).into_py(py)),
             Value::BulkString(data) => match std::str::from_utf8(data.as_ref()) {
                 Ok(val) => Ok(val.into_py(py)),
                 Err(_err) => Err(PyUnicodeDecodeError::new_err(data)),
             },
             Value::Array(bulk) => {
-                let elements: &PyList = PyList::new(py, iter_to_value(py, bulk)?);
+                let elements: &PyList = PyList::new(py, iter_to_value(py, bulk, return_bytes)?);
                 Ok(elements.into_py(py))
             }
             Value::Map(map) => {
                 let dict = PyDict::new(py);
                 for (key, value) in map {
-                    dict.set_item(redis_value_to_py(py, key)?, redis_value_to_py(py, value)?)?;
+                    dict.set_item(
+                        redis_value_to_py(py, key, return_bytes)?,
+                        redis_value_to_py(py, value, return_bytes)?,
+                    )?;
                 }
                 Ok(dict.into_py(py))
             }
             Value::Attribute { data, attributes } => {
                 let dict = PyDict::new(py);
-                let value = redis_value_to_py(py, *data)?;
-                let attributes = redis_value_to_py(py, Value::Map(attributes))?;
+                let value = redis_value_to_py(py, *data, return_bytes)?;
+                let attributes = redis_value_to_py(py, Value::Map(attributes), return_bytes)?;
                 dict.set_item("value", value)?;
                 dict.set_item("attributes", attributes)?;
                 Ok(dict.into_py(py))
             }
             Value::Set(set) => {
-                let set = iter_to_value(py, set)?;
+                let set = iter_to_value(py, set, return_bytes)?;
                 let set = PySet::new(py, set.iter())?;
                 Ok(set.into_py(py))
             }
@@ -117,14 +123,21 @@ fn glide(_py: Python, m: &PyModule) -> PyResult<()> {
             Value::Boolean(boolean) => Ok(PyBool::new(py, boolean).into_py(py)),
             Value::VerbatimString { format: _, text } => Ok(text.into_py(py)),
             Value::BigNumber(bigint) => Ok(bigint.into_py(py)),
-            Value::Push { kind: _, data: _ } => todo!(),
+            Value::Push { kind, data } => {
+                let dict = PyDict::new(py);
+                dict.set_item("kind", kind.to_string())?;
+                let values: &PyList = PyList::new(py, iter_to_value(py, data, return_bytes)?);
+                dict.set_item("values", values)?;
+                Ok(dict.into_py(py))
+            }
         }
     }
 
     #[pyfn(m)]
-    pub fn value_from_pointer(py: Python, pointer: u64) -> PyResult<PyObject> {
+    #[pyo3(signature = (pointer, return_bytes = false))]
+    pub fn value_from_pointer(py: Python, pointer: u64, return_bytes: bool) -> PyResult<PyObject> {
         let value = unsafe { Box::from_raw(pointer as *mut Value) };
-        redis_value_to_py(py, *value)
+        redis_value_to_py(py, *value, return_bytes)
     }
 
     #[pyfn(m)]